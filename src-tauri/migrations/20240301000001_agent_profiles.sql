@@ -0,0 +1,35 @@
+-- Agent profiles: several agents sharing one install
+
+CREATE TABLE agents (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    phone TEXT NOT NULL DEFAULT '',
+    email TEXT NOT NULL DEFAULT '',
+    brokerage_name TEXT NOT NULL DEFAULT '',
+    license_number TEXT,
+    signature TEXT,
+    default_brand_voice_id TEXT REFERENCES brand_voices(id) ON DELETE SET NULL,
+    default_style TEXT CHECK(default_style IN ('luxury', 'family', 'investment', 'first_time')),
+    default_tone TEXT CHECK(default_tone IN ('professional', 'warm', 'exciting')),
+    default_length TEXT CHECK(default_length IN ('short', 'medium', 'long')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+ALTER TABLE properties ADD COLUMN agent_id TEXT REFERENCES agents(id) ON DELETE SET NULL;
+CREATE INDEX idx_properties_agent ON properties(agent_id);
+
+-- Carry the single-agent settings over as the first profile and assign it
+-- to existing properties. The settings keys stay as the fallback for
+-- properties without an assigned agent.
+INSERT INTO agents (id, name, phone, email, brokerage_name)
+SELECT
+    lower(hex(randomblob(16))),
+    (SELECT value FROM settings WHERE key = 'agent_name'),
+    COALESCE((SELECT value FROM settings WHERE key = 'agent_phone'), ''),
+    COALESCE((SELECT value FROM settings WHERE key = 'agent_email'), ''),
+    COALESCE((SELECT value FROM settings WHERE key = 'brokerage_name'), '')
+WHERE COALESCE((SELECT value FROM settings WHERE key = 'agent_name'), '') != '';
+
+UPDATE properties SET agent_id = (SELECT id FROM agents LIMIT 1)
+WHERE agent_id IS NULL;
@@ -0,0 +1,72 @@
+-- Full-text search over properties and their generated listings
+
+CREATE VIRTUAL TABLE property_search USING fts5(
+    property_id UNINDEXED,
+    address,
+    city,
+    neighborhood,
+    features,
+    agent_notes,
+    listing_content,
+    tokenize = 'porter unicode61'
+);
+
+INSERT INTO property_search (property_id, address, city, neighborhood, features, agent_notes, listing_content)
+SELECT
+    p.id,
+    p.address,
+    p.city,
+    COALESCE(p.neighborhood, ''),
+    p.key_features || ' ' || COALESCE(p.neighborhood_highlights, '') || ' ' || COALESCE(p.nearby_amenities, ''),
+    COALESCE(p.agent_notes, ''),
+    COALESCE((SELECT group_concat(l.content, ' ') FROM listings l WHERE l.property_id = p.id), '')
+FROM properties p;
+
+CREATE TRIGGER properties_search_insert AFTER INSERT ON properties BEGIN
+    INSERT INTO property_search (property_id, address, city, neighborhood, features, agent_notes, listing_content)
+    VALUES (
+        new.id,
+        new.address,
+        new.city,
+        COALESCE(new.neighborhood, ''),
+        new.key_features || ' ' || COALESCE(new.neighborhood_highlights, '') || ' ' || COALESCE(new.nearby_amenities, ''),
+        COALESCE(new.agent_notes, ''),
+        ''
+    );
+END;
+
+CREATE TRIGGER properties_search_update AFTER UPDATE ON properties BEGIN
+    UPDATE property_search SET
+        address = new.address,
+        city = new.city,
+        neighborhood = COALESCE(new.neighborhood, ''),
+        features = new.key_features || ' ' || COALESCE(new.neighborhood_highlights, '') || ' ' || COALESCE(new.nearby_amenities, ''),
+        agent_notes = COALESCE(new.agent_notes, '')
+    WHERE property_id = old.id;
+END;
+
+CREATE TRIGGER properties_search_delete AFTER DELETE ON properties BEGIN
+    DELETE FROM property_search WHERE property_id = old.id;
+END;
+
+CREATE TRIGGER listings_search_insert AFTER INSERT ON listings BEGIN
+    UPDATE property_search
+    SET listing_content = COALESCE((SELECT group_concat(content, ' ') FROM listings WHERE property_id = new.property_id), '')
+    WHERE property_id = new.property_id;
+END;
+
+CREATE TRIGGER listings_search_update AFTER UPDATE OF content ON listings BEGIN
+    UPDATE property_search
+    SET listing_content = COALESCE((SELECT group_concat(content, ' ') FROM listings WHERE property_id = new.property_id), '')
+    WHERE property_id = new.property_id;
+END;
+
+CREATE TRIGGER listings_search_delete AFTER DELETE ON listings BEGIN
+    UPDATE property_search
+    SET listing_content = COALESCE((SELECT group_concat(content, ' ') FROM listings WHERE property_id = old.property_id), '')
+    WHERE property_id = old.property_id;
+END;
+
+CREATE INDEX idx_properties_created ON properties(created_at DESC);
+CREATE INDEX idx_properties_price ON properties(price);
+CREATE INDEX idx_properties_city ON properties(city COLLATE NOCASE);
@@ -0,0 +1,32 @@
+-- Property lifecycle status and listing dates
+
+ALTER TABLE properties ADD COLUMN status TEXT NOT NULL DEFAULT 'active' CHECK(status IN (
+    'draft', 'coming_soon', 'active', 'pending', 'sold', 'withdrawn', 'expired'
+));
+ALTER TABLE properties ADD COLUMN listed_at TEXT;
+ALTER TABLE properties ADD COLUMN pending_at TEXT;
+ALTER TABLE properties ADD COLUMN sold_at TEXT;
+ALTER TABLE properties ADD COLUMN sold_price INTEGER CHECK(sold_price > 0);
+-- Set when a listing is withdrawn or expires; stops the days-on-market clock
+ALTER TABLE properties ADD COLUMN off_market_at TEXT;
+CREATE INDEX idx_properties_status ON properties(status);
+
+CREATE TABLE property_status_history (
+    id TEXT PRIMARY KEY,
+    property_id TEXT NOT NULL REFERENCES properties(id) ON DELETE CASCADE,
+    status TEXT NOT NULL CHECK(status IN (
+        'draft', 'coming_soon', 'active', 'pending', 'sold', 'withdrawn', 'expired'
+    )),
+    -- Asking price at the time of the change (sold price for 'sold')
+    price INTEGER NOT NULL,
+    note TEXT,
+    changed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX idx_status_history_property ON property_status_history(property_id, changed_at);
+
+-- Existing properties were all being marketed, so treat them as listed on
+-- the day they were entered
+UPDATE properties SET listed_at = created_at;
+
+INSERT INTO property_status_history (id, property_id, status, price, changed_at)
+SELECT lower(hex(randomblob(16))), id, 'active', price, created_at FROM properties;
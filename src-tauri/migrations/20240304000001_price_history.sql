@@ -0,0 +1,68 @@
+-- Price history and price-improvement marketing
+
+CREATE TABLE price_history (
+    id TEXT PRIMARY KEY,
+    property_id TEXT NOT NULL REFERENCES properties(id) ON DELETE CASCADE,
+    old_price INTEGER NOT NULL,
+    new_price INTEGER NOT NULL,
+    changed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX idx_price_history_property ON price_history(property_id, changed_at);
+
+-- Every write path that changes the asking price gets recorded, not just
+-- update_property
+CREATE TRIGGER properties_price_history AFTER UPDATE OF price ON properties
+WHEN new.price != old.price
+BEGIN
+    INSERT INTO price_history (id, property_id, old_price, new_price)
+    VALUES (lower(hex(randomblob(16))), new.id, old.price, new.price);
+END;
+
+-- SQLite can't alter a CHECK constraint, so rebuild listings with the new
+-- generation types and recreate its index and search triggers
+CREATE TABLE listings_new (
+    id TEXT PRIMARY KEY,
+    property_id TEXT NOT NULL REFERENCES properties(id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    generation_type TEXT NOT NULL CHECK(generation_type IN (
+        'listing', 'social_instagram', 'social_facebook', 'social_linkedin',
+        'email_buyer', 'email_seller', 'email_open_house',
+        'email_price_improvement', 'social_price_improvement'
+    )),
+    style TEXT CHECK(style IN ('luxury', 'family', 'investment', 'first_time')),
+    tone TEXT CHECK(tone IN ('professional', 'warm', 'exciting')),
+    length TEXT CHECK(length IN ('short', 'medium', 'long')),
+    seo_keywords TEXT DEFAULT '[]',
+    brand_voice_id TEXT REFERENCES brand_voices(id) ON DELETE SET NULL,
+    tokens_used INTEGER NOT NULL,
+    generation_cost_cents INTEGER NOT NULL,
+    is_favorite INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT INTO listings_new (id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents, is_favorite, created_at)
+SELECT id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents, is_favorite, created_at
+FROM listings;
+
+DROP TABLE listings;
+ALTER TABLE listings_new RENAME TO listings;
+
+CREATE INDEX idx_listings_property ON listings(property_id, created_at DESC);
+
+CREATE TRIGGER listings_search_insert AFTER INSERT ON listings BEGIN
+    UPDATE property_search
+    SET listing_content = COALESCE((SELECT group_concat(content, ' ') FROM listings WHERE property_id = new.property_id), '')
+    WHERE property_id = new.property_id;
+END;
+
+CREATE TRIGGER listings_search_update AFTER UPDATE OF content ON listings BEGIN
+    UPDATE property_search
+    SET listing_content = COALESCE((SELECT group_concat(content, ' ') FROM listings WHERE property_id = new.property_id), '')
+    WHERE property_id = new.property_id;
+END;
+
+CREATE TRIGGER listings_search_delete AFTER DELETE ON listings BEGIN
+    UPDATE property_search
+    SET listing_content = COALESCE((SELECT group_concat(content, ' ') FROM listings WHERE property_id = old.property_id), '')
+    WHERE property_id = old.property_id;
+END;
@@ -0,0 +1,35 @@
+-- Soft delete: deleted rows stay in the trash until restored or purged
+
+ALTER TABLE properties ADD COLUMN deleted_at TEXT;
+ALTER TABLE listings ADD COLUMN deleted_at TEXT;
+ALTER TABLE photos ADD COLUMN deleted_at TEXT;
+
+CREATE INDEX idx_properties_deleted ON properties(deleted_at);
+CREATE INDEX idx_listings_deleted ON listings(deleted_at);
+CREATE INDEX idx_photos_deleted ON photos(deleted_at);
+
+-- Days a deleted item stays in the trash before it is purged for good
+INSERT INTO settings (key, value) VALUES ('trash_retention_days', '30');
+
+-- Trashed listings no longer contribute to property search
+DROP TRIGGER listings_search_insert;
+DROP TRIGGER listings_search_update;
+DROP TRIGGER listings_search_delete;
+
+CREATE TRIGGER listings_search_insert AFTER INSERT ON listings BEGIN
+    UPDATE property_search
+    SET listing_content = COALESCE((SELECT group_concat(content, ' ') FROM listings WHERE property_id = new.property_id AND deleted_at IS NULL), '')
+    WHERE property_id = new.property_id;
+END;
+
+CREATE TRIGGER listings_search_update AFTER UPDATE OF content, deleted_at ON listings BEGIN
+    UPDATE property_search
+    SET listing_content = COALESCE((SELECT group_concat(content, ' ') FROM listings WHERE property_id = new.property_id AND deleted_at IS NULL), '')
+    WHERE property_id = new.property_id;
+END;
+
+CREATE TRIGGER listings_search_delete AFTER DELETE ON listings BEGIN
+    UPDATE property_search
+    SET listing_content = COALESCE((SELECT group_concat(content, ' ') FROM listings WHERE property_id = old.property_id AND deleted_at IS NULL), '')
+    WHERE property_id = old.property_id;
+END;
@@ -0,0 +1,5 @@
+-- Scheduled backups into the app data directory
+INSERT INTO settings (key, value) VALUES ('auto_backup_enabled', 'false');
+INSERT INTO settings (key, value) VALUES ('auto_backup_interval_hours', '24');
+INSERT INTO settings (key, value) VALUES ('auto_backup_keep', '7');
+INSERT INTO settings (key, value) VALUES ('last_auto_backup_at', '');
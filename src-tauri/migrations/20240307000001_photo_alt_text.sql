@@ -0,0 +1,3 @@
+-- Accessibility alt text and an optional room tag for each photo
+ALTER TABLE photos ADD COLUMN alt_text TEXT;
+ALTER TABLE photos ADD COLUMN room TEXT;
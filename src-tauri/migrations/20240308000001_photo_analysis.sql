@@ -0,0 +1,4 @@
+-- Results of vision analysis for each photo
+ALTER TABLE photos ADD COLUMN detected_features TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE photos ADD COLUMN quality_score INTEGER;
+ALTER TABLE photos ADD COLUMN analyzed_at TEXT;
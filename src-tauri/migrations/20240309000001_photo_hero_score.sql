@@ -0,0 +1,2 @@
+-- Cached sharpness/exposure/resolution score used to pick the hero image
+ALTER TABLE photos ADD COLUMN hero_score REAL;
@@ -0,0 +1,4 @@
+-- SHA-256 of the imported file, so the same picture isn't imported twice
+ALTER TABLE photos ADD COLUMN content_hash TEXT;
+
+CREATE INDEX idx_photos_content_hash ON photos(property_id, content_hash);
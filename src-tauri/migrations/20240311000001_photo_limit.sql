@@ -0,0 +1,2 @@
+-- Photos allowed per property; luxury listings often need more than 20
+INSERT INTO settings (key, value) VALUES ('max_photos_per_property', '20');
@@ -0,0 +1,17 @@
+-- Every change to a voice's extracted style, so edits and retraining can be
+-- reviewed and rolled back
+CREATE TABLE brand_voice_versions (
+    id TEXT PRIMARY KEY,
+    brand_voice_id TEXT NOT NULL REFERENCES brand_voices(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    extracted_style TEXT NOT NULL,
+    sample_count INTEGER NOT NULL,
+    change_source TEXT NOT NULL CHECK(change_source IN ('extracted', 'edited', 'retrained', 'restored')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (brand_voice_id, version)
+);
+
+-- Existing voices start their history at version 1
+INSERT INTO brand_voice_versions (id, brand_voice_id, version, extracted_style, sample_count, change_source, created_at)
+SELECT lower(hex(randomblob(16))), id, 1, extracted_style, sample_count, 'extracted', created_at
+FROM brand_voices;
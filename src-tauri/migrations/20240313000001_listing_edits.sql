@@ -0,0 +1,2 @@
+-- When the agent last rewrote a listing's generated copy by hand
+ALTER TABLE listings ADD COLUMN edited_at TEXT;
@@ -0,0 +1,4 @@
+-- How closely a listing matches a brand voice, 0-100, and the breakdown
+-- behind it as JSON
+ALTER TABLE listings ADD COLUMN voice_score REAL;
+ALTER TABLE listings ADD COLUMN voice_score_details TEXT;
@@ -0,0 +1,3 @@
+-- Listings generated together as side-by-side variants share a group id
+ALTER TABLE listings ADD COLUMN variant_group_id TEXT;
+CREATE INDEX idx_listings_variant_group ON listings(variant_group_id);
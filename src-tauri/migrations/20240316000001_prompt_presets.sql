@@ -0,0 +1,73 @@
+-- User-defined styles, tones, lengths, social platforms and email templates.
+-- `instructions` may reference property and agent fields as {{variables}}.
+CREATE TABLE prompt_presets (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL CHECK(kind IN ('style', 'tone', 'length', 'platform', 'email_template')),
+    key TEXT NOT NULL,
+    name TEXT NOT NULL,
+    instructions TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (kind, key)
+);
+
+-- Custom styles, tones and lengths are stored by key, so listings lose their
+-- fixed CHECK lists; the app validates keys against built-ins and presets.
+-- Copy from a custom platform or email template is saved as social_custom or
+-- email_custom with the preset it used. SQLite can't alter a CHECK
+-- constraint, so rebuild listings and recreate its indexes and triggers.
+CREATE TABLE listings_new (
+    id TEXT PRIMARY KEY,
+    property_id TEXT NOT NULL REFERENCES properties(id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    generation_type TEXT NOT NULL CHECK(generation_type IN (
+        'listing', 'social_instagram', 'social_facebook', 'social_linkedin',
+        'email_buyer', 'email_seller', 'email_open_house',
+        'email_price_improvement', 'social_price_improvement',
+        'social_custom', 'email_custom'
+    )),
+    style TEXT,
+    tone TEXT,
+    length TEXT,
+    seo_keywords TEXT DEFAULT '[]',
+    brand_voice_id TEXT REFERENCES brand_voices(id) ON DELETE SET NULL,
+    tokens_used INTEGER NOT NULL,
+    generation_cost_cents INTEGER NOT NULL,
+    is_favorite INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    deleted_at TEXT,
+    edited_at TEXT,
+    voice_score REAL,
+    voice_score_details TEXT,
+    variant_group_id TEXT,
+    prompt_preset_id TEXT REFERENCES prompt_presets(id) ON DELETE SET NULL
+);
+
+INSERT INTO listings_new (id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents, is_favorite, created_at, deleted_at, edited_at, voice_score, voice_score_details, variant_group_id)
+SELECT id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents, is_favorite, created_at, deleted_at, edited_at, voice_score, voice_score_details, variant_group_id
+FROM listings;
+
+DROP TABLE listings;
+ALTER TABLE listings_new RENAME TO listings;
+
+CREATE INDEX idx_listings_property ON listings(property_id, created_at DESC);
+CREATE INDEX idx_listings_deleted ON listings(deleted_at);
+CREATE INDEX idx_listings_variant_group ON listings(variant_group_id);
+
+CREATE TRIGGER listings_search_insert AFTER INSERT ON listings BEGIN
+    UPDATE property_search
+    SET listing_content = COALESCE((SELECT group_concat(content, ' ') FROM listings WHERE property_id = new.property_id AND deleted_at IS NULL), '')
+    WHERE property_id = new.property_id;
+END;
+
+CREATE TRIGGER listings_search_update AFTER UPDATE OF content, deleted_at ON listings BEGIN
+    UPDATE property_search
+    SET listing_content = COALESCE((SELECT group_concat(content, ' ') FROM listings WHERE property_id = new.property_id AND deleted_at IS NULL), '')
+    WHERE property_id = new.property_id;
+END;
+
+CREATE TRIGGER listings_search_delete AFTER DELETE ON listings BEGIN
+    UPDATE property_search
+    SET listing_content = COALESCE((SELECT group_concat(content, ' ') FROM listings WHERE property_id = old.property_id AND deleted_at IS NULL), '')
+    WHERE property_id = old.property_id;
+END;
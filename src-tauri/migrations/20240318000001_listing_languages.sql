@@ -0,0 +1,6 @@
+-- Language of each listing's copy. Translations are linked to the listing
+-- they were translated from and keep their copy if it is purged.
+ALTER TABLE listings ADD COLUMN language TEXT NOT NULL DEFAULT 'en';
+ALTER TABLE listings ADD COLUMN source_listing_id TEXT REFERENCES listings(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_listings_source ON listings(source_listing_id);
@@ -0,0 +1,4 @@
+-- Claims in a listing's copy checked against its property: how many the
+-- property record doesn't support, and every claim found as JSON
+ALTER TABLE listings ADD COLUMN unsupported_claims INTEGER;
+ALTER TABLE listings ADD COLUMN fact_check_details TEXT;
@@ -0,0 +1,9 @@
+-- The last style update proposed for each voice and what it was trained on,
+-- so periodic checks only retrain once new feedback arrives
+CREATE TABLE brand_voice_suggestions (
+    brand_voice_id TEXT PRIMARY KEY REFERENCES brand_voices(id) ON DELETE CASCADE,
+    base_style TEXT NOT NULL,
+    samples TEXT NOT NULL,
+    proposed_style TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,3 @@
+-- The fact check only reads English; translations checked before that was
+-- enforced were stored as having no unsupported claims
+UPDATE listings SET unsupported_claims = NULL, fact_check_details = NULL WHERE language != 'en';
@@ -1,7 +1,15 @@
-use crate::ai::client::ClaudeClient;
-use crate::ai::prompts::{build_voice_extraction_prompt, MAX_TOKENS_BRAND_VOICE};
+use crate::ai::client::{strip_code_fences, ClaudeClient};
+use crate::ai::prompts::{
+    build_voice_extraction_prompt, build_voice_merge_prompt, build_voice_schema_retry,
+    MAX_TOKENS_BRAND_VOICE,
+};
+use crate::db::brand_voice::VoiceProfile;
 use crate::error::AppError;
 
+/// Requests per extraction, including retries after a response that
+/// doesn't match the voice profile schema
+const MAX_SCHEMA_ATTEMPTS: u32 = 2;
+
 /// Extract brand voice style from sample listing descriptions
 pub async fn extract_voice(
     client: &ClaudeClient,
@@ -14,36 +22,72 @@ pub async fn extract_voice(
     }
 
     let (system, user) = build_voice_extraction_prompt(sample_listings);
+    request_profile(client, &system, &user).await
+}
+
+/// Retrain an existing voice profile on additional sample listings
+pub async fn merge_voice(
+    client: &ClaudeClient,
+    existing_style: &str,
+    new_listings: &[String],
+) -> Result<String, AppError> {
+    if new_listings.is_empty() {
+        return Err(AppError::Validation(
+            "Add at least 1 sample listing to retrain the voice profile.".to_string(),
+        ));
+    }
 
-    let (response, _input_tokens, _output_tokens) = client
-        .send_message(&system, &user, MAX_TOKENS_BRAND_VOICE)
-        .await?;
+    let (system, user) = build_voice_merge_prompt(existing_style, new_listings);
+    request_profile(client, &system, &user).await
+}
+
+/// Send the prompt and parse a `VoiceProfile` from the response, asking
+/// again with the validation error when it doesn't match the schema.
+/// Returns the normalized profile as JSON.
+async fn request_profile(
+    client: &ClaudeClient,
+    system: &str,
+    user: &str,
+) -> Result<String, AppError> {
+    let mut prompt = user.to_string();
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let (response, _input_tokens, _output_tokens) = client
+            .send_message(system, &prompt, MAX_TOKENS_BRAND_VOICE)
+            .await?;
+
+        match parse_profile(&response) {
+            Ok(profile) => return profile.to_json(),
+            Err(problem) if attempt >= MAX_SCHEMA_ATTEMPTS => {
+                return Err(AppError::Api(format!(
+                    "Brand voice extraction didn't match the expected format: {}",
+                    problem
+                )))
+            }
+            Err(problem) => {
+                eprintln!("Voice profile rejected, retrying: {}", problem);
+                prompt = build_voice_schema_retry(user, &response, &problem);
+            }
+        }
+    }
+}
 
-    // Validate the response is valid JSON
-    let trimmed = response.trim();
+/// The profile in a response, or what's wrong with it
+fn parse_profile(response: &str) -> Result<VoiceProfile, String> {
     // Strip markdown code fences if Claude wraps in ```json
-    let json_str = if trimmed.starts_with("```") {
-        let inner = trimmed
-            .strip_prefix("```json")
-            .or_else(|| trimmed.strip_prefix("```"))
-            .unwrap_or(trimmed);
-        inner
-            .strip_suffix("```")
-            .unwrap_or(inner)
-            .trim()
-    } else {
-        trimmed
-    };
-
-    // Validate it parses as JSON
-    let _: serde_json::Value = serde_json::from_str(json_str).map_err(|e| {
-        AppError::Api(format!(
-            "Failed to parse brand voice extraction as JSON: {}",
-            e
-        ))
-    })?;
-
-    Ok(json_str.to_string())
+    let json_str = strip_code_fences(response);
+
+    let mut profile: VoiceProfile =
+        serde_json::from_str(json_str).map_err(|e| format!("invalid JSON ({})", e))?;
+    profile.normalize();
+
+    let missing = profile.missing_fields();
+    if !missing.is_empty() {
+        return Err(format!("missing or empty fields: {}", missing.join(", ")));
+    }
+    Ok(profile)
 }
 
 #[cfg(test)]
@@ -62,4 +106,57 @@ mod tests {
         let err = result.unwrap_err().to_string();
         assert!(err.contains("At least 2"));
     }
+
+    const PROFILE: &str = r#"{"tone":"Warm","vocabulary":["sun-drenched"],"sentence_patterns":"Short","themes":["light"],"signature_phrases":[],"avoids":["cozy"],"formatting":"Two paragraphs"}"#;
+
+    #[test]
+    fn test_parse_profile() {
+        let fenced = format!("```json\n{}\n```", PROFILE);
+        assert_eq!(parse_profile(&fenced).unwrap().avoids, vec!["cozy"]);
+
+        let problem = parse_profile(r#"{"tone":"Warm"}"#).unwrap_err();
+        assert!(problem.contains("vocabulary, sentence_patterns, themes"));
+        assert!(parse_profile("[\"tone\"]").is_err());
+    }
+
+    fn reply(text: &str) -> String {
+        serde_json::json!({
+            "content": [{"type": "text", "text": text}],
+            "usage": {"input_tokens": 100, "output_tokens": 50}
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_extract_retries_on_schema_mismatch() {
+        let mut server = mockito::Server::new_async().await;
+        let first = server
+            .mock("POST", "/v1/messages")
+            .match_body(mockito::Matcher::Regex("LISTING 1".to_string()))
+            .with_status(200)
+            .with_body(reply(r#"{"tone":"Warm"}"#))
+            .expect(1)
+            .create_async()
+            .await;
+        // The retry prompt also matches the first mock, which has had its hit
+        let retry = server
+            .mock("POST", "/v1/messages")
+            .match_body(mockito::Matcher::Regex("was rejected: missing".to_string()))
+            .with_status(200)
+            .with_body(reply(PROFILE))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = ClaudeClient::new("test-key".to_string(), "test-model".to_string())
+            .with_base_url(server.url());
+        let style = extract_voice(&client, &["one".to_string(), "two".to_string()])
+            .await
+            .unwrap();
+
+        first.assert_async().await;
+        retry.assert_async().await;
+        let profile = VoiceProfile::from_json(&style).unwrap();
+        assert_eq!(profile.formatting, "Two paragraphs");
+    }
 }
@@ -6,12 +6,17 @@ use tokio::time::{sleep, Duration};
 
 use crate::error::AppError;
 
+use super::fact_check::FactCheck;
+
 /// Maximum number of retries for API requests
 const MAX_RETRIES: u32 = 3;
 
 /// Initial backoff delay in milliseconds
 const INITIAL_BACKOFF_MS: u64 = 1000;
 
+/// Default Anthropic API endpoint; tests point the client at a mock server
+pub const API_BASE_URL: &str = "https://api.anthropic.com";
+
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase", tag = "event", content = "data")]
 pub enum StreamEvent {
@@ -30,12 +35,26 @@ pub enum StreamEvent {
     Error {
         message: String,
     },
+    /// Sent once the copy is saved, with its claims checked against the
+    /// property, so the editor can highlight unsupported ones
+    FactChecked {
+        listing_id: String,
+        fact_check: FactCheck,
+    },
 }
 
 pub struct ClaudeClient {
     client: Client,
     api_key: String,
     model: String,
+    base_url: String,
+}
+
+/// A base64-encoded image sent as an image content block
+pub struct ImageInput {
+    /// e.g. `image/jpeg`
+    pub media_type: String,
+    pub data: String,
 }
 
 #[derive(Serialize)]
@@ -50,7 +69,29 @@ struct MessageRequest<'a> {
 #[derive(Serialize)]
 struct Message<'a> {
     role: &'a str,
-    content: &'a str,
+    content: MessageContent<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum MessageContent<'a> {
+    Text(&'a str),
+    Blocks(Vec<ContentBlock<'a>>),
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock<'a> {
+    Text { text: &'a str },
+    Image { source: ImageSource<'a> },
+}
+
+#[derive(Serialize)]
+struct ImageSource<'a> {
+    #[serde(rename = "type")]
+    source_type: &'a str,
+    media_type: &'a str,
+    data: &'a str,
 }
 
 // SSE event parsing types
@@ -108,15 +149,63 @@ impl ClaudeClient {
             client: Client::new(),
             api_key,
             model,
+            base_url: API_BASE_URL.to_string(),
         }
     }
 
+    /// Send requests to a different API host, e.g. a mock server in tests
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    fn messages_url(&self) -> String {
+        format!("{}/v1/messages", self.base_url)
+    }
+
     /// Send a non-streaming request and return the full text response (with retry logic)
     pub async fn send_message(
         &self,
         system: &str,
         user_content: &str,
         max_tokens: u32,
+    ) -> Result<(String, u32, u32), AppError> {
+        self.send_with_retry(system, || MessageContent::Text(user_content), max_tokens)
+            .await
+    }
+
+    /// Send a non-streaming request with images ahead of the text, as image
+    /// content blocks (with retry logic)
+    pub async fn send_message_with_images(
+        &self,
+        system: &str,
+        images: &[ImageInput],
+        user_content: &str,
+        max_tokens: u32,
+    ) -> Result<(String, u32, u32), AppError> {
+        let content = || {
+            let mut blocks: Vec<ContentBlock> = images
+                .iter()
+                .map(|image| ContentBlock::Image {
+                    source: ImageSource {
+                        source_type: "base64",
+                        media_type: &image.media_type,
+                        data: &image.data,
+                    },
+                })
+                .collect();
+            blocks.push(ContentBlock::Text { text: user_content });
+            MessageContent::Blocks(blocks)
+        };
+
+        self.send_with_retry(system, content, max_tokens).await
+    }
+
+    async fn send_with_retry<'a>(
+        &self,
+        system: &str,
+        content: impl Fn() -> MessageContent<'a>,
+        max_tokens: u32,
     ) -> Result<(String, u32, u32), AppError> {
         let mut attempt = 0;
 
@@ -124,7 +213,7 @@ impl ClaudeClient {
             attempt += 1;
 
             let result = self
-                .send_message_attempt(system, user_content, max_tokens)
+                .send_message_attempt(system, content(), max_tokens)
                 .await;
 
             match result {
@@ -147,7 +236,7 @@ impl ClaudeClient {
     async fn send_message_attempt(
         &self,
         system: &str,
-        user_content: &str,
+        content: MessageContent<'_>,
         max_tokens: u32,
     ) -> Result<(String, u32, u32), AppError> {
         let body = MessageRequest {
@@ -157,13 +246,13 @@ impl ClaudeClient {
             system,
             messages: vec![Message {
                 role: "user",
-                content: user_content,
+                content,
             }],
         };
 
         let response = self
             .client
-            .post("https://api.anthropic.com/v1/messages")
+            .post(self.messages_url())
             .header("content-type", "application/json")
             .header("anthropic-version", "2023-06-01")
             .header("x-api-key", &self.api_key)
@@ -211,6 +300,21 @@ impl ClaudeClient {
         user_content: &str,
         max_tokens: u32,
         channel: &Channel<StreamEvent>,
+    ) -> Result<(String, u32, u32), AppError> {
+        self.stream_message_with(system, user_content, max_tokens, |event| {
+            let _ = channel.send(event);
+        })
+        .await
+    }
+
+    /// Like `stream_message`, handing each event to `on_event` instead of a
+    /// channel so several streams can share one
+    pub async fn stream_message_with(
+        &self,
+        system: &str,
+        user_content: &str,
+        max_tokens: u32,
+        on_event: impl Fn(StreamEvent),
     ) -> Result<(String, u32, u32), AppError> {
         let body = MessageRequest {
             model: &self.model,
@@ -219,13 +323,13 @@ impl ClaudeClient {
             system,
             messages: vec![Message {
                 role: "user",
-                content: user_content,
+                content: MessageContent::Text(user_content),
             }],
         };
 
         let response = self
             .client
-            .post("https://api.anthropic.com/v1/messages")
+            .post(self.messages_url())
             .header("content-type", "application/json")
             .header("anthropic-version", "2023-06-01")
             .header("x-api-key", &self.api_key)
@@ -270,7 +374,7 @@ impl ClaudeClient {
                                 }
                             }
                         }
-                        let _ = channel.send(StreamEvent::Started {
+                        on_event(StreamEvent::Started {
                             estimated_tokens: input_tokens,
                         });
                     }
@@ -280,9 +384,7 @@ impl ClaudeClient {
                                 if delta.delta.delta_type == "text_delta" {
                                     if let Some(text) = delta.delta.text {
                                         full_text.push_str(&text);
-                                        let _ = channel.send(StreamEvent::Delta {
-                                            text,
-                                        });
+                                        on_event(StreamEvent::Delta { text });
                                     }
                                 }
                             }
@@ -300,7 +402,7 @@ impl ClaudeClient {
                     "error" => {
                         if let Some(data) = &data {
                             if let Ok(err) = serde_json::from_str::<SseError>(data) {
-                                let _ = channel.send(StreamEvent::Error {
+                                on_event(StreamEvent::Error {
                                     message: err.error.message.clone(),
                                 });
                                 return Err(AppError::Api(err.error.message));
@@ -332,6 +434,20 @@ pub fn calculate_cost_cents(input_tokens: u32, output_tokens: u32) -> u32 {
     ((input_tokens as u64 * 3 + output_tokens as u64 * 15) / 10000) as u32
 }
 
+/// Strip the markdown code fences Claude sometimes wraps JSON responses in
+pub fn strip_code_fences(text: &str) -> &str {
+    let trimmed = text.trim();
+    if !trimmed.starts_with("```") {
+        return trimmed;
+    }
+
+    let inner = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .unwrap_or(trimmed);
+    inner.strip_suffix("```").unwrap_or(inner).trim()
+}
+
 /// Parse an SSE event block into (event_type, data)
 fn parse_sse_event(block: &str) -> (String, Option<String>) {
     let mut event_type = String::new();
@@ -411,6 +527,13 @@ data: {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}
         assert_eq!(err.error.message, "Overloaded");
     }
 
+    #[test]
+    fn test_strip_code_fences() {
+        assert_eq!(strip_code_fences("```json\n{\"a\": 1}\n```"), "{\"a\": 1}");
+        assert_eq!(strip_code_fences("```\n[1]\n```"), "[1]");
+        assert_eq!(strip_code_fences("  {\"a\": 1} "), "{\"a\": 1}");
+    }
+
     #[test]
     fn test_parse_sse_event_ping() {
         let block = "event: ping\ndata: {}";
@@ -1,5 +1,6 @@
 use tauri::ipc::Channel;
 
+use crate::db::prompt_presets::PromptPreset;
 use crate::db::properties::Property;
 use crate::error::AppError;
 
@@ -10,11 +11,14 @@ use super::prompts::{build_analysis_prompt, build_email_prompt, AgentInfo, MAX_T
 /// Two-stage email generation pipeline:
 /// 1. Analyze property (non-streaming) -> structured JSON
 /// 2. Generate email (streaming) -> text deltas via Channel
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_email(
     client: &ClaudeClient,
     property: &Property,
     template_type: &str,
+    custom: Option<&PromptPreset>,
     brand_voice_block: Option<&str>,
+    language: &str,
     agent_info: &AgentInfo,
     channel: &Channel<StreamEvent>,
 ) -> Result<GenerationResult, AppError> {
@@ -33,8 +37,15 @@ pub async fn generate_email(
     })?;
 
     // Stage 2: Generate email (streaming)
-    let (email_system, email_user) =
-        build_email_prompt(property, &analysis_text, template_type, brand_voice_block, agent_info);
+    let (email_system, email_user) = build_email_prompt(
+        property,
+        &analysis_text,
+        template_type,
+        custom,
+        brand_voice_block,
+        language,
+        agent_info,
+    );
 
     let (full_text, email_input, email_output) = client
         .stream_message(&email_system, &email_user, MAX_TOKENS_EMAIL, channel)
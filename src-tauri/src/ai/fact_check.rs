@@ -0,0 +1,864 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db::price_history::PriceChange;
+use crate::db::properties::Property;
+
+use super::prompts::format_price;
+
+/// Language the claim extractor reads. Copy in any other language isn't
+/// checked, since its claims would all go unnoticed.
+pub const CHECKED_LANGUAGE: &str = "en";
+
+/// Slack on a stated living area, for copy that rounds ("about 1,800 sq ft")
+const SQFT_TOLERANCE: f64 = 0.02;
+
+/// Slack on a stated price, for copy that rounds ("$1.2M")
+const PRICE_TOLERANCE: f64 = 0.01;
+
+/// Slack on a price the copy says is approximate ("around $1M")
+const APPROX_PRICE_TOLERANCE: f64 = 0.1;
+
+/// Dollar amounts under this share of the asking price are fees, credits and
+/// the like rather than a price for the home
+const MIN_PRICE_SHARE: f64 = 0.1;
+
+const BED_UNITS: &[&str] = &[
+    "bed", "beds", "bedroom", "bedrooms", "br", "bd", "bds", "bdrm", "bdrms",
+];
+const BATH_UNITS: &[&str] = &["bath", "baths", "bathroom", "bathrooms", "ba"];
+const SQFT_UNITS: &[&[&str]] = &[
+    &["sq", "ft"],
+    &["sq", "feet"],
+    &["square", "feet"],
+    &["square", "foot"],
+    &["sqft"],
+    &["sf"],
+];
+const ACRE_UNITS: &[&str] = &["acre", "acres", "ac"];
+const PARKING_WORDS: &[&str] = &["garage", "garages", "carport", "carports", "parking"];
+
+const BUILT_WORDS: &[&str] = &["built", "constructed", "construction", "circa", "erected"];
+const RENOVATED_WORDS: &[&str] = &[
+    "renovated",
+    "renovation",
+    "renovations",
+    "remodeled",
+    "remodelled",
+    "remodel",
+    "updated",
+    "update",
+    "updates",
+    "upgraded",
+    "restored",
+    "restoration",
+    "rebuilt",
+    "redone",
+    "replaced",
+    "refreshed",
+    "new",
+];
+
+/// Words that name a property type, and the `property_type` they claim
+const PROPERTY_TYPES: &[(&[&str], &str)] = &[
+    (&["single", "family"], "single_family"),
+    (&["condo"], "condo"),
+    (&["condominium"], "condo"),
+    (&["townhouse"], "townhouse"),
+    (&["townhome"], "townhouse"),
+    (&["multi", "family"], "multi_family"),
+    (&["duplex"], "multi_family"),
+    (&["triplex"], "multi_family"),
+    (&["fourplex"], "multi_family"),
+];
+
+/// Amenities copy tends to invent: the phrases that claim one (longest
+/// first), and the words in the property record that back it up
+const FEATURES: &[(&[&str], &[&str])] = &[
+    (&["swimming pool", "pool"], &["pool"]),
+    (&["hot tub", "jacuzzi"], &["hot tub", "jacuzzi", "spa"]),
+    (&["fireplace"], &["fireplace"]),
+    (&["basement"], &["basement"]),
+    (
+        &["waterfront", "lakefront", "oceanfront", "beachfront"],
+        &["waterfront", "lakefront", "oceanfront", "beachfront"],
+    ),
+    (&["elevator"], &["elevator"]),
+    (&["solar panel", "solar"], &["solar"]),
+    (
+        &["guest house", "in law unit", "adu", "casita"],
+        &["guest house", "in law", "adu", "casita"],
+    ),
+    (&["wine cellar"], &["wine cellar"]),
+    (&["garage"], &["garage"]),
+    (&["gated"], &["gated"]),
+];
+
+/// What a claim in generated copy is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimKind {
+    Bedrooms,
+    Bathrooms,
+    SquareFeet,
+    LotSize,
+    Price,
+    Parking,
+    YearBuilt,
+    YearRenovated,
+    PropertyType,
+    Feature,
+}
+
+/// A numeric or categorical claim found in generated copy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Claim {
+    pub kind: ClaimKind,
+    /// The claim as written
+    pub text: String,
+    /// UTF-16 offsets of `text` in the copy, so the editor can highlight it
+    pub start: usize,
+    pub end: usize,
+    pub supported: bool,
+    /// What the property record says instead, when it says anything
+    pub expected: Option<String>,
+    /// An unsupported claim the generated property analysis also makes,
+    /// rather than one the copy made up on its own
+    pub from_analysis: bool,
+}
+
+/// Claims in a listing checked against its property. Stored on the listing
+/// as JSON alongside the unsupported count.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FactCheck {
+    pub unsupported: usize,
+    pub claims: Vec<Claim>,
+}
+
+impl FactCheck {
+    pub fn unsupported_claims(&self) -> impl Iterator<Item = &Claim> {
+        self.claims.iter().filter(|c| !c.supported)
+    }
+}
+
+/// Find the beds, baths, sizes, prices, parking, years, property types and
+/// amenities `content` claims and check each against the property record
+/// and its price history. `analysis_json` is the analysis the copy was
+/// written from, when it's at hand.
+pub fn check(
+    property: &Property,
+    price_history: &[PriceChange],
+    analysis_json: Option<&str>,
+    content: &str,
+) -> FactCheck {
+    let record = Record::new(property, price_history);
+    let analysis = analysis_json.map(normalize).unwrap_or_default();
+    let tokens = tokenize(content);
+
+    let mut claims = Vec::new();
+    let mut i = 0;
+    // Start of the copy not yet part of a claim; a year can reach back to
+    // an earlier "built" that's already been claimed
+    let mut unclaimed = 0;
+    while i < tokens.len() {
+        let Some(found) = find_claim(&tokens, content, i, &record) else {
+            i += 1;
+            continue;
+        };
+        let first = found.first.max(unclaimed);
+        let start = tokens[first].start - usize::from(found.dollar && first == found.first);
+        let end = tokens[found.last].end;
+        let text = &content[start..end];
+        claims.push(Claim {
+            kind: found.kind,
+            text: text.to_string(),
+            start: utf16_offset(content, start),
+            end: utf16_offset(content, end),
+            supported: found.supported,
+            expected: found.expected,
+            from_analysis: !found.supported && mentions(&analysis, &normalize(text)),
+        });
+        i = found.last + 1;
+        unclaimed = i;
+    }
+
+    FactCheck {
+        unsupported: claims.iter().filter(|c| !c.supported).count(),
+        claims,
+    }
+}
+
+/// The property record in the forms claims are checked against
+struct Record<'a> {
+    property: &'a Property,
+    /// Free-text fields, normalized
+    text: String,
+    /// Every number in the free-text fields
+    numbers: Vec<f64>,
+    /// Current, sold and past asking prices in cents
+    prices: Vec<i64>,
+    parking: Vec<f64>,
+}
+
+impl<'a> Record<'a> {
+    fn new(property: &'a Property, price_history: &[PriceChange]) -> Self {
+        let list = |json: &str| serde_json::from_str::<Vec<String>>(json).unwrap_or_default();
+        let mut fields = list(&property.key_features);
+        fields.extend(list(&property.neighborhood_highlights));
+        fields.extend(list(&property.nearby_amenities));
+        fields.extend(
+            [
+                &property.lot_size,
+                &property.parking,
+                &property.neighborhood,
+                &property.school_district,
+                &property.agent_notes,
+            ]
+            .into_iter()
+            .flatten()
+            .cloned(),
+        );
+        let text = fields.join(" \n ");
+
+        let mut prices = vec![property.price];
+        prices.extend(property.sold_price);
+        for change in price_history {
+            prices.extend([change.old_price, change.new_price]);
+        }
+
+        Record {
+            property,
+            numbers: numbers_in(&text),
+            text: normalize(&text),
+            prices,
+            parking: property
+                .parking
+                .as_deref()
+                .map(numbers_in)
+                .unwrap_or_default(),
+        }
+    }
+
+    fn has_number(&self, value: f64) -> bool {
+        self.numbers.iter().any(|&n| (n - value).abs() < 1e-6)
+    }
+
+    fn mentions_any(&self, terms: &[&str]) -> bool {
+        terms.iter().any(|term| mentions(&self.text, term))
+    }
+
+    /// Whether a dollar amount, in cents, is a price the home has had or a
+    /// change between two of them
+    fn has_price(&self, cents: f64) -> bool {
+        let near_price = |price: i64| near(cents, price as f64, PRICE_TOLERANCE);
+        self.prices.iter().any(|&p| near_price(p))
+            || self.prices.iter().enumerate().any(|(i, &a)| {
+                self.prices[i + 1..]
+                    .iter()
+                    .any(|&b| a != b && near_price((a - b).abs()))
+            })
+    }
+
+    /// Whether a hedged dollar amount, in cents, fits the current asking
+    /// price or the sale price: a ceiling or floor must hold, a round figure
+    /// must be close to any price the home has had
+    fn fits_price(&self, cents: f64, qualifier: PriceQualifier) -> bool {
+        let mut current = std::iter::once(self.property.price).chain(self.property.sold_price);
+        match qualifier {
+            PriceQualifier::Under => current.any(|p| p as f64 <= cents * (1.0 + PRICE_TOLERANCE)),
+            PriceQualifier::Over => current.any(|p| p as f64 >= cents * (1.0 - PRICE_TOLERANCE)),
+            PriceQualifier::About => self
+                .prices
+                .iter()
+                .any(|&p| near(cents, p as f64, APPROX_PRICE_TOLERANCE)),
+        }
+    }
+}
+
+/// How copy hedges a price: a ceiling ("priced under $1M"), a floor ("sold
+/// for over $900K") or a round figure ("around $1M")
+#[derive(Clone, Copy)]
+enum PriceQualifier {
+    Under,
+    Over,
+    About,
+}
+
+const PRICE_QUALIFIERS: &[(&[&str], PriceQualifier)] = &[
+    (&["under"], PriceQualifier::Under),
+    (&["below"], PriceQualifier::Under),
+    (&["less", "than"], PriceQualifier::Under),
+    (&["over"], PriceQualifier::Over),
+    (&["above"], PriceQualifier::Over),
+    (&["more", "than"], PriceQualifier::Over),
+    (&["about"], PriceQualifier::About),
+    (&["around"], PriceQualifier::About),
+    (&["nearly"], PriceQualifier::About),
+    (&["almost"], PriceQualifier::About),
+    (&["approximately"], PriceQualifier::About),
+    (&["roughly"], PriceQualifier::About),
+];
+
+/// The qualifier in the words just before the token at `i`, if any
+fn price_qualifier(tokens: &[Token], i: usize) -> Option<PriceQualifier> {
+    PRICE_QUALIFIERS.iter().find_map(|(words, qualifier)| {
+        let start = i.checked_sub(words.len())?;
+        tokens[start..i]
+            .iter()
+            .zip(words.iter())
+            .all(|(t, w)| t.text == *w)
+            .then_some(*qualifier)
+    })
+}
+
+/// A claim starting at a token, before it's cut out of the copy
+struct Found {
+    kind: ClaimKind,
+    first: usize,
+    last: usize,
+    /// The claim starts with the `$` just before its first token
+    dollar: bool,
+    supported: bool,
+    expected: Option<String>,
+}
+
+fn find_claim(tokens: &[Token], content: &str, i: usize, record: &Record) -> Option<Found> {
+    property_type_claim(tokens, i, record)
+        .or_else(|| price_claim(tokens, content, i, record))
+        .or_else(|| quantity_claim(tokens, content, i, record))
+        .or_else(|| year_claim(tokens, content, i, record))
+        .or_else(|| feature_claim(tokens, i, record))
+}
+
+fn property_type_claim(tokens: &[Token], i: usize, record: &Record) -> Option<Found> {
+    let property_type = record.property.property_type.as_str();
+    PROPERTY_TYPES.iter().find_map(|(words, claimed)| {
+        let last = phrase_at(tokens, i, words)?;
+        let supported = *claimed == property_type;
+        Some(Found {
+            kind: ClaimKind::PropertyType,
+            first: i,
+            last,
+            dollar: false,
+            supported,
+            expected: (!supported).then(|| property_type.replace('_', " ")),
+        })
+    })
+}
+
+/// "$950,000", "$1.2M", "$875K", "$1.5 million", optionally hedged as in
+/// "under $1M" or "around $1M"
+fn price_claim(tokens: &[Token], content: &str, i: usize, record: &Record) -> Option<Found> {
+    let token = &tokens[i];
+    if !content[..token.start].ends_with('$') {
+        return None;
+    }
+    let mut dollars = digits_value(&token.text)?;
+    let mut last = i;
+    if let Some(next) = tokens.get(i + 1) {
+        let adjacent = next.start == token.end;
+        let multiplier = match next.text.as_str() {
+            "k" if adjacent => Some(1e3),
+            "m" | "mm" if adjacent => Some(1e6),
+            "thousand" => Some(1e3),
+            "million" => Some(1e6),
+            _ => None,
+        };
+        if let Some(multiplier) = multiplier {
+            dollars *= multiplier;
+            last += 1;
+        }
+    }
+
+    let cents = dollars * 100.0;
+    if cents < record.property.price as f64 * MIN_PRICE_SHARE {
+        return None;
+    }
+    let supported = match price_qualifier(tokens, i) {
+        Some(qualifier) => record.fits_price(cents, qualifier),
+        None => record.has_price(cents),
+    };
+    Some(Found {
+        kind: ClaimKind::Price,
+        first: i,
+        last,
+        dollar: true,
+        supported,
+        expected: (!supported).then(|| match record.property.sold_price {
+            Some(sold) => format!(
+                "Listed at ${}, sold at ${}",
+                format_price(record.property.price),
+                format_price(sold)
+            ),
+            None => format!("${}", format_price(record.property.price)),
+        }),
+    })
+}
+
+/// A count or size followed by its unit: "3 bedrooms", "2.5 baths",
+/// "1,800 sq ft", "0.25-acre lot", "two-car garage"
+fn quantity_claim(tokens: &[Token], content: &str, i: usize, record: &Record) -> Option<Found> {
+    let token = &tokens[i];
+    if content[..token.start].ends_with('$') {
+        return None;
+    }
+    let is_word = word_number(&token.text).is_some();
+    let mut value = number_value(&token.text)?;
+    let property = record.property;
+
+    // "2 1/2 baths", "two and a half baths"
+    let mut j = i + 1;
+    if phrase_at(tokens, j, &["1", "2"]).is_some()
+        && content[tokens[j].end..tokens[j + 1].start].trim() == "/"
+    {
+        value += 0.5;
+        j += 2;
+    } else if phrase_at(tokens, j, &["and", "a", "half"]).is_some() {
+        value += 0.5;
+        j += 3;
+    }
+    let qualifier = match tokens.get(j).map(|t| t.text.as_str()) {
+        Some(q @ ("full" | "half")) => {
+            j += 1;
+            Some(q)
+        }
+        _ => None,
+    };
+    let unit = tokens.get(j)?;
+
+    // "one bedroom has..." isn't a bedroom count; "three-bedroom" and
+    // "three bedrooms" are
+    if is_word {
+        let hyphenated = content[token.end..tokens[i + 1].start].trim() == "-";
+        if !hyphenated && !unit.text.ends_with('s') {
+            return None;
+        }
+    }
+
+    let found = |kind, last, supported: bool, expected: Option<String>| Found {
+        kind,
+        first: i,
+        last,
+        dollar: false,
+        supported,
+        expected: if supported { None } else { expected },
+    };
+
+    if BED_UNITS.contains(&unit.text.as_str()) {
+        let supported = qualifier.is_none() && value == property.beds as f64;
+        return Some(found(
+            ClaimKind::Bedrooms,
+            j,
+            supported,
+            Some(format!("{} bedrooms", property.beds)),
+        ));
+    }
+    if BATH_UNITS.contains(&unit.text.as_str()) {
+        let supported = match qualifier {
+            Some("full") => value == property.baths.floor(),
+            Some(_) => property.baths.fract() > 0.0,
+            None => (value - property.baths).abs() < 0.01,
+        };
+        return Some(found(
+            ClaimKind::Bathrooms,
+            j,
+            supported,
+            Some(format!("{} bathrooms", property.baths)),
+        ));
+    }
+    if qualifier.is_some() {
+        return None;
+    }
+
+    if let Some(last) = SQFT_UNITS.iter().find_map(|u| phrase_at(tokens, j, u)) {
+        let lot = |range: std::ops::Range<usize>| {
+            tokens
+                .get(range)
+                .is_some_and(|ts| ts.iter().any(|t| t.text == "lot"))
+        };
+        if lot(last + 1..(last + 3).min(tokens.len())) || lot(i.saturating_sub(2)..i) {
+            return Some(found(
+                ClaimKind::LotSize,
+                last,
+                record.has_number(value),
+                property.lot_size.clone(),
+            ));
+        }
+        let supported =
+            near(value, property.sqft as f64, SQFT_TOLERANCE) || record.has_number(value);
+        return Some(found(
+            ClaimKind::SquareFeet,
+            last,
+            supported,
+            Some(format!("{} sq ft", property.sqft)),
+        ));
+    }
+    if ACRE_UNITS.contains(&unit.text.as_str()) {
+        return Some(found(
+            ClaimKind::LotSize,
+            j,
+            record.has_number(value),
+            property.lot_size.clone(),
+        ));
+    }
+    if unit.text == "car" {
+        let last = (j + 1..(j + 3).min(tokens.len()))
+            .find(|&k| PARKING_WORDS.contains(&tokens[k].text.as_str()))?;
+        return Some(found(
+            ClaimKind::Parking,
+            last,
+            record.parking.contains(&value),
+            property.parking.clone(),
+        ));
+    }
+    None
+}
+
+/// A year next to building or renovation words: "built in 1925",
+/// "renovated in 2020", "2021 remodel"
+fn year_claim(tokens: &[Token], content: &str, i: usize, record: &Record) -> Option<Found> {
+    let token = &tokens[i];
+    if token.text.len() != 4 || content[..token.start].ends_with('$') {
+        return None;
+    }
+    let year: i64 = token.text.parse().ok()?;
+    if !(1800..=2100).contains(&year) {
+        return None;
+    }
+
+    let kind_of = |k: usize| {
+        let word = tokens[k].text.as_str();
+        if BUILT_WORDS.contains(&word) {
+            Some(ClaimKind::YearBuilt)
+        } else if RENOVATED_WORDS.contains(&word) {
+            Some(ClaimKind::YearRenovated)
+        } else {
+            None
+        }
+    };
+    // The nearest word before the year, then after it
+    let (kind, first, last) = (i.saturating_sub(3)..i)
+        .rev()
+        .find_map(|k| kind_of(k).map(|kind| (kind, k, i)))
+        .or_else(|| {
+            (i + 1..(i + 3).min(tokens.len())).find_map(|k| kind_of(k).map(|kind| (kind, i, k)))
+        })?;
+
+    let property = record.property;
+    let (supported, expected) = match kind {
+        ClaimKind::YearBuilt => (
+            property.year_built == Some(year),
+            property.year_built.map(|y| format!("Built in {}", y)),
+        ),
+        // Renovation years only ever come from the agent's notes and features
+        _ => (record.has_number(year as f64), None),
+    };
+    Some(Found {
+        kind,
+        first,
+        last,
+        dollar: false,
+        supported,
+        expected: if supported { None } else { expected },
+    })
+}
+
+fn feature_claim(tokens: &[Token], i: usize, record: &Record) -> Option<Found> {
+    FEATURES.iter().find_map(|(phrases, support)| {
+        let last = phrases.iter().find_map(|phrase| {
+            let words: Vec<&str> = phrase.split(' ').collect();
+            phrase_at(tokens, i, &words)
+        })?;
+        let supported = record.mentions_any(support);
+        let expected = match phrases[0] {
+            "garage" if !supported => record.property.parking.clone(),
+            _ => None,
+        };
+        Some(Found {
+            kind: ClaimKind::Feature,
+            first: i,
+            last,
+            dollar: false,
+            supported,
+            expected,
+        })
+    })
+}
+
+/// A word, number or number-with-separators in the copy, lowercased, with
+/// its byte span
+struct Token {
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+/// Split into runs of letters and digits. Digits keep their thousands and
+/// decimal separators, and letters straight after digits start a new token
+/// so "3BR" and "$1.2M" split into number and unit.
+fn tokenize(text: &str) -> Vec<Token> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut current: Option<Token> = None;
+
+    for (k, &(i, c)) in chars.iter().enumerate() {
+        let next_is_digit = chars.get(k + 1).is_some_and(|&(_, n)| n.is_ascii_digit());
+        let continues = current.as_ref().is_some_and(|t| {
+            let last = t.text.chars().next_back().unwrap_or(' ');
+            if c.is_alphanumeric() {
+                !(last.is_ascii_digit() && c.is_alphabetic())
+            } else {
+                matches!(c, '.' | ',') && last.is_ascii_digit() && next_is_digit
+            }
+        });
+
+        if continues {
+            if let Some(t) = current.as_mut() {
+                t.text.extend(c.to_lowercase());
+                t.end = i + c.len_utf8();
+            }
+            continue;
+        }
+        tokens.extend(current.take());
+        if c.is_alphanumeric() || (c == '.' && next_is_digit) {
+            current = Some(Token {
+                text: c.to_lowercase().collect(),
+                start: i,
+                end: i + c.len_utf8(),
+            });
+        }
+    }
+    tokens.extend(current);
+    tokens
+}
+
+/// Index of the phrase's last token when `tokens` spell it out from
+/// `start`. The last word may be plural.
+fn phrase_at(tokens: &[Token], start: usize, words: &[&str]) -> Option<usize> {
+    let slice = tokens.get(start..start + words.len())?;
+    let matches = slice.iter().zip(words).enumerate().all(|(k, (t, w))| {
+        t.text == *w
+            || (k == words.len() - 1
+                && t.text
+                    .strip_prefix(w)
+                    .is_some_and(|rest| rest == "s" || rest == "es"))
+    });
+    matches.then(|| start + words.len() - 1)
+}
+
+fn word_number(word: &str) -> Option<f64> {
+    let n = match word {
+        "one" | "single" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+        "ten" => 10,
+        _ => return None,
+    };
+    Some(n as f64)
+}
+
+fn number_value(word: &str) -> Option<f64> {
+    word_number(word).or_else(|| digits_value(word))
+}
+
+/// "1,800" and "2.5"; a comma not followed by three digits is a decimal
+/// comma
+fn digits_value(word: &str) -> Option<f64> {
+    if !word.chars().any(|c| c.is_ascii_digit())
+        || !word
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == ',' || c == '.')
+    {
+        return None;
+    }
+    let thousands = word.split(',').skip(1).all(|group| {
+        let digits = group.split('.').next().unwrap_or("");
+        digits.len() == 3
+    });
+    let plain = if thousands {
+        word.replace(',', "")
+    } else {
+        word.replace(',', ".")
+    };
+    plain.parse().ok()
+}
+
+fn numbers_in(text: &str) -> Vec<f64> {
+    tokenize(text)
+        .iter()
+        .filter_map(|t| number_value(&t.text))
+        .collect()
+}
+
+fn near(value: f64, target: f64, tolerance: f64) -> bool {
+    (value - target).abs() <= target.abs() * tolerance
+}
+
+/// Lowercase words and numbers separated by single spaces, for comparing
+/// phrases regardless of punctuation
+fn normalize(text: &str) -> String {
+    tokenize(text)
+        .into_iter()
+        .map(|t| t.text.replace(',', ""))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether normalized `text` contains the normalized `term` as whole
+/// words, allowing a plural
+fn mentions(text: &str, term: &str) -> bool {
+    if term.is_empty() {
+        return false;
+    }
+    text.match_indices(term).any(|(start, _)| {
+        let rest = &text[start + term.len()..];
+        let rest = rest
+            .strip_prefix("es")
+            .or_else(|| rest.strip_prefix('s'))
+            .filter(|r| r.is_empty() || r.starts_with(' '))
+            .unwrap_or(rest);
+        (start == 0 || text[..start].ends_with(' ')) && (rest.is_empty() || rest.starts_with(' '))
+    })
+}
+
+fn utf16_offset(text: &str, byte: usize) -> usize {
+    text[..byte].encode_utf16().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn property() -> Property {
+        Property {
+            year_built: Some(1925),
+            lot_size: Some("0.25 acres".to_string()),
+            parking: Some("2-car garage".to_string()),
+            key_features: r#"["hardwood floors","pool","kitchen remodeled 2019"]"#.to_string(),
+            neighborhood: Some("Noe Valley".to_string()),
+            ..crate::db::test_property()
+        }
+    }
+
+    fn unsupported(check: &FactCheck) -> Vec<&str> {
+        check
+            .unsupported_claims()
+            .map(|c| c.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_supported_claims() {
+        let content = "Charming 3-bedroom, 2 1/2 bath single-family Victorian: about 1,800 sq ft \
+            on a 0.25-acre lot, built in 1925 with a kitchen remodeled in 2019, a sparkling \
+            pool and a two-car garage. Offered at $950,000.";
+        let check = check(&property(), &[], None, content);
+
+        assert_eq!(unsupported(&check), Vec::<&str>::new());
+        let kinds: Vec<ClaimKind> = check.claims.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ClaimKind::Bedrooms,
+                ClaimKind::Bathrooms,
+                ClaimKind::PropertyType,
+                ClaimKind::SquareFeet,
+                ClaimKind::LotSize,
+                ClaimKind::YearBuilt,
+                ClaimKind::YearRenovated,
+                ClaimKind::Feature,
+                ClaimKind::Parking,
+                ClaimKind::Price,
+            ]
+        );
+        assert_eq!(check.claims[9].text, "$950,000");
+    }
+
+    #[test]
+    fn test_unsupported_claims() {
+        let content = "This 4 bed, 3 bath condo has a 4-car garage, a fireplace and a \
+            basement, renovated 2020. Just $1.2M!";
+        let check = check(&property(), &[], None, content);
+
+        assert_eq!(
+            unsupported(&check),
+            vec![
+                "4 bed",
+                "3 bath",
+                "condo",
+                "4-car garage",
+                "fireplace",
+                "basement",
+                "renovated 2020",
+                "$1.2M"
+            ]
+        );
+        assert_eq!(check.unsupported, 8);
+        let garage = &check.claims[3];
+        assert_eq!(garage.kind, ClaimKind::Parking);
+        assert_eq!(garage.expected.as_deref(), Some("2-car garage"));
+        assert_eq!(check.claims[0].expected.as_deref(), Some("3 bedrooms"));
+        assert_eq!(check.claims[7].expected.as_deref(), Some("$950,000"));
+    }
+
+    #[test]
+    fn test_prices_from_history() {
+        let history = [PriceChange {
+            id: "c1".to_string(),
+            property_id: "test-id".to_string(),
+            old_price: 110000000,
+            new_price: 95000000,
+            changed_at: String::new(),
+        }];
+        let content = "Was $1.1M, now $950K: $150,000 off. HOA is $450/month.";
+        let check = check(&property(), &history, None, content);
+        assert_eq!(check.claims.len(), 3);
+        assert_eq!(check.unsupported, 0);
+
+        let check = super::check(&property(), &[], None, content);
+        assert_eq!(unsupported(&check), vec!["$1.1M", "$150,000"]);
+    }
+
+    #[test]
+    fn test_hedged_prices() {
+        // The home is listed at $950K
+        let content = "Priced under $1M, well above $900K and around $1M.";
+        let check = check(&property(), &[], None, content);
+        assert_eq!(check.claims.len(), 3);
+        assert_eq!(check.unsupported, 0);
+
+        let content = "Priced less than $900K, over $1M and nearly $1.2M.";
+        let check = super::check(&property(), &[], None, content);
+        assert_eq!(unsupported(&check), vec!["$900K", "$1M", "$1.2M"]);
+    }
+
+    #[test]
+    fn test_ignores_counts_that_arent_claims() {
+        let content = "One bedroom opens to the garden; the other two share a bath. \
+            Since 2015 the street has hosted a fair. Call 555-1234.";
+        let check = check(&property(), &[], None, content);
+        assert!(check.claims.is_empty());
+    }
+
+    #[test]
+    fn test_offsets_and_analysis() {
+        let content = "✨ Dream home with a 4-car garage";
+        let analysis = r#"{"selling_points":["Rare 4 car garage"]}"#;
+        let check = check(&property(), &[], Some(analysis), content);
+
+        let claim = &check.claims[0];
+        assert!(claim.from_analysis);
+        let utf16: Vec<u16> = content.encode_utf16().collect();
+        assert_eq!(
+            String::from_utf16(&utf16[claim.start..claim.end]).unwrap(),
+            "4-car garage"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tauri::ipc::Channel;
 
 use crate::db::properties::Property;
@@ -27,6 +27,31 @@ pub struct GenerationResult {
     pub analysis_json: String,
 }
 
+/// Stage-one output shared by every listing written from it
+pub struct AnalysisResult {
+    pub analysis_json: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+}
+
+/// A style/tone/length/voice combination to write from one analysis
+pub struct ListingVariant {
+    pub options: GenerationOptions,
+    pub brand_voice_block: Option<String>,
+}
+
+/// Events for several listings streamed over one channel, tagged by the
+/// variant's index in the request
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "event", content = "data")]
+pub enum VariantEvent {
+    Analyzing { variants: usize },
+    Stream { variant: usize, event: StreamEvent },
+    Saved { variant: usize, listing_id: String },
+    Failed { variant: usize, message: String },
+    Finished { group_id: String },
+}
+
 /// Two-stage listing generation pipeline:
 /// 1. Analyze property (non-streaming) → structured JSON
 /// 2. Generate listing description (streaming) → text deltas via Channel
@@ -39,31 +64,24 @@ pub async fn generate_listing(
     channel: &Channel<StreamEvent>,
 ) -> Result<GenerationResult, AppError> {
     // Stage 1: Property analysis (non-streaming)
-    let (analysis_system, analysis_user) = build_analysis_prompt(property);
-    let (analysis_text, analysis_input, _analysis_output) = client
-        .send_message(&analysis_system, &analysis_user, MAX_TOKENS_ANALYSIS)
-        .await?;
-
-    // Validate we got valid JSON (but we don't need to deserialize it for the prompt)
-    let _parsed: PropertyAnalysis = serde_json::from_str(&analysis_text).map_err(|e| {
-        AppError::Api(format!(
-            "Failed to parse property analysis from Claude: {}",
-            e
-        ))
-    })?;
+    let analysis = analyze_property(client, property).await?;
 
     // Stage 2: Generate listing (streaming)
-    let (listing_system, listing_user) =
-        build_listing_prompt(property, &analysis_text, options, brand_voice_block, agent_info);
-
-    let max_tokens = max_tokens_for_listing(&options.length);
-
-    let (full_text, listing_input, listing_output) = client
-        .stream_message(&listing_system, &listing_user, max_tokens, channel)
-        .await?;
+    let (full_text, listing_input, listing_output) = write_listing(
+        client,
+        property,
+        &analysis.analysis_json,
+        options,
+        brand_voice_block,
+        agent_info,
+        |event| {
+            let _ = channel.send(event);
+        },
+    )
+    .await?;
 
-    let total_input = analysis_input + listing_input;
-    let total_output = _analysis_output + listing_output;
+    let total_input = analysis.input_tokens + listing_input;
+    let total_output = analysis.output_tokens + listing_output;
     let cost_cents = calculate_cost_cents(total_input, total_output);
 
     // Send finished event
@@ -79,10 +97,147 @@ pub async fn generate_listing(
         input_tokens: total_input,
         output_tokens: total_output,
         cost_cents,
-        analysis_json: analysis_text,
+        analysis_json: analysis.analysis_json,
     })
 }
 
+/// Write several variants of a listing from a single property analysis.
+/// The variants stream concurrently; one failing doesn't stop the others,
+/// so each gets its own result. Only a failed analysis fails the whole run.
+pub async fn generate_variants(
+    client: &ClaudeClient,
+    property: &Property,
+    variants: &[ListingVariant],
+    agent_info: &AgentInfo,
+    channel: &Channel<VariantEvent>,
+) -> Result<Vec<Result<GenerationResult, AppError>>, AppError> {
+    let _ = channel.send(VariantEvent::Analyzing {
+        variants: variants.len(),
+    });
+    let analysis = analyze_property(client, property).await?;
+
+    let runs = variants.iter().enumerate().map(|(index, variant)| {
+        let analysis = &analysis;
+        async move {
+            let result = write_listing(
+                client,
+                property,
+                &analysis.analysis_json,
+                &variant.options,
+                variant.brand_voice_block.as_deref(),
+                agent_info,
+                |event| {
+                    let _ = channel.send(VariantEvent::Stream {
+                        variant: index,
+                        event,
+                    });
+                },
+            )
+            .await;
+
+            match result {
+                Ok((full_text, listing_input, listing_output)) => {
+                    // Each variant carries an even share of the analysis
+                    let (analysis_input, analysis_output) =
+                        analysis_share(analysis, variants.len(), index);
+                    let input_tokens = analysis_input + listing_input;
+                    let output_tokens = analysis_output + listing_output;
+                    let cost_cents = calculate_cost_cents(input_tokens, output_tokens);
+                    let _ = channel.send(VariantEvent::Stream {
+                        variant: index,
+                        event: StreamEvent::Finished {
+                            full_text: full_text.clone(),
+                            input_tokens,
+                            output_tokens,
+                            cost_cents,
+                        },
+                    });
+                    Ok(GenerationResult {
+                        full_text,
+                        input_tokens,
+                        output_tokens,
+                        cost_cents,
+                        analysis_json: analysis.analysis_json.clone(),
+                    })
+                }
+                Err(e) => {
+                    let _ = channel.send(VariantEvent::Failed {
+                        variant: index,
+                        message: e.to_string(),
+                    });
+                    Err(e)
+                }
+            }
+        }
+    });
+
+    Ok(futures::future::join_all(runs).await)
+}
+
+/// Stage 1: analyze the property into structured JSON
+pub async fn analyze_property(
+    client: &ClaudeClient,
+    property: &Property,
+) -> Result<AnalysisResult, AppError> {
+    let (analysis_system, analysis_user) = build_analysis_prompt(property);
+    let (analysis_json, input_tokens, output_tokens) = client
+        .send_message(&analysis_system, &analysis_user, MAX_TOKENS_ANALYSIS)
+        .await?;
+
+    // Validate we got valid JSON (but we don't need to deserialize it for the prompt)
+    let _parsed: PropertyAnalysis = serde_json::from_str(&analysis_json).map_err(|e| {
+        AppError::Api(format!(
+            "Failed to parse property analysis from Claude: {}",
+            e
+        ))
+    })?;
+
+    Ok(AnalysisResult {
+        analysis_json,
+        input_tokens,
+        output_tokens,
+    })
+}
+
+/// Stage 2: stream the listing copy written from an analysis
+async fn write_listing(
+    client: &ClaudeClient,
+    property: &Property,
+    analysis_json: &str,
+    options: &GenerationOptions,
+    brand_voice_block: Option<&str>,
+    agent_info: &AgentInfo,
+    on_event: impl Fn(StreamEvent),
+) -> Result<(String, u32, u32), AppError> {
+    let (listing_system, listing_user) = build_listing_prompt(
+        property,
+        analysis_json,
+        options,
+        brand_voice_block,
+        agent_info,
+    );
+    let max_tokens = max_tokens_for_listing(&options.length);
+
+    client
+        .stream_message_with(&listing_system, &listing_user, max_tokens, on_event)
+        .await
+}
+
+/// Variant `index`'s share of the analysis tokens; the first variant takes
+/// the remainder so the shares add up to the total
+fn analysis_share(analysis: &AnalysisResult, variants: usize, index: usize) -> (u32, u32) {
+    let variants = variants.max(1) as u32;
+    let share = |total: u32| {
+        let base = total / variants;
+        if index == 0 {
+            base + total % variants
+        } else {
+            base
+        }
+    };
+    (share(analysis.input_tokens), share(analysis.output_tokens))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,6 +258,18 @@ mod tests {
         assert_eq!(analysis.emotional_hooks.len(), 3);
     }
 
+    #[test]
+    fn test_analysis_share_adds_up() {
+        let analysis = AnalysisResult {
+            analysis_json: String::new(),
+            input_tokens: 1001,
+            output_tokens: 300,
+        };
+        let shares: Vec<(u32, u32)> = (0..3).map(|i| analysis_share(&analysis, 3, i)).collect();
+        assert_eq!(shares, vec![(335, 100), (333, 100), (333, 100)]);
+        assert_eq!(shares.iter().map(|s| s.0).sum::<u32>(), 1001);
+    }
+
     #[test]
     fn test_invalid_analysis_json() {
         let bad_json = "not valid json";
@@ -0,0 +1,436 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// Print ads are sold by space, so the body has a hard character limit
+pub const DEFAULT_PRINT_AD_LIMIT: usize = 300;
+pub const MIN_PRINT_AD_LIMIT: usize = 80;
+pub const MAX_PRINT_AD_LIMIT: usize = 2000;
+pub const MAX_PRINT_AD_HEADLINE: usize = 60;
+
+/// Long-form copy types, each with its own prompt, output format and export
+/// layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LongFormKind {
+    /// Walkthrough video script with a shot list per scene
+    VideoScript,
+    /// Single-property website: headline, tagline and sections
+    WebsiteCopy,
+    /// Room-by-room feature sheet
+    FeatureSheet,
+    NeighborhoodGuide,
+    /// Print ad with a hard character limit on the body
+    PrintAd,
+}
+
+impl LongFormKind {
+    pub const ALL: [LongFormKind; 5] = [
+        LongFormKind::VideoScript,
+        LongFormKind::WebsiteCopy,
+        LongFormKind::FeatureSheet,
+        LongFormKind::NeighborhoodGuide,
+        LongFormKind::PrintAd,
+    ];
+
+    /// Stored as the listing's `generation_type`
+    pub fn generation_type(self) -> &'static str {
+        match self {
+            LongFormKind::VideoScript => "video_script",
+            LongFormKind::WebsiteCopy => "website_copy",
+            LongFormKind::FeatureSheet => "feature_sheet",
+            LongFormKind::NeighborhoodGuide => "neighborhood_guide",
+            LongFormKind::PrintAd => "print_ad",
+        }
+    }
+
+    pub fn from_generation_type(generation_type: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|k| k.generation_type() == generation_type)
+    }
+
+    /// Section title in exports
+    pub fn title(self) -> &'static str {
+        match self {
+            LongFormKind::VideoScript => "Video Script",
+            LongFormKind::WebsiteCopy => "Property Website",
+            LongFormKind::FeatureSheet => "Feature Sheet",
+            LongFormKind::NeighborhoodGuide => "Neighborhood Guide",
+            LongFormKind::PrintAd => "Print Ad",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scene {
+    pub location: String,
+    pub duration_seconds: Option<u32>,
+    pub shots: Vec<String>,
+    pub voiceover: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Section {
+    pub heading: String,
+    pub paragraphs: Vec<String>,
+    pub bullets: Vec<String>,
+}
+
+/// Parsed long-form copy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum LongFormDocument {
+    #[serde(rename_all = "camelCase")]
+    VideoScript {
+        title: String,
+        scenes: Vec<Scene>,
+        total_seconds: u32,
+    },
+    #[serde(rename_all = "camelCase")]
+    WebsiteCopy {
+        headline: String,
+        tagline: String,
+        sections: Vec<Section>,
+    },
+    #[serde(rename_all = "camelCase")]
+    FeatureSheet { title: String, rooms: Vec<Section> },
+    #[serde(rename_all = "camelCase")]
+    NeighborhoodGuide {
+        title: String,
+        sections: Vec<Section>,
+    },
+    #[serde(rename_all = "camelCase")]
+    PrintAd {
+        headline: String,
+        body: String,
+        characters: usize,
+    },
+}
+
+/// Parse generated (or edited) copy in the output format its kind's prompt
+/// asks for
+pub fn parse(kind: LongFormKind, text: &str) -> Result<LongFormDocument, AppError> {
+    let (preamble, blocks) = split_blocks(text);
+    let missing = |what: &str| {
+        AppError::Validation(format!(
+            "The {} has no {}; it may have been edited out of its format",
+            kind.title().to_lowercase(),
+            what
+        ))
+    };
+
+    match kind {
+        LongFormKind::VideoScript => {
+            let scenes: Vec<Scene> = blocks
+                .iter()
+                .filter(|(marker, _)| marker.to_uppercase().starts_with("SCENE"))
+                .map(|(_, body)| parse_scene(body))
+                .collect();
+            if scenes.is_empty() {
+                return Err(missing("scenes"));
+            }
+            let total_seconds = scenes.iter().filter_map(|s| s.duration_seconds).sum();
+            Ok(LongFormDocument::VideoScript {
+                title: field(&preamble, "TITLE").unwrap_or_default(),
+                scenes,
+                total_seconds,
+            })
+        }
+        LongFormKind::WebsiteCopy => {
+            let sections = sections(&blocks, "SECTION");
+            if sections.is_empty() {
+                return Err(missing("sections"));
+            }
+            Ok(LongFormDocument::WebsiteCopy {
+                headline: field(&preamble, "HEADLINE").ok_or_else(|| missing("headline"))?,
+                tagline: field(&preamble, "TAGLINE").unwrap_or_default(),
+                sections,
+            })
+        }
+        LongFormKind::FeatureSheet => {
+            let rooms = sections(&blocks, "ROOM");
+            if rooms.is_empty() {
+                return Err(missing("rooms"));
+            }
+            Ok(LongFormDocument::FeatureSheet {
+                title: field(&preamble, "TITLE").unwrap_or_default(),
+                rooms,
+            })
+        }
+        LongFormKind::NeighborhoodGuide => {
+            let sections = sections(&blocks, "SECTION");
+            if sections.is_empty() {
+                return Err(missing("sections"));
+            }
+            Ok(LongFormDocument::NeighborhoodGuide {
+                title: field(&preamble, "TITLE").unwrap_or_default(),
+                sections,
+            })
+        }
+        LongFormKind::PrintAd => {
+            let headline = field(&preamble, "HEADLINE").ok_or_else(|| missing("headline"))?;
+            let body = multiline_field(&preamble, "BODY").ok_or_else(|| missing("body"))?;
+            Ok(LongFormDocument::PrintAd {
+                headline,
+                characters: body.chars().count(),
+                body,
+            })
+        }
+    }
+}
+
+/// Cut a print ad body to `limit` characters at the last sentence end, or
+/// the last word when no sentence fits. Used only when the model overruns
+/// the limit twice.
+pub fn trim_to_limit(body: &str, limit: usize) -> String {
+    let body = body.trim();
+    if body.chars().count() <= limit {
+        return body.to_string();
+    }
+    let cut: String = body.chars().take(limit).collect();
+    let sentence_end = cut
+        .char_indices()
+        .filter(|&(i, c)| {
+            matches!(c, '.' | '!' | '?') && cut[i + c.len_utf8()..].starts_with(char::is_whitespace)
+                || matches!(c, '.' | '!' | '?') && i + c.len_utf8() == cut.len()
+        })
+        .map(|(i, c)| i + c.len_utf8())
+        .next_back();
+    match sentence_end {
+        Some(end) => cut[..end].to_string(),
+        None => match cut.rfind(char::is_whitespace) {
+            Some(end) => cut[..end].trim_end().to_string(),
+            None => cut,
+        },
+    }
+}
+
+/// Rebuild a print ad's text after its body was trimmed
+pub fn format_print_ad(headline: &str, body: &str) -> String {
+    format!("HEADLINE: {}\nBODY: {}", headline, body)
+}
+
+/// Text before the first `---MARKER---` line, then each marker with the
+/// text under it
+fn split_blocks(text: &str) -> (String, Vec<(String, String)>) {
+    let mut preamble = String::new();
+    let mut blocks: Vec<(String, String)> = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let marker = trimmed
+            .strip_prefix("---")
+            .and_then(|m| m.strip_suffix("---"))
+            .map(str::trim)
+            .filter(|m| !m.is_empty());
+        match (marker, blocks.last_mut()) {
+            (Some(marker), _) => blocks.push((marker.to_string(), String::new())),
+            (None, Some((_, body))) => {
+                body.push_str(line);
+                body.push('\n');
+            }
+            (None, None) => {
+                preamble.push_str(line);
+                preamble.push('\n');
+            }
+        }
+    }
+    (preamble, blocks)
+}
+
+/// A `NAME: value` line
+fn field(text: &str, name: &str) -> Option<String> {
+    text.lines()
+        .find_map(|line| strip_label(line.trim(), name))
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// A `NAME: value` field whose value runs to the next label or the end
+fn multiline_field(text: &str, name: &str) -> Option<String> {
+    let mut lines = text.lines();
+    let first = lines.find_map(|line| strip_label(line.trim(), name))?;
+    let mut value = first.trim().to_string();
+    for line in lines {
+        if is_label(line.trim()) {
+            break;
+        }
+        value.push('\n');
+        value.push_str(line.trim_end());
+    }
+    let value = value.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+fn strip_label<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let rest = line.get(..name.len())?;
+    if !rest.eq_ignore_ascii_case(name) {
+        return None;
+    }
+    line[name.len()..].trim_start().strip_prefix(':')
+}
+
+/// An all-caps `LABEL:` at the start of a line
+fn is_label(line: &str) -> bool {
+    line.split_once(':').is_some_and(|(label, _)| {
+        !label.is_empty()
+            && label
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c == ' ' || c == '_')
+    })
+}
+
+fn parse_scene(body: &str) -> Scene {
+    let mut shots = Vec::new();
+    let mut in_shots = false;
+    for line in body.lines().map(str::trim) {
+        if strip_label(line, "SHOTS").is_some() {
+            in_shots = true;
+        } else if is_label(line) {
+            in_shots = false;
+        } else if in_shots {
+            if let Some(shot) = bullet(line) {
+                shots.push(shot);
+            }
+        }
+    }
+    Scene {
+        location: field(body, "LOCATION").unwrap_or_default(),
+        duration_seconds: field(body, "DURATION").and_then(|d| {
+            d.trim_end_matches(|c: char| !c.is_ascii_digit())
+                .parse()
+                .ok()
+        }),
+        shots,
+        voiceover: multiline_field(body, "VOICEOVER").unwrap_or_default(),
+    }
+}
+
+/// Blocks marked `NAME: heading`, split into paragraphs and bullet points
+fn sections(blocks: &[(String, String)], name: &str) -> Vec<Section> {
+    blocks
+        .iter()
+        .filter_map(|(marker, body)| {
+            let heading = strip_label(marker, name)?.trim().to_string();
+            let mut paragraphs = Vec::new();
+            let mut bullets = Vec::new();
+            for paragraph in body.split("\n\n") {
+                let mut text = Vec::new();
+                for line in paragraph.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                    match bullet(line) {
+                        Some(item) => bullets.push(item),
+                        None => text.push(line),
+                    }
+                }
+                if !text.is_empty() {
+                    paragraphs.push(text.join(" "));
+                }
+            }
+            Some(Section {
+                heading,
+                paragraphs,
+                bullets,
+            })
+        })
+        .collect()
+}
+
+fn bullet(line: &str) -> Option<String> {
+    line.strip_prefix("- ")
+        .or_else(|| line.strip_prefix("• "))
+        .or_else(|| line.strip_prefix("* "))
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generation_type_round_trip() {
+        for kind in LongFormKind::ALL {
+            assert_eq!(
+                LongFormKind::from_generation_type(kind.generation_type()),
+                Some(kind)
+            );
+        }
+        assert_eq!(LongFormKind::from_generation_type("listing"), None);
+    }
+
+    #[test]
+    fn test_parse_video_script() {
+        let text = "TITLE: A Morning at 123 Oak\n\n---SCENE 1---\nLOCATION: Front exterior\nDURATION: 8s\nSHOTS:\n- Drone push-in on the facade\n- Slow pan across the porch\nVOICEOVER: Tucked on a quiet street,\nthis home opens with light.\n\n---SCENE 2---\nLOCATION: Kitchen\nDURATION: 12\nSHOTS:\n- Gimbal walk past the island\nVOICEOVER: The chef's kitchen is built for gathering.\n";
+        let LongFormDocument::VideoScript {
+            title,
+            scenes,
+            total_seconds,
+        } = parse(LongFormKind::VideoScript, text).unwrap()
+        else {
+            panic!("expected a video script");
+        };
+        assert_eq!(title, "A Morning at 123 Oak");
+        assert_eq!(scenes.len(), 2);
+        assert_eq!(scenes[0].location, "Front exterior");
+        assert_eq!(scenes[0].shots.len(), 2);
+        assert_eq!(
+            scenes[0].voiceover,
+            "Tucked on a quiet street,\nthis home opens with light."
+        );
+        assert_eq!(total_seconds, 20);
+
+        assert!(parse(LongFormKind::VideoScript, "Just prose.").is_err());
+    }
+
+    #[test]
+    fn test_parse_sectioned_kinds() {
+        let sheet = "TITLE: 123 Oak Street\n---ROOM: Kitchen---\n- Quartz counters\n- Viking range\n---ROOM: Primary Suite---\n- Walk-in closet\n";
+        let LongFormDocument::FeatureSheet { rooms, .. } =
+            parse(LongFormKind::FeatureSheet, sheet).unwrap()
+        else {
+            panic!("expected a feature sheet");
+        };
+        assert_eq!(rooms[0].heading, "Kitchen");
+        assert_eq!(rooms[0].bullets, vec!["Quartz counters", "Viking range"]);
+        assert_eq!(rooms[1].heading, "Primary Suite");
+
+        let site = "HEADLINE: Light-Filled Living in Mission Bay\nTAGLINE: Three beds, one block from the park\n---SECTION: The Home---\nFirst paragraph\ncontinues here.\n\nSecond paragraph.\n";
+        let LongFormDocument::WebsiteCopy {
+            headline, sections, ..
+        } = parse(LongFormKind::WebsiteCopy, site).unwrap()
+        else {
+            panic!("expected website copy");
+        };
+        assert_eq!(headline, "Light-Filled Living in Mission Bay");
+        assert_eq!(
+            sections[0].paragraphs,
+            vec!["First paragraph continues here.", "Second paragraph."]
+        );
+
+        // Website copy needs its headline
+        assert!(parse(LongFormKind::WebsiteCopy, "---SECTION: Home---\nText\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_print_ad() {
+        let ad = "HEADLINE: Just Listed in Mission Bay\nBODY: Three beds, chef's kitchen.\nCall Jane today.";
+        assert_eq!(
+            parse(LongFormKind::PrintAd, ad).unwrap(),
+            LongFormDocument::PrintAd {
+                headline: "Just Listed in Mission Bay".to_string(),
+                body: "Three beds, chef's kitchen.\nCall Jane today.".to_string(),
+                characters: 44,
+            }
+        );
+    }
+
+    #[test]
+    fn test_trim_to_limit() {
+        let body = "Three beds. Chef's kitchen! Walk to the park and the bay.";
+        assert_eq!(trim_to_limit(body, 100), body);
+        assert_eq!(trim_to_limit(body, 30), "Three beds. Chef's kitchen!");
+        assert_eq!(trim_to_limit("Spacious sunlit rooms", 12), "Spacious");
+    }
+}
@@ -0,0 +1,184 @@
+use tauri::ipc::Channel;
+
+use crate::db::photos::Photo;
+use crate::db::properties::Property;
+use crate::error::AppError;
+
+use super::client::{calculate_cost_cents, ClaudeClient, StreamEvent};
+use super::listing_generator::{analyze_property, GenerationResult};
+use super::long_form::{self, LongFormDocument, LongFormKind, MAX_PRINT_AD_HEADLINE};
+use super::prompts::{
+    build_feature_sheet_prompt, build_neighborhood_guide_prompt, build_print_ad_prompt,
+    build_print_ad_retry, build_video_script_prompt, build_website_copy_prompt, AgentInfo,
+    MAX_TOKENS_LONG_FORM, MAX_TOKENS_PRINT_AD,
+};
+
+/// Two-stage long-form generation pipeline:
+/// 1. Analyze property (non-streaming) -> structured JSON
+/// 2. Generate the copy for `kind` (streaming) -> text deltas via Channel
+///
+/// A print ad whose body runs over `char_limit` is rewritten once, then cut
+/// at the last sentence that fits, so the saved ad is always within it. An
+/// ad that doesn't come back as a headline and body is rejected.
+#[allow(clippy::too_many_arguments)]
+pub async fn generate_long_form(
+    client: &ClaudeClient,
+    property: &Property,
+    kind: LongFormKind,
+    photos: &[Photo],
+    char_limit: usize,
+    brand_voice_block: Option<&str>,
+    language: &str,
+    agent_info: &AgentInfo,
+    channel: &Channel<StreamEvent>,
+) -> Result<GenerationResult, AppError> {
+    // Stage 1: Property analysis (non-streaming)
+    let analysis = analyze_property(client, property).await?;
+    let analysis_text = &analysis.analysis_json;
+
+    // Stage 2: Generate the copy (streaming)
+    let (system, user) = match kind {
+        LongFormKind::VideoScript => build_video_script_prompt(
+            property,
+            analysis_text,
+            photos,
+            brand_voice_block,
+            language,
+            agent_info,
+        ),
+        LongFormKind::WebsiteCopy => build_website_copy_prompt(
+            property,
+            analysis_text,
+            brand_voice_block,
+            language,
+            agent_info,
+        ),
+        LongFormKind::FeatureSheet => build_feature_sheet_prompt(
+            property,
+            analysis_text,
+            photos,
+            brand_voice_block,
+            language,
+            agent_info,
+        ),
+        LongFormKind::NeighborhoodGuide => build_neighborhood_guide_prompt(
+            property,
+            analysis_text,
+            brand_voice_block,
+            language,
+            agent_info,
+        ),
+        LongFormKind::PrintAd => build_print_ad_prompt(
+            property,
+            analysis_text,
+            char_limit,
+            brand_voice_block,
+            language,
+            agent_info,
+        ),
+    };
+    let max_tokens = match kind {
+        LongFormKind::PrintAd => MAX_TOKENS_PRINT_AD,
+        _ => MAX_TOKENS_LONG_FORM,
+    };
+
+    let (mut full_text, mut total_input, mut total_output) = client
+        .stream_message(&system, &user, max_tokens, channel)
+        .await?;
+    total_input += analysis.input_tokens;
+    total_output += analysis.output_tokens;
+
+    if kind == LongFormKind::PrintAd {
+        if let Some(characters) = over_limit(&full_text, char_limit) {
+            let retry = build_print_ad_retry(&user, &full_text, characters, char_limit);
+            let (shorter, input, output) = client.send_message(&system, &retry, max_tokens).await?;
+            total_input += input;
+            total_output += output;
+            full_text = shorter;
+        }
+        full_text = fit_print_ad(&full_text, char_limit)?;
+    }
+
+    let cost_cents = calculate_cost_cents(total_input, total_output);
+
+    // Send finished event
+    let _ = channel.send(StreamEvent::Finished {
+        full_text: full_text.clone(),
+        input_tokens: total_input,
+        output_tokens: total_output,
+        cost_cents,
+    });
+
+    Ok(GenerationResult {
+        full_text,
+        input_tokens: total_input,
+        output_tokens: total_output,
+        cost_cents,
+        analysis_json: analysis.analysis_json,
+    })
+}
+
+/// The body's length when a print ad runs over its limit
+fn over_limit(text: &str, char_limit: usize) -> Option<usize> {
+    match long_form::parse(LongFormKind::PrintAd, text) {
+        Ok(LongFormDocument::PrintAd { characters, .. }) if characters > char_limit => {
+            Some(characters)
+        }
+        _ => None,
+    }
+}
+
+/// Trim a print ad's headline and body to their limits. Text without a
+/// headline and body can't be measured, so it's an error rather than being
+/// saved over the limit.
+fn fit_print_ad(text: &str, char_limit: usize) -> Result<String, AppError> {
+    match long_form::parse(LongFormKind::PrintAd, text) {
+        Ok(LongFormDocument::PrintAd {
+            headline,
+            body,
+            characters,
+        }) => {
+            if characters <= char_limit && headline.chars().count() <= MAX_PRINT_AD_HEADLINE {
+                return Ok(text.trim().to_string());
+            }
+            Ok(long_form::format_print_ad(
+                &long_form::trim_to_limit(&headline, MAX_PRINT_AD_HEADLINE),
+                &long_form::trim_to_limit(&body, char_limit),
+            ))
+        }
+        _ => Err(AppError::Api(
+            "The print ad didn't come back as a headline and body, so it can't be fitted to the character limit. Try generating it again.".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_print_ad() {
+        let ad = "HEADLINE: Just Listed\nBODY: Three beds. Chef's kitchen. Walk to the park.";
+        assert_eq!(over_limit(ad, 100), None);
+        assert_eq!(fit_print_ad(ad, 100).unwrap(), ad);
+
+        assert_eq!(over_limit(ad, 30), Some(45));
+        assert_eq!(
+            fit_print_ad(ad, 30).unwrap(),
+            "HEADLINE: Just Listed\nBODY: Three beds. Chef's kitchen."
+        );
+
+        let long_headline = format!("HEADLINE: {}\nBODY: Three beds.", "Sunny ".repeat(20));
+        let fitted = fit_print_ad(&long_headline, 100).unwrap();
+        let headline = fitted
+            .lines()
+            .next()
+            .unwrap()
+            .trim_start_matches("HEADLINE: ");
+        assert!(headline.chars().count() <= MAX_PRINT_AD_HEADLINE);
+        assert!(fitted.ends_with("BODY: Three beds."));
+
+        assert_eq!(over_limit("Just listed: three beds.", 10), None);
+        assert!(fit_print_ad("Just listed: three beds.", 10).is_err());
+    }
+}
@@ -1,6 +1,14 @@
 pub mod brand_voice;
 pub mod client;
 pub mod email_generator;
+pub mod fact_check;
 pub mod listing_generator;
+pub mod long_form;
+pub mod long_form_generator;
+pub mod photo_analyzer;
+pub mod photo_caption_generator;
+pub mod price_improvement_generator;
 pub mod prompts;
 pub mod social_generator;
+pub mod translator;
+pub mod voice_score;
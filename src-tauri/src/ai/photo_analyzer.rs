@@ -0,0 +1,315 @@
+use std::path::PathBuf;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::db::photos::{Photo, ROOMS};
+use crate::db::properties::Property;
+use crate::error::AppError;
+use crate::photos::manager;
+
+use super::client::{calculate_cost_cents, strip_code_fences, ClaudeClient, ImageInput};
+use super::prompts::{build_photo_analysis_prompt, MAX_TOKENS_PHOTO_ANALYSIS};
+
+/// Photos sent per request. Keeps each request well under the API's image
+/// and payload limits.
+const BATCH_SIZE: usize = 5;
+
+/// Most feature suggestions offered to the agent at once
+const MAX_SUGGESTIONS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoAnalysis {
+    #[serde(alias = "photo_id")]
+    pub photo_id: String,
+    /// One of `photos::ROOMS`
+    pub room: String,
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// 1-10
+    #[serde(alias = "quality_score")]
+    pub quality_score: i64,
+    #[serde(default, alias = "quality_notes")]
+    pub quality_notes: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoAnalysisReport {
+    pub photos: Vec<PhotoAnalysis>,
+    /// Detected features not yet in the property's `key_features`, most
+    /// frequently seen first
+    pub suggested_features: Vec<String>,
+    /// Photos left out because their files couldn't be read
+    pub skipped: Vec<SkippedPhoto>,
+    pub tokens_used: u32,
+    pub cost_cents: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedPhoto {
+    pub photo_id: String,
+    pub error: String,
+}
+
+/// Send photos to Claude as image blocks, in batches, and collect a room,
+/// features and quality score for each
+pub async fn analyze_photos(
+    client: &ClaudeClient,
+    property: &Property,
+    photos: &[Photo],
+) -> Result<PhotoAnalysisReport, AppError> {
+    let mut analyses = Vec::new();
+    let mut skipped = Vec::new();
+    let mut input_tokens = 0;
+    let mut output_tokens = 0;
+
+    for batch in photos.chunks(BATCH_SIZE) {
+        let paths: Vec<PathBuf> = batch
+            .iter()
+            .map(|p| PathBuf::from(&p.original_path))
+            .collect();
+        let encoded = tokio::task::spawn_blocking(move || {
+            paths
+                .iter()
+                .map(|path| {
+                    manager::encode_for_analysis(path).map(|bytes| ImageInput {
+                        media_type: "image/jpeg".to_string(),
+                        data: BASE64.encode(bytes),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|e| AppError::Photo(format!("Image encoding thread error: {}", e)))?;
+
+        // A missing or corrupt file is reported rather than failing the
+        // rest of the batch
+        let mut images = Vec::with_capacity(batch.len());
+        let mut readable = Vec::with_capacity(batch.len());
+        for (photo, image) in batch.iter().zip(encoded) {
+            match image {
+                Ok(image) => {
+                    images.push(image);
+                    readable.push(photo.clone());
+                }
+                Err(e) => skipped.push(SkippedPhoto {
+                    photo_id: photo.id.clone(),
+                    error: e.to_string(),
+                }),
+            }
+        }
+        if readable.is_empty() {
+            continue;
+        }
+        let batch = readable.as_slice();
+
+        let (system, user) = build_photo_analysis_prompt(property, batch);
+        let (response, input, output) = client
+            .send_message_with_images(&system, &images, &user, MAX_TOKENS_PHOTO_ANALYSIS)
+            .await?;
+        input_tokens += input;
+        output_tokens += output;
+
+        analyses.extend(parse_analyses(&response, batch)?);
+    }
+
+    let existing: Vec<String> = serde_json::from_str(&property.key_features).unwrap_or_default();
+    let suggested_features = suggest_features(&existing, &analyses);
+
+    Ok(PhotoAnalysisReport {
+        photos: analyses,
+        suggested_features,
+        skipped,
+        tokens_used: input_tokens + output_tokens,
+        cost_cents: calculate_cost_cents(input_tokens, output_tokens),
+    })
+}
+
+/// Parse the model's JSON for one batch, keeping only photos in the batch
+/// and normalizing rooms and scores
+fn parse_analyses(response: &str, batch: &[Photo]) -> Result<Vec<PhotoAnalysis>, AppError> {
+    let parsed: Vec<PhotoAnalysis> = serde_json::from_str(strip_code_fences(response))
+        .map_err(|e| AppError::Api(format!("Failed to parse photo analysis as JSON: {}", e)))?;
+
+    Ok(batch
+        .iter()
+        .filter_map(|photo| {
+            let analysis = parsed.iter().find(|a| a.photo_id == photo.id)?;
+            let room = analysis.room.trim().to_lowercase().replace(' ', "_");
+            Some(PhotoAnalysis {
+                photo_id: photo.id.clone(),
+                room: if ROOMS.contains(&room.as_str()) {
+                    room
+                } else {
+                    "other".to_string()
+                },
+                features: analysis
+                    .features
+                    .iter()
+                    .map(|f| f.trim().to_string())
+                    .filter(|f| !f.is_empty())
+                    .collect(),
+                quality_score: analysis.quality_score.clamp(1, 10),
+                quality_notes: analysis.quality_notes.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Features seen in the photos that the property doesn't list yet, most
+/// frequently seen first. A feature counts as listed if either phrase
+/// contains the other, so "quartz counters" is not offered next to
+/// "quartz counters in kitchen".
+fn suggest_features(existing: &[String], analyses: &[PhotoAnalysis]) -> Vec<String> {
+    let existing: Vec<String> = existing.iter().map(|f| f.to_lowercase()).collect();
+    let mut counts: Vec<(String, usize)> = Vec::new();
+
+    for feature in analyses.iter().flat_map(|a| &a.features) {
+        let key = feature.to_lowercase();
+        if existing
+            .iter()
+            .any(|e| e.contains(&key) || key.contains(e.as_str()))
+        {
+            continue;
+        }
+        match counts.iter_mut().find(|(f, _)| f.to_lowercase() == key) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((feature.clone(), 1)),
+        }
+    }
+
+    // Stable sort keeps first-seen order among equal counts
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(feature, _)| feature)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn photo(id: &str, path: &str) -> Photo {
+        Photo {
+            id: id.to_string(),
+            filename: format!("{}.jpg", id),
+            original_path: path.to_string(),
+            ..crate::db::test_photo()
+        }
+    }
+
+    fn property(key_features: &str) -> Property {
+        Property {
+            key_features: key_features.to_string(),
+            ..crate::db::test_property()
+        }
+    }
+
+    fn analysis(id: &str, features: &[&str]) -> PhotoAnalysis {
+        PhotoAnalysis {
+            photo_id: id.to_string(),
+            room: "kitchen".to_string(),
+            features: features.iter().map(|f| f.to_string()).collect(),
+            quality_score: 7,
+            quality_notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_suggest_features_skips_listed_and_ranks_by_count() {
+        let existing = vec!["Quartz countertops in kitchen".to_string()];
+        let analyses = vec![
+            analysis("a", &["quartz countertops", "Skylights"]),
+            analysis("b", &["Vaulted ceilings", "skylights"]),
+            analysis("c", &["vaulted ceilings", "Skylights"]),
+        ];
+
+        assert_eq!(
+            suggest_features(&existing, &analyses),
+            vec!["Skylights", "Vaulted ceilings"]
+        );
+    }
+
+    #[test]
+    fn test_parse_analyses_normalizes_output() {
+        let response = r#"[
+            {"photo_id": "a", "room": "Living Room", "features": [" fireplace ", ""], "quality_score": 14, "quality_notes": "Too dark"},
+            {"photo_id": "b", "room": "ballroom", "features": [], "quality_score": 5},
+            {"photo_id": "other", "room": "kitchen", "features": [], "quality_score": 5}
+        ]"#;
+        let parsed = parse_analyses(response, &[photo("a", ""), photo("b", "")]).unwrap();
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].room, "living_room");
+        assert_eq!(parsed[0].features, vec!["fireplace"]);
+        assert_eq!(parsed[0].quality_score, 10);
+        assert_eq!(parsed[1].room, "other");
+    }
+
+    #[tokio::test]
+    async fn test_analyze_photos_against_mock_api() {
+        let dir = std::env::temp_dir().join(format!("realestate-vision-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("kitchen.png");
+        image::RgbImage::new(2000, 1500).save(&path).unwrap();
+
+        let reply = serde_json::json!([{
+            "photo_id": "k1",
+            "room": "kitchen",
+            "features": ["quartz countertops", "pendant lighting"],
+            "quality_score": 8,
+            "quality_notes": ""
+        }]);
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/messages")
+            .match_header("x-api-key", "test-key")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(r#""type":"image""#.to_string()),
+                Matcher::Regex(r#""media_type":"image/jpeg""#.to_string()),
+                Matcher::Regex("photo_id: k1".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "content": [{"type": "text", "text": reply.to_string()}],
+                    "usage": {"input_tokens": 1500, "output_tokens": 100}
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = ClaudeClient::new("test-key".to_string(), "test-model".to_string())
+            .with_base_url(server.url());
+        let report = analyze_photos(
+            &client,
+            &property(r#"["Pendant lighting"]"#),
+            &[
+                photo("k1", &path.to_string_lossy()),
+                photo("gone", &dir.join("gone.jpg").to_string_lossy()),
+            ],
+        )
+        .await
+        .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(report.photos.len(), 1);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].photo_id, "gone");
+        assert_eq!(report.photos[0].room, "kitchen");
+        assert_eq!(report.photos[0].quality_score, 8);
+        assert_eq!(report.suggested_features, vec!["quartz countertops"]);
+        assert_eq!(report.tokens_used, 1600);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
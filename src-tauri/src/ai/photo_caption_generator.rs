@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db::photos::Photo;
+use crate::db::properties::Property;
+use crate::error::AppError;
+
+use super::client::{strip_code_fences, ClaudeClient};
+use super::prompts::{build_photo_caption_prompt, MAX_TOKENS_PHOTO_CAPTIONS};
+
+/// A drafted caption and alt text for one photo, for the agent to review
+/// before saving
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptionDraft {
+    #[serde(alias = "photo_id")]
+    pub photo_id: String,
+    pub caption: String,
+    #[serde(alias = "alt_text")]
+    pub alt_text: String,
+}
+
+/// Draft captions and alt text for the given photos from the property data
+/// and each photo's position and room tag
+pub async fn draft_captions(
+    client: &ClaudeClient,
+    property: &Property,
+    photos: &[Photo],
+) -> Result<Vec<CaptionDraft>, AppError> {
+    if photos.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let (system, user) = build_photo_caption_prompt(property, photos);
+    let (response, _input_tokens, _output_tokens) = client
+        .send_message(&system, &user, MAX_TOKENS_PHOTO_CAPTIONS)
+        .await?;
+
+    parse_drafts(&response, photos)
+}
+
+/// Parse the model's JSON, keeping only drafts for photos that were asked
+/// about, in photo order
+fn parse_drafts(response: &str, photos: &[Photo]) -> Result<Vec<CaptionDraft>, AppError> {
+    let drafts: Vec<CaptionDraft> = serde_json::from_str(strip_code_fences(response))
+        .map_err(|e| AppError::Api(format!("Failed to parse photo captions as JSON: {}", e)))?;
+
+    Ok(photos
+        .iter()
+        .filter_map(|photo| {
+            drafts
+                .iter()
+                .find(|d| d.photo_id == photo.id)
+                .map(|d| CaptionDraft {
+                    photo_id: photo.id.clone(),
+                    caption: d.caption.trim().to_string(),
+                    alt_text: d.alt_text.trim().to_string(),
+                })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn photo(id: &str) -> Photo {
+        Photo {
+            id: id.to_string(),
+            filename: format!("{}.jpg", id),
+            ..crate::db::test_photo()
+        }
+    }
+
+    #[test]
+    fn test_parse_drafts_matches_requested_photos() {
+        let response = r#"```json
+[
+  {"photo_id": "b", "caption": " Chef's kitchen ", "alt_text": "Kitchen with island"},
+  {"photo_id": "zzz", "caption": "Made up", "alt_text": "Made up"},
+  {"photo_id": "a", "caption": "Welcome home", "alt_text": "Front of a two-story house"}
+]
+```"#;
+        let drafts = parse_drafts(response, &[photo("a"), photo("b")]).unwrap();
+        assert_eq!(drafts.len(), 2);
+        assert_eq!(drafts[0].photo_id, "a");
+        assert_eq!(drafts[1].caption, "Chef's kitchen");
+    }
+
+    #[test]
+    fn test_parse_drafts_rejects_invalid_json() {
+        assert!(parse_drafts("Here are your captions!", &[photo("a")]).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use tauri::ipc::Channel;
+
+use crate::db::price_history::PriceChange;
+use crate::db::properties::Property;
+use crate::error::AppError;
+
+use super::client::{calculate_cost_cents, ClaudeClient, StreamEvent};
+use super::listing_generator::{GenerationResult, PropertyAnalysis};
+use super::prompts::{
+    build_analysis_prompt, build_price_improvement_prompt, AgentInfo, MAX_TOKENS_ANALYSIS,
+    MAX_TOKENS_EMAIL, MAX_TOKENS_SOCIAL,
+};
+
+/// Channels a price-improvement campaign can be written for
+pub const CHANNELS: &[&str] = &["email", "instagram", "facebook", "linkedin"];
+
+/// Two-stage price-improvement generation pipeline:
+/// 1. Analyze property (non-streaming) -> structured JSON
+/// 2. Generate the email or social posts (streaming) -> text deltas via Channel
+#[allow(clippy::too_many_arguments)]
+pub async fn generate_price_improvement(
+    client: &ClaudeClient,
+    property: &Property,
+    target: &str,
+    change: &PriceChange,
+    brand_voice_block: Option<&str>,
+    language: &str,
+    agent_info: &AgentInfo,
+    channel: &Channel<StreamEvent>,
+) -> Result<GenerationResult, AppError> {
+    // Stage 1: Property analysis (non-streaming)
+    let (analysis_system, analysis_user) = build_analysis_prompt(property);
+    let (analysis_text, analysis_input, analysis_output) = client
+        .send_message(&analysis_system, &analysis_user, MAX_TOKENS_ANALYSIS)
+        .await?;
+
+    // Validate we got valid JSON
+    let _parsed: PropertyAnalysis = serde_json::from_str(&analysis_text).map_err(|e| {
+        AppError::Api(format!(
+            "Failed to parse property analysis from Claude: {}",
+            e
+        ))
+    })?;
+
+    // Stage 2: Generate the campaign copy (streaming)
+    let (system, user) = build_price_improvement_prompt(
+        property,
+        &analysis_text,
+        target,
+        change,
+        brand_voice_block,
+        language,
+        agent_info,
+    );
+    let max_tokens = if target == "email" {
+        MAX_TOKENS_EMAIL
+    } else {
+        MAX_TOKENS_SOCIAL
+    };
+
+    let (full_text, gen_input, gen_output) = client
+        .stream_message(&system, &user, max_tokens, channel)
+        .await?;
+
+    let total_input = analysis_input + gen_input;
+    let total_output = analysis_output + gen_output;
+    let cost_cents = calculate_cost_cents(total_input, total_output);
+
+    // Send finished event
+    let _ = channel.send(StreamEvent::Finished {
+        full_text: full_text.clone(),
+        input_tokens: total_input,
+        output_tokens: total_output,
+        cost_cents,
+    });
+
+    Ok(GenerationResult {
+        full_text,
+        input_tokens: total_input,
+        output_tokens: total_output,
+        cost_cents,
+        analysis_json: analysis_text,
+    })
+}
@@ -1,10 +1,68 @@
+use super::long_form::MAX_PRINT_AD_HEADLINE;
+use crate::db::agents::Agent;
+use crate::db::brand_voice::VoiceProfile;
+use crate::db::photos::{Photo, ROOMS};
+use crate::db::price_history::PriceChange;
+use crate::db::prompt_presets::PromptPreset;
 use crate::db::properties::Property;
 
+/// Built-in keys; anything else must be a prompt preset
+pub const STYLES: &[&str] = &["luxury", "family", "investment", "first_time"];
+pub const TONES: &[&str] = &["professional", "warm", "exciting"];
+pub const LENGTHS: &[&str] = &["short", "medium", "long"];
+pub const PLATFORMS: &[&str] = &["instagram", "facebook", "linkedin"];
+pub const EMAIL_TEMPLATES: &[&str] = &["buyer", "seller", "open_house"];
+
+/// Languages copy can be written or translated in, as (code, name)
+pub const LANGUAGES: &[(&str, &str)] = &[
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("zh", "Simplified Chinese"),
+    ("vi", "Vietnamese"),
+];
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Variables a prompt preset can use as `{{name}}`
+pub const PRESET_VARIABLES: &[&str] = &[
+    "address",
+    "city",
+    "state",
+    "zip",
+    "property_type",
+    "beds",
+    "baths",
+    "sqft",
+    "price",
+    "year_built",
+    "lot_size",
+    "parking",
+    "key_features",
+    "neighborhood",
+    "school_district",
+    "agent_name",
+    "agent_phone",
+    "agent_email",
+    "brokerage",
+];
+
 pub struct AgentInfo {
     pub name: String,
     pub phone: String,
     pub email: String,
     pub brokerage: String,
+    pub signature: String,
+}
+
+impl From<&Agent> for AgentInfo {
+    fn from(agent: &Agent) -> Self {
+        Self {
+            name: agent.name.clone(),
+            phone: agent.phone.clone(),
+            email: agent.email.clone(),
+            brokerage: agent.brokerage_name.clone(),
+            signature: agent.signature.clone().unwrap_or_default(),
+        }
+    }
 }
 
 pub struct GenerationOptions {
@@ -12,6 +70,13 @@ pub struct GenerationOptions {
     pub tone: String,
     pub length: String,
     pub seo_keywords: Vec<String>,
+    /// Rendered preset instructions for a custom style, tone or length,
+    /// used in place of the built-in ones
+    pub custom_style: Option<String>,
+    pub custom_tone: Option<String>,
+    pub custom_length: Option<String>,
+    /// Language code from `LANGUAGES`
+    pub language: String,
 }
 
 /// Build the property analysis prompt (Stage 1 - non-streaming)
@@ -26,35 +91,7 @@ pub fn build_analysis_prompt(property: &Property) -> (String, String) {
 Respond with ONLY valid JSON. No markdown, no explanation."#
         .to_string();
 
-    let key_features: Vec<String> =
-        serde_json::from_str(&property.key_features).unwrap_or_default();
-    let highlights: Vec<String> =
-        serde_json::from_str(&property.neighborhood_highlights).unwrap_or_default();
-    let amenities: Vec<String> =
-        serde_json::from_str(&property.nearby_amenities).unwrap_or_default();
-
-    let price_formatted = format_price(property.price);
-
-    let user = format!(
-        "Property: {}, {}, {} {}\nType: {} | Built: {}\n{} bed / {} bath / {} sqft\nPrice: ${}\nLot: {} | Parking: {}\nKey Features: {}\nNeighborhood: {}\nHighlights: {}\nSchools: {}\nNearby: {}",
-        property.address,
-        property.city,
-        property.state,
-        property.zip,
-        property.property_type.replace('_', " "),
-        property.year_built.map_or("N/A".to_string(), |y| y.to_string()),
-        property.beds,
-        property.baths,
-        property.sqft,
-        price_formatted,
-        property.lot_size.as_deref().unwrap_or("N/A"),
-        property.parking.as_deref().unwrap_or("N/A"),
-        key_features.join(", "),
-        property.neighborhood.as_deref().unwrap_or("N/A"),
-        highlights.join(", "),
-        property.school_district.as_deref().unwrap_or("N/A"),
-        amenities.join(", "),
-    );
+    let user = format_property_details(property);
 
     (system, user)
 }
@@ -67,11 +104,22 @@ pub fn build_listing_prompt(
     brand_voice_block: Option<&str>,
     agent_info: &AgentInfo,
 ) -> (String, String) {
-    let style_instructions = get_style_instructions(&options.style);
-    let tone_instructions = get_tone_instructions(&options.tone);
-    let length_instructions = get_length_instructions(&options.length);
+    let style_instructions = options
+        .custom_style
+        .as_deref()
+        .unwrap_or_else(|| get_style_instructions(&options.style));
+    let tone_instructions = options
+        .custom_tone
+        .as_deref()
+        .unwrap_or_else(|| get_tone_instructions(&options.tone));
+    let length_instructions = options
+        .custom_length
+        .as_deref()
+        .unwrap_or_else(|| get_length_instructions(&options.length));
 
     let brand_block = brand_voice_block.unwrap_or("");
+    let language_block = get_language_instructions(&options.language);
+    let status_block = get_status_instructions(property);
     let seo_keywords = if options.seo_keywords.is_empty() {
         "none specified".to_string()
     } else {
@@ -95,6 +143,8 @@ TONE: {tone_instructions}
 LENGTH: {length_instructions}
 
 {brand_block}
+{status_block}
+{language_block}
 
 RULES:
 - NEVER fabricate features not provided in the property data
@@ -158,16 +208,24 @@ Write the listing description now."#,
     (system, user)
 }
 
-/// Build social media generation prompt
+/// Build social media generation prompt. A custom platform's preset, with
+/// its instructions already rendered, replaces the built-in instructions.
 pub fn build_social_prompt(
     property: &Property,
     analysis_json: &str,
     platform: &str,
+    custom: Option<&PromptPreset>,
     brand_voice_block: Option<&str>,
+    language: &str,
     agent_info: &AgentInfo,
 ) -> (String, String) {
-    let platform_instructions = get_platform_instructions(platform);
+    let (platform, platform_instructions) = match custom {
+        Some(preset) => (preset.name.as_str(), preset.instructions.as_str()),
+        None => (platform, get_platform_instructions(platform)),
+    };
     let brand_block = brand_voice_block.unwrap_or("");
+    let language_block = get_language_instructions(language);
+    let status_block = get_status_instructions(property);
 
     let agent_cta = if !agent_info.name.is_empty() {
         format!(
@@ -184,6 +242,8 @@ pub fn build_social_prompt(
 {platform_instructions}
 
 {brand_block}
+{status_block}
+{language_block}
 
 RULES:
 - NEVER fabricate features not in the property data
@@ -216,16 +276,32 @@ OUTPUT FORMAT:
     (system, user)
 }
 
-/// Build email generation prompt
+/// Build email generation prompt. A custom template's preset, with its
+/// instructions already rendered, replaces the built-in instructions.
 pub fn build_email_prompt(
     property: &Property,
     analysis_json: &str,
     template_type: &str,
+    custom: Option<&PromptPreset>,
     brand_voice_block: Option<&str>,
+    language: &str,
     agent_info: &AgentInfo,
 ) -> (String, String) {
-    let template_instructions = get_template_instructions(template_type);
+    let (template_type, template_instructions) = match custom {
+        Some(preset) => (preset.name.as_str(), preset.instructions.as_str()),
+        None => (template_type, get_template_instructions(template_type)),
+    };
     let brand_block = brand_voice_block.unwrap_or("");
+    let language_block = get_language_instructions(language);
+    let status_block = get_status_instructions(property);
+    let signature_rule = if agent_info.signature.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "\n- End the email with this signature exactly as written:\n{}",
+            agent_info.signature
+        )
+    };
 
     let system = format!(
         r#"You are an email marketing specialist for real estate. Write a {template_type} email.
@@ -233,6 +309,8 @@ pub fn build_email_prompt(
 {template_instructions}
 
 {brand_block}
+{status_block}
+{language_block}
 
 RULES:
 - Subject line: 6-10 words, creates curiosity or urgency, no ALL CAPS, no spam trigger words
@@ -240,7 +318,7 @@ RULES:
 - Body: Personal, actionable, scannable (short paragraphs, bold key details)
 - Include property details naturally, don't dump raw data
 - End with one clear CTA (not multiple competing CTAs)
-- Sign off with agent name: {}, phone: {}, email: {}
+- Sign off with agent name: {}, phone: {}, email: {}{signature_rule}
 
 OUTPUT FORMAT:
 SUBJECT: {{subject line}}
@@ -265,6 +343,62 @@ PREVIEW: {{preview text}}
     (system, user)
 }
 
+/// Build a "Price Improvement" email or social prompt. `channel` is `email`
+/// or a social platform; the latest price change is passed to the model so
+/// the copy can reference the old and new price.
+pub fn build_price_improvement_prompt(
+    property: &Property,
+    analysis_json: &str,
+    channel: &str,
+    change: &PriceChange,
+    brand_voice_block: Option<&str>,
+    language: &str,
+    agent_info: &AgentInfo,
+) -> (String, String) {
+    let (system, user) = if channel == "email" {
+        build_email_prompt(
+            property,
+            analysis_json,
+            "price_improvement",
+            None,
+            brand_voice_block,
+            language,
+            agent_info,
+        )
+    } else {
+        build_social_prompt(
+            property,
+            analysis_json,
+            channel,
+            None,
+            brand_voice_block,
+            language,
+            agent_info,
+        )
+    };
+
+    let system = format!(
+        r#"{system}
+
+CAMPAIGN: Price Improvement
+- Announce the new price and reference the previous price exactly as given
+- Call it a "price improvement" or "new price", never a "price drop", "slashed" or "reduced to sell"
+- Position the new price as an opportunity, not a sign of trouble
+- Do not speculate about why the price changed"#
+    );
+
+    let user = format!(
+        "{user}\n\nPRICE CHANGE:\nPrevious price: ${}\nNew price: ${}\nDifference: ${} ({:.1}%)\nChanged: {}",
+        format_price(change.old_price),
+        format_price(change.new_price),
+        format_price(change.reduction().abs()),
+        change.reduction_percent().abs(),
+        change.changed_at,
+    );
+
+    (system, user)
+}
+
 /// Build brand voice extraction prompt
 pub fn build_voice_extraction_prompt(sample_listings: &[String]) -> (String, String) {
     let system = r#"You are a linguistic analyst specializing in writing style extraction. Analyze the following real estate listing descriptions written by the same agent and extract their unique writing voice.
@@ -289,35 +423,507 @@ OUTPUT: Respond with ONLY valid JSON matching this exact structure:
     (system, user)
 }
 
-/// Build the brand voice injection block for generation prompts
+/// Build the prompt that folds new sample listings into an existing voice
+/// profile, keeping what still holds and refining the rest
+pub fn build_voice_merge_prompt(existing_style: &str, new_listings: &[String]) -> (String, String) {
+    let system = r#"You are a linguistic analyst specializing in writing style extraction. You are given an agent's existing voice profile and new listing descriptions by the same agent. Update the profile so it reflects all of their writing: keep traits the new listings confirm, refine ones they contradict, and add distinctive words, phrases and themes that appear. Keep any field the agent has customized unless the new listings clearly contradict it.
+
+OUTPUT: Respond with ONLY valid JSON with the same structure as the existing profile:
+{
+  "tone": "1-2 sentence description of overall tone",
+  "vocabulary": ["array", "of", "10-15", "distinctive", "words/phrases"],
+  "sentence_patterns": "Description of typical sentence structure, length, use of questions/exclamations",
+  "themes": ["array", "of", "3-5", "recurring", "themes"],
+  "signature_phrases": ["exact", "phrases", "they", "reuse"],
+  "avoids": ["words", "or", "patterns", "they", "never", "use"],
+  "formatting": "Description of how they structure descriptions"
+}"#
+        .to_string();
+
+    let mut user = format!(
+        "EXISTING VOICE PROFILE:\n{}\n\nNEW LISTING DESCRIPTIONS:\n\n",
+        existing_style
+    );
+    for (i, listing) in new_listings.iter().enumerate() {
+        user.push_str(&format!("LISTING {}:\n{}\n\n", i + 1, listing));
+    }
+
+    (system, user)
+}
+
+/// Build the prompt that rates how closely a piece of copy matches a brand
+/// voice, for the judge half of the voice consistency score
+pub fn build_voice_judge_prompt(voice_block: &str, content: &str) -> (String, String) {
+    let system = r#"You are an editor who checks marketing copy against an agent's brand voice guide. Rate how consistently the copy follows the voice: tone, word choice, sentence patterns, themes, formatting and the words it must avoid. Judge voice only, not factual accuracy or overall quality.
+
+Scoring: 90-100 indistinguishable from the agent's own writing; 70-89 mostly on voice with small slips; 40-69 generic copy with traces of the voice; below 40 a different voice or uses words the guide forbids.
+
+OUTPUT: Respond with ONLY valid JSON:
+{"score": 0-100, "notes": "1-2 sentences on the biggest differences from the voice"}"#
+        .to_string();
+
+    let user = format!("{}\n\nCOPY TO RATE:\n{}", voice_block, content);
+
+    (system, user)
+}
+
+/// Build the photo caption prompt. Each photo is described by its position,
+/// room tag and file name; the model drafts a caption and alt text for each.
+pub fn build_photo_caption_prompt(property: &Property, photos: &[Photo]) -> (String, String) {
+    let system = r#"You are a real estate marketing copywriter who also writes web accessibility text. For each listed photo of the property, write:
+- "caption": a short marketing caption (4-10 words) that names the space and one appealing detail from the property facts
+- "alt_text": a plain, factual description for screen readers (max 125 characters), no marketing language, not starting with "Image of" or "Photo of"
+
+Only mention features that appear in the property facts; where the room is unknown, infer it from the photo's position (the first photo is usually the front exterior) and file name, and keep the wording general.
+
+OUTPUT: Respond with ONLY a valid JSON array, one object per photo, in the same order:
+[{"photo_id": "...", "caption": "...", "alt_text": "..."}]"#
+        .to_string();
+
+    let mut user = format!("{}\n\nPHOTOS:\n", format_property_details(property));
+    for (i, photo) in photos.iter().enumerate() {
+        user.push_str(&format!(
+            "{}. photo_id: {} | position {} of {} | room: {} | file: {}\n",
+            i + 1,
+            photo.id,
+            photo.sort_order + 1,
+            photos.len(),
+            photo.room.as_deref().unwrap_or("unknown"),
+            photo.filename,
+        ));
+    }
+
+    (system, user)
+}
+
+/// Build the vision analysis prompt for a batch of photos. The images are
+/// sent as image blocks in the same order as the photos listed here.
+pub fn build_photo_analysis_prompt(property: &Property, photos: &[Photo]) -> (String, String) {
+    let system = format!(
+        r#"You are a real estate photo analyst. For each image, in order, identify:
+- "room": the room or area shown, exactly one of: {}
+- "features": up to 6 specific, marketable features visible in the photo (e.g. "quartz countertops", "vaulted ceilings", "stainless steel appliances"). Only list what is clearly visible; no generic terms like "room" or "window".
+- "quality_score": 1-10 rating of the photo itself for a listing (lighting, focus, composition, clutter)
+- "quality_notes": one short sentence on how the photo could be improved, or "" if it is listing-ready
+
+OUTPUT: Respond with ONLY a valid JSON array, one object per image, in the same order:
+[{{"photo_id": "...", "room": "...", "features": ["..."], "quality_score": 7, "quality_notes": "..."}}]"#,
+        ROOMS.join(", ")
+    );
+
+    let mut user = format!("{}\n\nIMAGES:\n", format_property_details(property));
+    for (i, photo) in photos.iter().enumerate() {
+        user.push_str(&format!(
+            "Image {}: photo_id: {} | file: {}\n",
+            i + 1,
+            photo.id,
+            photo.filename
+        ));
+    }
+
+    (system, user)
+}
+
+/// Build the walkthrough video script prompt. Analyzed photos give the
+/// model the rooms it can film and what is visible in each.
+pub fn build_video_script_prompt(
+    property: &Property,
+    analysis_json: &str,
+    photos: &[Photo],
+    brand_voice_block: Option<&str>,
+    language: &str,
+    agent_info: &AgentInfo,
+) -> (String, String) {
+    let brand_block = brand_voice_block.unwrap_or("");
+    let language_block = get_language_instructions(language);
+    let status_block = get_status_instructions(property);
+
+    let system = format!(
+        r#"You are a real estate videographer and scriptwriter. Write a 60-90 second walkthrough video script for this property, ordered as a natural walk from the exterior through the home.
+
+{brand_block}
+{status_block}
+{language_block}
+
+RULES:
+- NEVER fabricate features not in the property data or the photo notes
+- Plan scenes only for spaces listed under ROOMS ON FILE, plus the exterior and neighborhood
+- Each scene gets 2-4 concrete shots (camera move and subject, e.g. "Slow dolly past the island toward the windows")
+- Voiceover is spoken aloud: short sentences, no abbreviations, no hashtags, about 2.5 words per second of the scene
+- The final scene closes with a call to action naming the agent if provided
+
+OUTPUT FORMAT:
+TITLE: {{video title}}
+---SCENE 1---
+LOCATION: {{room or area}}
+DURATION: {{seconds}}s
+SHOTS:
+- {{shot}}
+- {{shot}}
+VOICEOVER: {{spoken lines}}
+---SCENE 2---
+..."#
+    );
+
+    let user = long_form_user(
+        property,
+        analysis_json,
+        &format!("ROOMS ON FILE:\n{}", format_photo_rooms(photos)),
+        agent_info,
+    );
+
+    (system, user)
+}
+
+/// Build the single-property website prompt: a headline, a tagline and
+/// titled sections
+pub fn build_website_copy_prompt(
+    property: &Property,
+    analysis_json: &str,
+    brand_voice_block: Option<&str>,
+    language: &str,
+    agent_info: &AgentInfo,
+) -> (String, String) {
+    let brand_block = brand_voice_block.unwrap_or("");
+    let language_block = get_language_instructions(language);
+    let status_block = get_status_instructions(property);
+
+    let system = format!(
+        r#"You are a real estate copywriter writing the text for a single-property website.
+
+{brand_block}
+{status_block}
+{language_block}
+
+RULES:
+- NEVER fabricate features not in the property data
+- NEVER reference protected classes (race, religion, national origin, familial status, disability, sex)
+- Headline: max 10 words. Tagline: one line that pairs a key fact with the lifestyle
+- Write these sections in order: The Home, Features, The Neighborhood, Schedule a Showing
+- Each section: 1-3 short paragraphs; Features may use "- " bullet points instead
+- Schedule a Showing ends with the agent's contact details if provided
+- Plain text only, no markdown
+
+OUTPUT FORMAT:
+HEADLINE: {{headline}}
+TAGLINE: {{tagline}}
+---SECTION: The Home---
+{{paragraphs}}
+---SECTION: Features---
+..."#
+    );
+
+    let user = long_form_user(property, analysis_json, "", agent_info);
+
+    (system, user)
+}
+
+/// Build the room-by-room feature sheet prompt. Rooms come from analyzed
+/// photos where there are any, otherwise from the property's features.
+pub fn build_feature_sheet_prompt(
+    property: &Property,
+    analysis_json: &str,
+    photos: &[Photo],
+    brand_voice_block: Option<&str>,
+    language: &str,
+    agent_info: &AgentInfo,
+) -> (String, String) {
+    let brand_block = brand_voice_block.unwrap_or("");
+    let language_block = get_language_instructions(language);
+
+    let system = format!(
+        r#"You are preparing a printed room-by-room feature sheet handed out at showings.
+
+{brand_block}
+{language_block}
+
+RULES:
+- NEVER fabricate features not in the property data or the photo notes
+- One block per room or area, in walkthrough order; group features you can't place under "Throughout"
+- 2-6 bullet points per room, each a specific feature in a few words (e.g. "Quartz counters with waterfall edge")
+- No sentences, no marketing adjectives without a fact behind them, no prices
+
+OUTPUT FORMAT:
+TITLE: {{address}}
+---ROOM: {{room name}}---
+- {{feature}}
+- {{feature}}
+---ROOM: {{room name}}---
+..."#
+    );
+
+    let user = long_form_user(
+        property,
+        analysis_json,
+        &format!("ROOMS ON FILE:\n{}", format_photo_rooms(photos)),
+        agent_info,
+    );
+
+    (system, user)
+}
+
+/// Build the neighborhood guide prompt from the property's location facts
+pub fn build_neighborhood_guide_prompt(
+    property: &Property,
+    analysis_json: &str,
+    brand_voice_block: Option<&str>,
+    language: &str,
+    agent_info: &AgentInfo,
+) -> (String, String) {
+    let brand_block = brand_voice_block.unwrap_or("");
+    let language_block = get_language_instructions(language);
+
+    let system = format!(
+        r#"You are a local real estate expert writing a neighborhood guide for buyers considering this property.
+
+{brand_block}
+{language_block}
+
+RULES:
+- NEVER invent businesses, parks, schools, distances or statistics; use only the neighborhood, highlights, schools and nearby places in the property data, and speak generally where data is missing
+- NEVER describe the residents or steer buyers (no references to race, religion, national origin, familial status, disability or sex, and no "safe" or "family-friendly" claims)
+- 3-5 sections chosen from: Overview, Getting Around, Schools, Food & Shopping, Parks & Recreation, Living Here
+- Each section: one short paragraph, optionally followed by "- " bullet points
+- Close the last section with the agent's contact details if provided
+
+OUTPUT FORMAT:
+TITLE: {{guide title}}
+---SECTION: {{section name}}---
+{{paragraph}}
+- {{optional bullet}}
+---SECTION: {{section name}}---
+..."#
+    );
+
+    let user = long_form_user(property, analysis_json, "", agent_info);
+
+    (system, user)
+}
+
+/// Build the print ad prompt. `char_limit` is a hard limit on the body,
+/// counting spaces and line breaks.
+pub fn build_print_ad_prompt(
+    property: &Property,
+    analysis_json: &str,
+    char_limit: usize,
+    brand_voice_block: Option<&str>,
+    language: &str,
+    agent_info: &AgentInfo,
+) -> (String, String) {
+    let brand_block = brand_voice_block.unwrap_or("");
+    let language_block = get_language_instructions(language);
+    let status_block = get_status_instructions(property);
+
+    let system = format!(
+        r#"You are a real estate copywriter writing a print advertisement sold by space.
+
+{brand_block}
+{status_block}
+{language_block}
+
+RULES:
+- HARD LIMITS: the headline is at most {MAX_PRINT_AD_HEADLINE} characters; the body is at most {char_limit} characters including spaces. Copy over the limit cannot be printed.
+- NEVER fabricate features not in the property data
+- Lead with the strongest fact; include beds, baths and price; end with the agent's name and phone if provided
+- Common print abbreviations are fine (bd, ba, sf, w/)
+- No hashtags, URLs, emoji or markdown
+
+OUTPUT FORMAT:
+HEADLINE: {{headline}}
+BODY: {{body}}"#
+    );
+
+    let user = long_form_user(property, analysis_json, "", agent_info);
+
+    (system, user)
+}
+
+/// Ask again after a print ad ran over its character limit, showing the
+/// model the ad and how far over it was
+pub fn build_print_ad_retry(
+    user: &str,
+    response: &str,
+    characters: usize,
+    char_limit: usize,
+) -> String {
+    format!(
+        "{}\n\nYOUR PREVIOUS AD:\n{}\n\nThat body is {} characters; the limit is {}. Rewrite the ad in the same format with a body of at most {} characters. Cut words, not facts the buyer needs.",
+        user,
+        response.trim(),
+        characters,
+        char_limit,
+        char_limit
+    )
+}
+
+/// Build the prompt that translates existing copy. The copy's output format
+/// (markers such as `---POST 1---` or `SUBJECT:`) must survive unchanged.
+pub fn build_translation_prompt(
+    content: &str,
+    source_language: &str,
+    target_language: &str,
+    brand_voice_block: Option<&str>,
+) -> (String, String) {
+    let source = language_name(source_language).unwrap_or(source_language);
+    let target = language_name(target_language).unwrap_or(target_language);
+    let brand_block = brand_voice_block.unwrap_or("");
+
+    let system = format!(
+        r#"You are a professional real estate translator. Translate the copy from {source} into {target} for native {target}-speaking buyers and sellers.
+
+{brand_block}
+
+RULES:
+- Carry over the voice described above: the same warmth, energy and sentence rhythm, adapted naturally to {target} rather than translated word for word
+- Keep every number exactly as written, in digits: prices, beds, baths, square footage, years, phone numbers, distances
+- Keep the street address, proper names (neighborhoods, schools, businesses, the brokerage), email addresses and URLs unchanged
+- Keep line breaks, bullet points and hashtags; translate the words in hashtags only where a {target} hashtag is commonly used
+- Keep all format labels and markers (e.g. "SUBJECT:", "PREVIEW:", "HEADLINE:", "---POST 1---", "---SCENE 1---") in English exactly as written
+- Add nothing and leave nothing out
+
+OUTPUT: Write ONLY the translated copy."#
+    );
+
+    let user = format!("COPY TO TRANSLATE:\n{}", content.trim());
+
+    (system, user)
+}
+
+/// Ask again after a translation dropped or changed numbers from the
+/// original, listing the ones that are missing
+pub fn build_translation_retry(user: &str, response: &str, missing: &[String]) -> String {
+    format!(
+        "{}\n\nYOUR PREVIOUS TRANSLATION:\n{}\n\nThese numbers from the original are missing or changed: {}. Translate again, keeping every number exactly as written in the original.",
+        user,
+        response.trim(),
+        missing.join(", ")
+    )
+}
+
+/// Build the brand voice injection block for generation prompts. Empty
+/// profile fields are left out; None when nothing usable remains.
 pub fn build_voice_block(extracted_style_json: &str) -> Option<String> {
-    let style: serde_json::Value = serde_json::from_str(extracted_style_json).ok()?;
+    let mut profile = VoiceProfile::from_json(extracted_style_json).ok()?;
+    profile.normalize();
 
+    let mut lines = Vec::new();
+    let mut push = |label: &str, value: String| {
+        if !value.is_empty() {
+            lines.push(format!("- {}: {}", label, value));
+        }
+    };
+    push("Tone", profile.tone);
+    push("Vocabulary preferences", profile.vocabulary.join(", "));
+    push("Sentence patterns", profile.sentence_patterns);
+    push("Signature themes", profile.themes.join(", "));
+    push("Example phrases to emulate", profile.signature_phrases.join(", "));
+    push("Never use these words or patterns", profile.avoids.join(", "));
+    push("Formatting", profile.formatting);
+
+    if lines.is_empty() {
+        return None;
+    }
     Some(format!(
-        r#"BRAND VOICE: Match this writing style:
-- Tone: {}
-- Vocabulary preferences: {}
-- Sentence patterns: {}
-- Signature themes: {}
-- Example phrases to emulate: {}
-Maintain this voice while following all other instructions."#,
-        style["tone"].as_str().unwrap_or(""),
-        style["vocabulary"]
-            .as_array()
-            .map(|v| v.iter().filter_map(|s| s.as_str()).collect::<Vec<_>>().join(", "))
-            .unwrap_or_default(),
-        style["sentence_patterns"].as_str().unwrap_or(""),
-        style["themes"]
-            .as_array()
-            .map(|v| v.iter().filter_map(|s| s.as_str()).collect::<Vec<_>>().join(", "))
-            .unwrap_or_default(),
-        style["signature_phrases"]
-            .as_array()
-            .map(|v| v.iter().filter_map(|s| s.as_str()).collect::<Vec<_>>().join(", "))
-            .unwrap_or_default(),
+        "BRAND VOICE: Match this writing style:\n{}\nMaintain this voice while following all other instructions.",
+        lines.join("\n")
     ))
 }
 
+/// Ask again after a voice profile response failed schema validation,
+/// showing the model what it sent and what was wrong
+pub fn build_voice_schema_retry(user: &str, response: &str, problem: &str) -> String {
+    format!(
+        "{}\n\nYOUR PREVIOUS RESPONSE:\n{}\n\nThat response was rejected: {}. Respond again with ONLY the JSON object in the required structure, with every field filled in.",
+        user,
+        response.trim(),
+        problem
+    )
+}
+
+/// Fill a preset's `{{variable}}` placeholders from the property and agent.
+/// Unknown variables are left as written; `unknown_preset_variables` is
+/// checked when a preset is saved.
+pub fn render_preset(template: &str, property: &Property, agent_info: &AgentInfo) -> String {
+    let list = |json: &str| {
+        serde_json::from_str::<Vec<String>>(json)
+            .unwrap_or_default()
+            .join(", ")
+    };
+    let value = |name: &str| -> Option<String> {
+        let value = match name {
+            "address" => property.address.clone(),
+            "city" => property.city.clone(),
+            "state" => property.state.clone(),
+            "zip" => property.zip.clone(),
+            "property_type" => property.property_type.replace('_', " "),
+            "beds" => property.beds.to_string(),
+            "baths" => property.baths.to_string(),
+            "sqft" => property.sqft.to_string(),
+            "price" => format!("${}", format_price(property.price)),
+            "year_built" => property
+                .year_built
+                .map_or("N/A".to_string(), |y| y.to_string()),
+            "lot_size" => property
+                .lot_size
+                .clone()
+                .unwrap_or_else(|| "N/A".to_string()),
+            "parking" => property
+                .parking
+                .clone()
+                .unwrap_or_else(|| "N/A".to_string()),
+            "key_features" => list(&property.key_features),
+            "neighborhood" => property
+                .neighborhood
+                .clone()
+                .unwrap_or_else(|| "N/A".to_string()),
+            "school_district" => property
+                .school_district
+                .clone()
+                .unwrap_or_else(|| "N/A".to_string()),
+            "agent_name" => agent_info.name.clone(),
+            "agent_phone" => agent_info.phone.clone(),
+            "agent_email" => agent_info.email.clone(),
+            "brokerage" => agent_info.brokerage.clone(),
+            _ => return None,
+        };
+        Some(value)
+    };
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        rendered.push_str(&rest[..start]);
+        match value(name) {
+            Some(value) => rendered.push_str(&value),
+            None => rendered.push_str(&rest[start..start + len + 4]),
+        }
+        rest = &rest[start + len + 4..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// `{{variables}}` in a preset that aren't in `PRESET_VARIABLES`
+pub fn unknown_preset_variables(template: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim().to_string();
+        if !PRESET_VARIABLES.contains(&name.as_str()) && !unknown.contains(&name) {
+            unknown.push(name);
+        }
+        rest = &rest[start + len + 4..];
+    }
+    unknown
+}
+
 fn get_style_instructions(style: &str) -> &str {
     match style {
         "luxury" => "Write for affluent buyers who value exclusivity, craftsmanship, and lifestyle. Use sophisticated language: \"bespoke\", \"curated\", \"impeccable\", \"artisan\". Emphasize unique/custom elements, premium materials, and the lifestyle the home enables. Longer sentences, aspirational imagery.",
@@ -359,12 +965,151 @@ fn get_template_instructions(template: &str) -> &str {
     match template {
         "buyer" => "This email goes to a potential buyer whose search criteria match this property. Open with why this property fits their needs. Highlight 3-4 key features that match typical buyer criteria. Create soft urgency (\"just listed\", \"early access\"). CTA: \"Schedule a private showing\" or \"Reply to learn more\".",
         "seller" => "This email goes to a potential seller in the same neighborhood. Use this listing as social proof of market activity. Reference the neighborhood by name. Highlight the sale price / market conditions. CTA: \"Curious what your home is worth?\" or \"Free comparative market analysis\".",
+        "price_improvement" => "This email goes to buyers and agents who may have passed on this property at its previous price. Lead with the price improvement, state the previous and new price, and remind them of 2-3 standout features. CTA: \"Schedule a showing\" or \"Reply to learn more\".",
         "open_house" => "This email invites potential buyers to an open house. Include: date, time, address, 3 property highlights, what refreshments/experience to expect. Create excitement. CTA: \"RSVP\" or \"Add to calendar\". Make the open house sound like an event, not a chore.",
         _ => "Write a professional real estate email.",
     }
 }
 
-fn format_price(price_cents: i64) -> String {
+/// Name of a language code from `LANGUAGES`
+pub fn language_name(code: &str) -> Option<&'static str> {
+    LANGUAGES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, name)| *name)
+}
+
+/// Instructions for writing in a language other than English; empty for
+/// English. Output format labels stay in English so the copy still parses.
+fn get_language_instructions(language: &str) -> String {
+    match language_name(language) {
+        Some(name) if language != DEFAULT_LANGUAGE => format!(
+            "LANGUAGE: Write the copy in {name}, as a native {name}-speaking real estate copywriter would, not as a word-for-word translation of English marketing. Keep the street address, proper names (neighborhoods, schools, businesses, the brokerage) and the agent's contact details exactly as written. Write every number with digits. Keep the OUTPUT FORMAT labels and markers in English exactly as shown."
+        ),
+        _ => String::new(),
+    }
+}
+
+/// Framing for properties that are no longer (or not yet) simply for sale.
+/// Empty for active and draft properties.
+fn get_status_instructions(property: &Property) -> String {
+    match property.status.as_str() {
+        "coming_soon" => "LISTING STATUS: Coming Soon. The property is not yet available for showings. Write it as a \"Coming Soon\" teaser that builds anticipation; invite people to reach out for early access rather than to book a showing.".to_string(),
+        "pending" => "LISTING STATUS: Pending (under contract). Present it as \"Under Contract\" / \"Pending\". Do not invite offers or showings; you may mention that backup offers are welcome and highlight the strong buyer response.".to_string(),
+        "sold" => {
+            let sold_for = property
+                .sold_price
+                .map(|p| format!(" for ${}", format_price(p)))
+                .unwrap_or_default();
+            let dom = property
+                .days_on_market
+                .map(|d| format!(" after {} days on market", d))
+                .unwrap_or_default();
+            format!(
+                "LISTING STATUS: Sold{sold_for}{dom}. Write this as a \"Just Sold\" announcement celebrating the result. Never invite offers or showings. Use the sale as proof of neighborhood demand and the agent's results, and invite nearby owners to ask what their home is worth."
+            )
+        }
+        "withdrawn" | "expired" => "LISTING STATUS: Off market. Do not describe the property as currently for sale and do not invite offers or showings.".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Property facts, analysis and any extra context for the long-form prompts
+fn long_form_user(
+    property: &Property,
+    analysis_json: &str,
+    extra: &str,
+    agent_info: &AgentInfo,
+) -> String {
+    let agent = if agent_info.name.is_empty() {
+        "none".to_string()
+    } else {
+        format!(
+            "{} | {} | {} | {}",
+            agent_info.name, agent_info.phone, agent_info.email, agent_info.brokerage
+        )
+    };
+
+    format!(
+        "{}\nAgent: {}\n\n{}{}MARKET ANALYSIS:\n{}",
+        format_property_details(property),
+        agent,
+        extra,
+        if extra.is_empty() { "" } else { "\n\n" },
+        analysis_json
+    )
+}
+
+/// Tagged rooms in walkthrough order, each with the features vision analysis
+/// found in its photos
+fn format_photo_rooms(photos: &[Photo]) -> String {
+    let mut lines = Vec::new();
+    for room in ROOMS {
+        let in_room: Vec<&Photo> = photos
+            .iter()
+            .filter(|p| p.room.as_deref() == Some(*room))
+            .collect();
+        if in_room.is_empty() {
+            continue;
+        }
+        let mut features: Vec<String> = Vec::new();
+        for photo in &in_room {
+            let detected: Vec<String> =
+                serde_json::from_str(&photo.detected_features).unwrap_or_default();
+            for feature in detected {
+                if !features.contains(&feature) {
+                    features.push(feature);
+                }
+            }
+        }
+        lines.push(if features.is_empty() {
+            format!("- {}", room.replace('_', " "))
+        } else {
+            format!("- {}: {}", room.replace('_', " "), features.join(", "))
+        });
+    }
+
+    if lines.is_empty() {
+        "No analyzed photos; use the key features and keep rooms general.".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+/// Property facts in the compact form used by the analysis and photo prompts
+fn format_property_details(property: &Property) -> String {
+    let key_features: Vec<String> =
+        serde_json::from_str(&property.key_features).unwrap_or_default();
+    let highlights: Vec<String> =
+        serde_json::from_str(&property.neighborhood_highlights).unwrap_or_default();
+    let amenities: Vec<String> =
+        serde_json::from_str(&property.nearby_amenities).unwrap_or_default();
+
+    let price_formatted = format_price(property.price);
+
+    format!(
+        "Property: {}, {}, {} {}\nType: {} | Built: {}\n{} bed / {} bath / {} sqft\nPrice: ${}\nLot: {} | Parking: {}\nKey Features: {}\nNeighborhood: {}\nHighlights: {}\nSchools: {}\nNearby: {}",
+        property.address,
+        property.city,
+        property.state,
+        property.zip,
+        property.property_type.replace('_', " "),
+        property.year_built.map_or("N/A".to_string(), |y| y.to_string()),
+        property.beds,
+        property.baths,
+        property.sqft,
+        price_formatted,
+        property.lot_size.as_deref().unwrap_or("N/A"),
+        property.parking.as_deref().unwrap_or("N/A"),
+        key_features.join(", "),
+        property.neighborhood.as_deref().unwrap_or("N/A"),
+        highlights.join(", "),
+        property.school_district.as_deref().unwrap_or("N/A"),
+        amenities.join(", "),
+    )
+}
+
+pub(crate) fn format_price(price_cents: i64) -> String {
     let dollars = price_cents / 100;
     let mut s = dollars.to_string();
     let mut result = String::new();
@@ -392,6 +1137,12 @@ pub const MAX_TOKENS_ANALYSIS: u32 = 1024;
 pub const MAX_TOKENS_SOCIAL: u32 = 2048;
 pub const MAX_TOKENS_EMAIL: u32 = 2048;
 pub const MAX_TOKENS_BRAND_VOICE: u32 = 2048;
+pub const MAX_TOKENS_PHOTO_CAPTIONS: u32 = 4096;
+pub const MAX_TOKENS_PHOTO_ANALYSIS: u32 = 2048;
+pub const MAX_TOKENS_VOICE_JUDGE: u32 = 512;
+pub const MAX_TOKENS_LONG_FORM: u32 = 4096;
+pub const MAX_TOKENS_PRINT_AD: u32 = 1024;
+pub const MAX_TOKENS_TRANSLATION: u32 = 4096;
 
 #[cfg(test)]
 mod tests {
@@ -418,6 +1169,14 @@ mod tests {
             school_district: Some("SFUSD".to_string()),
             nearby_amenities: r#"["Whole Foods 0.3mi"]"#.to_string(),
             agent_notes: None,
+            agent_id: None,
+            status: "active".to_string(),
+            listed_at: Some("2024-01-01 00:00:00".to_string()),
+            pending_at: None,
+            sold_at: None,
+            sold_price: None,
+            off_market_at: None,
+            days_on_market: Some(12),
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
         }
@@ -445,6 +1204,7 @@ mod tests {
             phone: "555-1234".to_string(),
             email: "jane@example.com".to_string(),
             brokerage: "RE/MAX".to_string(),
+            signature: String::new(),
         };
 
         for style in &["luxury", "family", "investment", "first_time"] {
@@ -453,6 +1213,10 @@ mod tests {
                 tone: "warm".to_string(),
                 length: "medium".to_string(),
                 seo_keywords: vec!["waterfront".to_string()],
+                custom_style: None,
+                custom_tone: None,
+                custom_length: None,
+                language: "en".to_string(),
             };
             let (system, user) = build_listing_prompt(&property, analysis, &options, None, &agent);
             assert!(system.contains("expert real estate copywriter"));
@@ -469,16 +1233,20 @@ mod tests {
             phone: "555".to_string(),
             email: "j@e.com".to_string(),
             brokerage: "RE".to_string(),
+            signature: String::new(),
         };
 
-        let (system, _) = build_social_prompt(&property, "{}", "instagram", None, &agent);
+        let (system, _) =
+            build_social_prompt(&property, "{}", "instagram", None, None, "en", &agent);
         assert!(system.contains("Instagram"));
         assert!(system.contains("hashtags"));
 
-        let (system, _) = build_social_prompt(&property, "{}", "facebook", None, &agent);
+        let (system, _) =
+            build_social_prompt(&property, "{}", "facebook", None, None, "en", &agent);
         assert!(system.contains("Facebook"));
 
-        let (system, _) = build_social_prompt(&property, "{}", "linkedin", None, &agent);
+        let (system, _) =
+            build_social_prompt(&property, "{}", "linkedin", None, None, "en", &agent);
         assert!(system.contains("LinkedIn"));
     }
 
@@ -490,13 +1258,270 @@ mod tests {
             phone: "555".to_string(),
             email: "j@e.com".to_string(),
             brokerage: "RE".to_string(),
+            signature: String::new(),
         };
 
-        let (system, _) = build_email_prompt(&property, "{}", "buyer", None, &agent);
+        let (system, _) = build_email_prompt(&property, "{}", "buyer", None, None, "en", &agent);
         assert!(system.contains("SUBJECT:"));
 
-        let (system, _) = build_email_prompt(&property, "{}", "seller", None, &agent);
+        let (system, _) = build_email_prompt(&property, "{}", "seller", None, None, "en", &agent);
         assert!(system.contains("email"));
+        assert!(!system.contains("signature exactly"));
+    }
+
+    #[test]
+    fn test_email_prompt_uses_agent_signature() {
+        let property = sample_property();
+        let agent = AgentInfo {
+            name: "Jane".to_string(),
+            phone: "555".to_string(),
+            email: "j@e.com".to_string(),
+            brokerage: "RE".to_string(),
+            signature: "Jane Smith | DRE #01234567".to_string(),
+        };
+
+        let (system, _) = build_email_prompt(&property, "{}", "buyer", None, None, "en", &agent);
+        assert!(system.contains("signature exactly"));
+        assert!(system.contains("DRE #01234567"));
+    }
+
+    #[test]
+    fn test_status_instructions() {
+        let agent = AgentInfo {
+            name: "Jane".to_string(),
+            phone: "555".to_string(),
+            email: "j@e.com".to_string(),
+            brokerage: "RE".to_string(),
+            signature: String::new(),
+        };
+        let mut property = sample_property();
+        let (system, _) =
+            build_social_prompt(&property, "{}", "instagram", None, None, "en", &agent);
+        assert!(!system.contains("LISTING STATUS"));
+
+        property.status = "sold".to_string();
+        property.sold_price = Some(87500000);
+        let (system, _) =
+            build_social_prompt(&property, "{}", "instagram", None, None, "en", &agent);
+        assert!(system.contains("Just Sold"));
+        assert!(system.contains("$875,000"));
+        assert!(system.contains("after 12 days on market"));
+
+        property.status = "pending".to_string();
+        let (system, _) = build_email_prompt(&property, "{}", "buyer", None, None, "en", &agent);
+        assert!(system.contains("Under Contract"));
+    }
+
+    #[test]
+    fn test_build_price_improvement_prompt() {
+        let property = sample_property();
+        let agent = AgentInfo {
+            name: "Jane".to_string(),
+            phone: "555".to_string(),
+            email: "j@e.com".to_string(),
+            brokerage: "RE".to_string(),
+            signature: String::new(),
+        };
+        let change = PriceChange {
+            id: "c1".to_string(),
+            property_id: property.id.clone(),
+            old_price: 100000000,
+            new_price: 95000000,
+            changed_at: "2024-02-01 00:00:00".to_string(),
+        };
+
+        let (system, user) =
+            build_price_improvement_prompt(&property, "{}", "email", &change, None, "en", &agent);
+        assert!(system.contains("SUBJECT:"));
+        assert!(system.contains("Price Improvement"));
+        assert!(user.contains("Previous price: $1,000,000"));
+        assert!(user.contains("New price: $950,000"));
+        assert!(user.contains("$50,000 (5.0%)"));
+
+        let (system, _) = build_price_improvement_prompt(
+            &property,
+            "{}",
+            "instagram",
+            &change,
+            None,
+            "en",
+            &agent,
+        );
+        assert!(system.contains("Instagram"));
+        assert!(system.contains("---POST 1---"));
+    }
+
+    #[test]
+    fn test_build_photo_caption_prompt() {
+        let property = sample_property();
+        let photo = |id: &str, order: i64, room: Option<&str>| Photo {
+            id: id.to_string(),
+            property_id: property.id.clone(),
+            filename: format!("{}.jpg", id),
+            sort_order: order,
+            room: room.map(str::to_string),
+            ..crate::db::test_photo()
+        };
+        let photos = vec![photo("front", 0, None), photo("kitchen", 1, Some("kitchen"))];
+
+        let (system, user) = build_photo_caption_prompt(&property, &photos);
+        assert!(system.contains("alt_text"));
+        assert!(user.contains("123 Oak Street"));
+        assert!(user.contains("photo_id: front | position 1 of 2 | room: unknown"));
+        assert!(user.contains("photo_id: kitchen | position 2 of 2 | room: kitchen"));
+    }
+
+    #[test]
+    fn test_build_long_form_prompts() {
+        let property = sample_property();
+        let agent = AgentInfo {
+            name: "Jane".to_string(),
+            phone: "555".to_string(),
+            email: "j@e.com".to_string(),
+            brokerage: "RE".to_string(),
+            signature: String::new(),
+        };
+        let photo = |room: Option<&str>, features: &str| Photo {
+            property_id: property.id.clone(),
+            room: room.map(str::to_string),
+            detected_features: features.to_string(),
+            ..crate::db::test_photo()
+        };
+        let photos = vec![
+            photo(Some("kitchen"), r#"["quartz countertops","island"]"#),
+            photo(Some("kitchen"), r#"["island","pendant lights"]"#),
+            photo(Some("exterior_front"), "[]"),
+            photo(None, r#"["fence"]"#),
+        ];
+
+        let (system, user) =
+            build_video_script_prompt(&property, "{}", &photos, None, "en", &agent);
+        assert!(system.contains("---SCENE 1---"));
+        // Rooms in walkthrough order, features merged across photos
+        assert!(user
+            .contains("- exterior front\n- kitchen: quartz countertops, island, pendant lights"));
+        assert!(!user.contains("fence"));
+        assert!(user.contains("Agent: Jane | 555"));
+
+        let (system, user) = build_feature_sheet_prompt(&property, "{}", &[], None, "en", &agent);
+        assert!(system.contains("---ROOM:"));
+        assert!(user.contains("No analyzed photos"));
+
+        let (system, _) = build_website_copy_prompt(&property, "{}", None, "en", &agent);
+        assert!(system.contains("HEADLINE:") && system.contains("---SECTION: The Home---"));
+
+        let (system, _) = build_neighborhood_guide_prompt(&property, "{}", None, "en", &agent);
+        assert!(system.contains("NEVER invent businesses"));
+
+        let (system, user) = build_print_ad_prompt(&property, "{}", 250, None, "en", &agent);
+        assert!(system.contains("the body is at most 250 characters"));
+        let retry = build_print_ad_retry(&user, "HEADLINE: x\nBODY: y", 310, 250);
+        assert!(retry.contains("That body is 310 characters; the limit is 250"));
+    }
+
+    #[test]
+    fn test_language_instructions_and_translation_prompt() {
+        let property = sample_property();
+        let agent = AgentInfo {
+            name: "Jane".to_string(),
+            phone: "555".to_string(),
+            email: "j@e.com".to_string(),
+            brokerage: "RE".to_string(),
+            signature: String::new(),
+        };
+        let (system, _) = build_email_prompt(&property, "{}", "buyer", None, None, "en", &agent);
+        assert!(!system.contains("LANGUAGE:"));
+        let (system, _) = build_email_prompt(&property, "{}", "buyer", None, None, "vi", &agent);
+        assert!(system.contains("LANGUAGE: Write the copy in Vietnamese"));
+        assert!(system.contains("OUTPUT FORMAT labels and markers in English"));
+
+        assert_eq!(language_name("zh"), Some("Simplified Chinese"));
+        assert_eq!(language_name("fr"), None);
+
+        let (system, user) =
+            build_translation_prompt("3 bed for $950,000", "en", "es", Some("BRAND VOICE: Crisp"));
+        assert!(system.contains("from English into Spanish"));
+        assert!(system.contains("BRAND VOICE: Crisp"));
+        assert!(user.ends_with("3 bed for $950,000"));
+        let retry = build_translation_retry(&user, "3 hab. por $95", &["950,000".to_string()]);
+        assert!(retry.contains("missing or changed: 950,000"));
+    }
+
+    #[test]
+    fn test_custom_presets_replace_built_in_instructions() {
+        let property = sample_property();
+        let agent = AgentInfo {
+            name: "Jane".to_string(),
+            phone: "555".to_string(),
+            email: "j@e.com".to_string(),
+            brokerage: "RE".to_string(),
+            signature: String::new(),
+        };
+        let options = GenerationOptions {
+            style: "new_construction".to_string(),
+            tone: "warm".to_string(),
+            length: "medium".to_string(),
+            seo_keywords: vec![],
+            custom_style: Some("Stress builder warranties.".to_string()),
+            custom_tone: None,
+            custom_length: None,
+            language: "en".to_string(),
+        };
+        let (system, _) = build_listing_prompt(&property, "{}", &options, None, &agent);
+        assert!(system.contains("STYLE: Stress builder warranties."));
+        assert!(system.contains("TONE: Conversational and inviting"));
+
+        let preset = PromptPreset {
+            id: "p1".to_string(),
+            kind: "platform".to_string(),
+            key: "tiktok_script".to_string(),
+            name: "TikTok script".to_string(),
+            instructions: "Write 3 scripts under 45 seconds.".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        let (system, _) = build_social_prompt(
+            &property,
+            "{}",
+            "tiktok_script",
+            Some(&preset),
+            None,
+            "en",
+            &agent,
+        );
+        assert!(system.contains("Create TikTok script-optimized posts"));
+        assert!(system.contains("Write 3 scripts under 45 seconds."));
+    }
+
+    #[test]
+    fn test_render_preset() {
+        let property = sample_property();
+        let agent = AgentInfo {
+            name: "Jane".to_string(),
+            phone: "555".to_string(),
+            email: "j@e.com".to_string(),
+            brokerage: "RE".to_string(),
+            signature: String::new(),
+        };
+        let rendered = render_preset(
+            "{{ beds }} beds in {{neighborhood}} at {{price}}, call {{agent_name}}. {{mood}} {{open",
+            &property,
+            &agent,
+        );
+        assert_eq!(
+            rendered,
+            "3 beds in Mission Bay at $950,000, call Jane. {{mood}} {{open"
+        );
+        assert_eq!(
+            render_preset("Features: {{key_features}}", &property, &agent),
+            "Features: hardwood floors, chef's kitchen, pool"
+        );
+
+        assert_eq!(
+            unknown_preset_variables("{{city}} {{mood}} {{ mood }} {{vibe}}"),
+            vec!["mood", "vibe"]
+        );
+        assert!(unknown_preset_variables("{{city}} for {{agent_name}}").is_empty());
     }
 
     #[test]
@@ -514,6 +1539,18 @@ mod tests {
         assert!(block.contains("stunning"));
     }
 
+    #[test]
+    fn test_voice_block_includes_avoids_and_formatting_and_skips_empty() {
+        let style = r#"{"tone":"Crisp","vocabulary":[],"avoids":["cozy","nestled"],"formatting":"Open with the view"}"#;
+        let block = build_voice_block(style).unwrap();
+        assert!(block.contains("Never use these words or patterns: cozy, nestled"));
+        assert!(block.contains("Formatting: Open with the view"));
+        assert!(!block.contains("Vocabulary preferences"));
+
+        assert!(build_voice_block("{}").is_none());
+        assert!(build_voice_block("not json").is_none());
+    }
+
     #[test]
     fn test_max_tokens() {
         assert_eq!(max_tokens_for_listing("short"), 1024);
@@ -1,5 +1,6 @@
 use tauri::ipc::Channel;
 
+use crate::db::prompt_presets::PromptPreset;
 use crate::db::properties::Property;
 use crate::error::AppError;
 
@@ -10,11 +11,14 @@ use super::listing_generator::{GenerationResult, PropertyAnalysis};
 /// Two-stage social media post generation pipeline:
 /// 1. Analyze property (non-streaming) -> structured JSON
 /// 2. Generate social media posts (streaming) -> text deltas via Channel
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_social_posts(
     client: &ClaudeClient,
     property: &Property,
     platform: &str,
+    custom: Option<&PromptPreset>,
     brand_voice_block: Option<&str>,
+    language: &str,
     agent_info: &AgentInfo,
     channel: &Channel<StreamEvent>,
 ) -> Result<GenerationResult, AppError> {
@@ -33,8 +37,15 @@ pub async fn generate_social_posts(
     })?;
 
     // Stage 2: Generate social posts (streaming)
-    let (social_system, social_user) =
-        build_social_prompt(property, &analysis_text, platform, brand_voice_block, agent_info);
+    let (social_system, social_user) = build_social_prompt(
+        property,
+        &analysis_text,
+        platform,
+        custom,
+        brand_voice_block,
+        language,
+        agent_info,
+    );
 
     let (full_text, social_input, social_output) = client
         .stream_message(&social_system, &social_user, MAX_TOKENS_SOCIAL, channel)
@@ -0,0 +1,170 @@
+use serde::Serialize;
+use tauri::ipc::Channel;
+
+use crate::error::AppError;
+
+use super::client::{calculate_cost_cents, ClaudeClient};
+use super::prompts::{
+    build_translation_prompt, build_translation_retry, language_name, MAX_TOKENS_TRANSLATION,
+};
+
+pub struct TranslationResult {
+    pub full_text: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub cost_cents: u32,
+}
+
+/// Progress of translating one listing into several languages, tagged by
+/// language code
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "event", content = "data")]
+pub enum TranslationEvent {
+    Started {
+        languages: Vec<String>,
+    },
+    Saved {
+        language: String,
+        listing_id: String,
+    },
+    Failed {
+        language: String,
+        message: String,
+    },
+    Finished,
+}
+
+/// Translate copy into each target language concurrently. One language
+/// failing doesn't stop the others, so each gets its own result.
+pub async fn translate_all(
+    client: &ClaudeClient,
+    content: &str,
+    source_language: &str,
+    target_languages: &[String],
+    brand_voice_block: Option<&str>,
+    channel: &Channel<TranslationEvent>,
+) -> Vec<Result<TranslationResult, AppError>> {
+    let _ = channel.send(TranslationEvent::Started {
+        languages: target_languages.to_vec(),
+    });
+
+    let runs = target_languages.iter().map(|language| async move {
+        let result = translate(
+            client,
+            content,
+            source_language,
+            language,
+            brand_voice_block,
+        )
+        .await;
+        if let Err(ref e) = result {
+            let _ = channel.send(TranslationEvent::Failed {
+                language: language.clone(),
+                message: e.to_string(),
+            });
+        }
+        result
+    });
+
+    futures::future::join_all(runs).await
+}
+
+/// Translate copy into one language. A translation that drops or changes a
+/// number from the original is retried once, then rejected.
+pub async fn translate(
+    client: &ClaudeClient,
+    content: &str,
+    source_language: &str,
+    target_language: &str,
+    brand_voice_block: Option<&str>,
+) -> Result<TranslationResult, AppError> {
+    let (system, user) =
+        build_translation_prompt(content, source_language, target_language, brand_voice_block);
+    let (mut full_text, mut input_tokens, mut output_tokens) = client
+        .send_message(&system, &user, MAX_TOKENS_TRANSLATION)
+        .await?;
+
+    let missing = missing_numbers(content, &full_text);
+    if !missing.is_empty() {
+        let retry = build_translation_retry(&user, &full_text, &missing);
+        let (text, input, output) = client
+            .send_message(&system, &retry, MAX_TOKENS_TRANSLATION)
+            .await?;
+        input_tokens += input;
+        output_tokens += output;
+        full_text = text;
+
+        let missing = missing_numbers(content, &full_text);
+        if !missing.is_empty() {
+            return Err(AppError::Api(format!(
+                "The {} translation changed these numbers from the original: {}",
+                language_name(target_language).unwrap_or(target_language),
+                missing.join(", ")
+            )));
+        }
+    }
+
+    Ok(TranslationResult {
+        full_text: full_text.trim().to_string(),
+        input_tokens,
+        output_tokens,
+        cost_cents: calculate_cost_cents(input_tokens, output_tokens),
+    })
+}
+
+/// Numbers in the original, as written there, that don't appear in the
+/// translation. Thousands and decimal separators are ignored so "$950,000"
+/// matches "950.000 $" and "2.5" matches "2,5".
+pub fn missing_numbers(original: &str, translated: &str) -> Vec<String> {
+    let translated: Vec<String> = numbers(translated).into_iter().map(|(_, n)| n).collect();
+    let mut missing = Vec::new();
+    for (written, digits) in numbers(original) {
+        if !translated.contains(&digits) && !missing.contains(&written) {
+            missing.push(written);
+        }
+    }
+    missing
+}
+
+/// Each run of digits (with separators between them) as written and as
+/// bare digits
+fn numbers(text: &str) -> Vec<(String, String)> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut numbers = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len()
+            && (chars[i].is_ascii_digit()
+                || (matches!(chars[i], ',' | '.')
+                    && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())))
+        {
+            i += 1;
+        }
+        let written: String = chars[start..i].iter().collect();
+        let digits = written.chars().filter(char::is_ascii_digit).collect();
+        numbers.push((written, digits));
+    }
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_numbers() {
+        let original = "3 bed / 2.5 bath, 1,800 sqft for $950,000. Call 555-1234.";
+        let spanish =
+            "3 habitaciones / 2,5 baños, 1.800 pies cuadrados por $950.000. Llame al 555-1234.";
+        assert!(missing_numbers(original, spanish).is_empty());
+
+        let changed =
+            "3 habitaciones / 2 baños, 1.800 pies cuadrados por $95万. Llame al 555-1234.";
+        assert_eq!(missing_numbers(original, changed), vec!["2.5", "950,000"]);
+    }
+}
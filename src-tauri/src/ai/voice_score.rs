@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ai::client::{strip_code_fences, ClaudeClient};
+use crate::ai::prompts::{build_voice_block, build_voice_judge_prompt, MAX_TOKENS_VOICE_JUDGE};
+use crate::db::brand_voice::{BrandVoice, VoiceProfile};
+use crate::error::AppError;
+
+/// Vocabulary terms a listing needs to use for full vocabulary credit; a
+/// single listing can't be expected to use every term in the profile
+const VOCABULARY_TARGET: usize = 4;
+const SIGNATURE_TARGET: usize = 1;
+
+/// Share of the lexical score from vocabulary; the rest is signature phrases
+const VOCABULARY_WEIGHT: f64 = 0.7;
+
+/// Taken off the lexical score for each avoided word used, up to all of it
+const AVOID_PENALTY: f64 = 0.25;
+
+/// Share of the combined score from the judge when it ran
+const JUDGE_WEIGHT: f64 = 0.6;
+
+/// How well a listing matches a brand voice, 0-100. Stored on the listing
+/// as JSON alongside the combined score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceScore {
+    pub brand_voice_id: String,
+    pub score: f64,
+    pub lexical_score: f64,
+    pub judge_score: Option<f64>,
+    pub judge_notes: Option<String>,
+    pub vocabulary_used: Vec<String>,
+    pub signature_phrases_used: Vec<String>,
+    pub avoided_words_found: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexicalScore {
+    pub score: f64,
+    pub vocabulary_used: Vec<String>,
+    pub signature_phrases_used: Vec<String>,
+    pub avoided_words_found: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct JudgeResponse {
+    score: f64,
+    #[serde(default)]
+    notes: String,
+}
+
+/// Deterministic part of the score: which of the profile's vocabulary and
+/// signature phrases the content uses, less a penalty for avoided words
+pub fn lexical_score(profile: &VoiceProfile, content: &str) -> LexicalScore {
+    let text = content.to_lowercase();
+    let found = |terms: &[String]| -> Vec<String> {
+        terms
+            .iter()
+            .filter(|t| contains_term(&text, &t.to_lowercase()))
+            .cloned()
+            .collect()
+    };
+    let vocabulary_used = found(&profile.vocabulary);
+    let signature_phrases_used = found(&profile.signature_phrases);
+    let avoided_words_found = found(&profile.avoids);
+
+    let coverage = |used: usize, available: usize, target: usize| -> Option<f64> {
+        (available > 0).then(|| (used as f64 / target.min(available) as f64).min(1.0))
+    };
+    let vocabulary = coverage(
+        vocabulary_used.len(),
+        profile.vocabulary.len(),
+        VOCABULARY_TARGET,
+    );
+    let signature = coverage(
+        signature_phrases_used.len(),
+        profile.signature_phrases.len(),
+        SIGNATURE_TARGET,
+    );
+    let usage = match (vocabulary, signature) {
+        (Some(v), Some(s)) => v * VOCABULARY_WEIGHT + s * (1.0 - VOCABULARY_WEIGHT),
+        (Some(v), None) => v,
+        (None, Some(s)) => s,
+        // Nothing to look for; only avoided words count
+        (None, None) => 1.0,
+    };
+    let penalty = (avoided_words_found.len() as f64 * AVOID_PENALTY).min(1.0);
+
+    LexicalScore {
+        score: round(usage * (1.0 - penalty) * 100.0),
+        vocabulary_used,
+        signature_phrases_used,
+        avoided_words_found,
+    }
+}
+
+/// Whether `term` appears in `text` as whole words (both lowercase)
+fn contains_term(text: &str, term: &str) -> bool {
+    let term = term.trim();
+    if term.is_empty() {
+        return false;
+    }
+    text.match_indices(term).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + term.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Ask the model to rate the content against the voice, 0-100
+pub async fn judge(
+    client: &ClaudeClient,
+    voice: &BrandVoice,
+    content: &str,
+) -> Result<(f64, String), AppError> {
+    let voice_block = build_voice_block(&voice.extracted_style).ok_or_else(|| {
+        AppError::Validation("The brand voice has no style to score against".to_string())
+    })?;
+    let (system, user) = build_voice_judge_prompt(&voice_block, content);
+
+    let (response, _input_tokens, _output_tokens) = client
+        .send_message(&system, &user, MAX_TOKENS_VOICE_JUDGE)
+        .await?;
+
+    let parsed: JudgeResponse = serde_json::from_str(strip_code_fences(&response))
+        .map_err(|e| AppError::Api(format!("Failed to parse voice rating: {}", e)))?;
+    Ok((
+        round(parsed.score.clamp(0.0, 100.0)),
+        parsed.notes.trim().to_string(),
+    ))
+}
+
+/// Score content against a voice; the judge only runs when a client is given
+pub async fn score(
+    voice: &BrandVoice,
+    content: &str,
+    judge_client: Option<&ClaudeClient>,
+) -> Result<VoiceScore, AppError> {
+    let profile = VoiceProfile::from_json(&voice.extracted_style)?;
+    let lexical = lexical_score(&profile, content);
+
+    let (judge_score, judge_notes) = match judge_client {
+        Some(client) => {
+            let (score, notes) = judge(client, voice, content).await?;
+            (Some(score), Some(notes).filter(|n| !n.is_empty()))
+        }
+        None => (None, None),
+    };
+
+    Ok(VoiceScore {
+        brand_voice_id: voice.id.clone(),
+        score: combine(lexical.score, judge_score),
+        lexical_score: lexical.score,
+        judge_score,
+        judge_notes,
+        vocabulary_used: lexical.vocabulary_used,
+        signature_phrases_used: lexical.signature_phrases_used,
+        avoided_words_found: lexical.avoided_words_found,
+    })
+}
+
+fn combine(lexical: f64, judge: Option<f64>) -> f64 {
+    match judge {
+        Some(judge) => round(judge * JUDGE_WEIGHT + lexical * (1.0 - JUDGE_WEIGHT)),
+        None => lexical,
+    }
+}
+
+fn round(score: f64) -> f64 {
+    (score * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> VoiceProfile {
+        VoiceProfile {
+            tone: "Warm".to_string(),
+            vocabulary: [
+                "sun-drenched",
+                "gathering",
+                "effortless",
+                "retreat",
+                "curated",
+            ]
+            .map(String::from)
+            .to_vec(),
+            sentence_patterns: "Short".to_string(),
+            themes: vec!["light".to_string()],
+            signature_phrases: vec!["welcome home".to_string()],
+            avoids: vec!["cozy".to_string(), "must-see".to_string()],
+            formatting: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_contains_term_matches_whole_words() {
+        assert!(contains_term("a sun-drenched retreat.", "sun-drenched"));
+        assert!(contains_term("welcome home!", "welcome home"));
+        assert!(!contains_term("retreats abound", "retreat"));
+        assert!(!contains_term("anything", ""));
+    }
+
+    #[test]
+    fn test_lexical_score() {
+        let on_voice =
+            "Welcome home to a sun-drenched retreat made for gathering, with effortless flow.";
+        let score = lexical_score(&profile(), on_voice);
+        assert_eq!(score.vocabulary_used.len(), 4);
+        assert_eq!(score.signature_phrases_used, vec!["welcome home"]);
+        assert_eq!(score.score, 100.0);
+
+        // Half the vocabulary target, no signature phrase, one avoided word
+        let off_voice = "A cozy retreat with a curated kitchen.";
+        let score = lexical_score(&profile(), off_voice);
+        assert_eq!(score.avoided_words_found, vec!["cozy"]);
+        assert!((26.2..=26.3).contains(&score.score));
+
+        // Avoided words can take the score to zero
+        let score = lexical_score(&profile(), "Cozy! A must-see.");
+        assert_eq!(score.score, 0.0);
+    }
+
+    #[test]
+    fn test_combine_weights_judge() {
+        assert_eq!(combine(50.0, None), 50.0);
+        assert_eq!(combine(50.0, Some(100.0)), 80.0);
+    }
+}
@@ -0,0 +1,443 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Row, SqlitePool};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::error::AppError;
+
+pub const FORMAT: &str = "realestate-backup";
+pub const FORMAT_VERSION: u32 = 1;
+
+pub(crate) const MANIFEST_ENTRY: &str = "manifest.json";
+pub(crate) const DB_ENTRY: &str = "realestate.db";
+pub(crate) const PHOTOS_ENTRY: &str = "photos";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub format: String,
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: String,
+    /// Latest applied sqlx migration in the backed-up database
+    pub migration_version: i64,
+    /// Photos directory on the machine that made the backup. Photo paths in
+    /// the database are absolute, so restore rewrites this prefix.
+    pub photos_dir: String,
+    pub property_count: i64,
+    pub listing_count: i64,
+    pub photo_count: i64,
+    pub file_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFileInfo {
+    pub path: String,
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub modified_at: String,
+}
+
+/// Latest migration version compiled into this build of the app
+pub fn latest_migration_version() -> i64 {
+    sqlx::migrate!("./migrations")
+        .iter()
+        .map(|m| m.version)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Write a backup archive of the database and every photo to `dest`.
+///
+/// The database is copied with `VACUUM INTO`, which takes a consistent
+/// snapshot through SQLite itself and is safe while the app holds the
+/// database open in WAL mode.
+pub async fn create_backup(
+    pool: &SqlitePool,
+    app_data_dir: &Path,
+    dest: &Path,
+) -> Result<BackupManifest, AppError> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let snapshot = dest.with_extension("db.tmp");
+    if snapshot.exists() {
+        std::fs::remove_file(&snapshot)?;
+    }
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(snapshot.to_string_lossy().to_string())
+        .execute(pool)
+        .await?;
+
+    // Count from the snapshot itself, since the app may write in between
+    let counts = match snapshot_counts(&snapshot).await {
+        Ok(counts) => counts,
+        Err(e) => {
+            let _ = std::fs::remove_file(&snapshot);
+            return Err(e);
+        }
+    };
+
+    let photos_dir = app_data_dir.join(PHOTOS_ENTRY);
+    let mut manifest = BackupManifest {
+        format: FORMAT.to_string(),
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        migration_version: counts.migration_version,
+        photos_dir: photos_dir.to_string_lossy().to_string(),
+        property_count: counts.properties,
+        listing_count: counts.listings,
+        photo_count: counts.photos,
+        file_count: 0,
+    };
+
+    let dest = dest.to_path_buf();
+    let snapshot_path = snapshot.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        write_archive(&dest, &mut manifest, &snapshot_path, &photos_dir).map(|_| manifest)
+    })
+    .await
+    .map_err(|e| AppError::Backup(format!("Backup task failed: {}", e)));
+
+    let _ = std::fs::remove_file(&snapshot);
+    result?
+}
+
+/// What a database snapshot holds, for its manifest
+struct SnapshotCounts {
+    migration_version: i64,
+    properties: i64,
+    listings: i64,
+    photos: i64,
+}
+
+async fn snapshot_counts(snapshot: &Path) -> Result<SnapshotCounts, AppError> {
+    let options = SqliteConnectOptions::new()
+        .filename(snapshot)
+        .read_only(true);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await?;
+
+    let counts = sqlx::query(
+        "SELECT
+            (SELECT COUNT(*) FROM properties) AS properties,
+            (SELECT COUNT(*) FROM listings) AS listings,
+            (SELECT COUNT(*) FROM photos) AS photos,
+            (SELECT COALESCE(MAX(version), 0) FROM _sqlx_migrations WHERE success = 1) AS migration",
+    )
+    .fetch_one(&pool)
+    .await;
+    pool.close().await;
+
+    let counts = counts?;
+    Ok(SnapshotCounts {
+        migration_version: counts.get("migration"),
+        properties: counts.get("properties"),
+        listings: counts.get("listings"),
+        photos: counts.get("photos"),
+    })
+}
+
+fn write_archive(
+    dest: &Path,
+    manifest: &mut BackupManifest,
+    db_snapshot: &Path,
+    photos_dir: &Path,
+) -> Result<(), AppError> {
+    let mut photo_files = Vec::new();
+    if photos_dir.exists() {
+        collect_files(photos_dir, &mut photo_files)?;
+    }
+    manifest.file_count = photo_files.len() + 1;
+
+    // Write next to the destination and rename so a failed backup never
+    // leaves a truncated archive behind
+    let partial = dest.with_extension("partial");
+    let write = || -> Result<(), AppError> {
+        let mut zip = ZipWriter::new(File::create(&partial)?);
+        let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        // Photos are already compressed
+        let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+
+        zip.start_file(MANIFEST_ENTRY, deflated)
+            .map_err(zip_error)?;
+        zip.write_all(&serde_json::to_vec_pretty(manifest)?)?;
+
+        zip.start_file(DB_ENTRY, deflated).map_err(zip_error)?;
+        std::io::copy(&mut File::open(db_snapshot)?, &mut zip)?;
+
+        for path in &photo_files {
+            let relative = path.strip_prefix(photos_dir).map_err(|e| {
+                AppError::Backup(format!("Unexpected photo path {}: {}", path.display(), e))
+            })?;
+            let name = Path::new(PHOTOS_ENTRY).join(relative);
+            let name = name.to_string_lossy().replace('\\', "/");
+
+            zip.start_file(name, stored).map_err(zip_error)?;
+            std::io::copy(&mut File::open(path)?, &mut zip)?;
+        }
+
+        zip.finish().map_err(zip_error)?;
+        Ok(())
+    };
+
+    if let Err(e) = write() {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+
+    std::fs::rename(&partial, dest)?;
+    Ok(())
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), AppError> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Read and validate the manifest of a backup archive
+pub fn read_manifest(archive_path: &Path) -> Result<BackupManifest, AppError> {
+    let mut archive = ZipArchive::new(File::open(archive_path)?).map_err(zip_error)?;
+    read_manifest_from(&mut archive)
+}
+
+pub(crate) fn read_manifest_from(
+    archive: &mut ZipArchive<File>,
+) -> Result<BackupManifest, AppError> {
+    let mut contents = String::new();
+    archive
+        .by_name(MANIFEST_ENTRY)
+        .map_err(|_| {
+            AppError::Backup("Not a backup archive: manifest.json is missing".to_string())
+        })?
+        .read_to_string(&mut contents)?;
+
+    let manifest: BackupManifest = serde_json::from_str(&contents)
+        .map_err(|e| AppError::Backup(format!("Invalid backup manifest: {}", e)))?;
+
+    if manifest.format != FORMAT {
+        return Err(AppError::Backup(format!(
+            "Not a backup archive (format '{}')",
+            manifest.format
+        )));
+    }
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(AppError::Backup(
+            "This backup was made by a newer version of the app. Please update before restoring."
+                .to_string(),
+        ));
+    }
+    if manifest.migration_version > latest_migration_version() {
+        return Err(AppError::Backup(
+            "This backup's database is newer than this version of the app. Please update before restoring."
+                .to_string(),
+        ));
+    }
+
+    Ok(manifest)
+}
+
+/// Backups in `dir` whose file name starts with `prefix`, newest first
+pub fn list_backups(dir: &Path, prefix: &str) -> Result<Vec<BackupFileInfo>, AppError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if !file_name.starts_with(prefix) || !file_name.ends_with(".zip") {
+            continue;
+        }
+
+        let metadata = entry.metadata()?;
+        let modified: chrono::DateTime<chrono::Utc> = metadata.modified()?.into();
+        backups.push(BackupFileInfo {
+            path: entry.path().to_string_lossy().to_string(),
+            file_name,
+            size_bytes: metadata.len(),
+            modified_at: modified.to_rfc3339(),
+        });
+    }
+
+    // File names embed a sortable timestamp
+    backups.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+    Ok(backups)
+}
+
+/// Delete all but the newest `keep` backups matching `prefix`
+pub fn prune_backups(dir: &Path, prefix: &str, keep: usize) -> Result<usize, AppError> {
+    let backups = list_backups(dir, prefix)?;
+    let mut removed = 0;
+    for backup in backups.iter().skip(keep) {
+        std::fs::remove_file(&backup.path)?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// File name for a new backup, e.g. `auto-backup-20240301-093000.zip`
+pub fn backup_file_name(prefix: &str) -> String {
+    format!(
+        "{}{}.zip",
+        prefix,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    )
+}
+
+pub(crate) fn zip_error(e: zip::result::ZipError) -> AppError {
+    AppError::Backup(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{init_pool, properties};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("realestate-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_backup_contains_database_photos_and_manifest() {
+        let app_dir = temp_dir("backup-app");
+        // VACUUM INTO from an in-memory database never reaches the disk
+        let pool = init_pool(&app_dir.join(DB_ENTRY)).await.unwrap();
+        let photo_dir = app_dir.join("photos").join("prop1").join("thumbs");
+        std::fs::create_dir_all(&photo_dir).unwrap();
+        std::fs::write(app_dir.join("photos/prop1/a.jpg"), b"original").unwrap();
+        std::fs::write(photo_dir.join("a.jpg"), b"thumb").unwrap();
+
+        let dest = app_dir.join("backups").join("backup.zip");
+        let manifest = create_backup(&pool, &app_dir, &dest).await.unwrap();
+
+        assert_eq!(manifest.format, FORMAT);
+        assert_eq!(manifest.migration_version, latest_migration_version());
+        assert_eq!(manifest.file_count, 3);
+        assert!(!dest.with_extension("db.tmp").exists());
+
+        let mut archive = ZipArchive::new(File::open(&dest).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "manifest.json",
+                "photos/prop1/a.jpg",
+                "photos/prop1/thumbs/a.jpg",
+                "realestate.db"
+            ]
+        );
+
+        let mut header = [0u8; 16];
+        archive
+            .by_name(DB_ENTRY)
+            .unwrap()
+            .read_exact(&mut header)
+            .unwrap();
+        assert_eq!(&header, b"SQLite format 3\0");
+
+        assert_eq!(
+            read_manifest(&dest).unwrap().photos_dir,
+            manifest.photos_dir
+        );
+        std::fs::remove_dir_all(&app_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_counts_come_from_the_snapshot() {
+        let app_dir = temp_dir("backup-snapshot");
+        let pool = init_pool(&app_dir.join(DB_ENTRY)).await.unwrap();
+        let snapshot = app_dir.join("snapshot.db");
+        sqlx::query("VACUUM INTO ?")
+            .bind(snapshot.to_string_lossy().to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // A write after the snapshot isn't part of the backup
+        properties::create(&pool, crate::db::test_property_input())
+            .await
+            .unwrap();
+
+        let counts = snapshot_counts(&snapshot).await.unwrap();
+        assert_eq!(counts.properties, 0);
+        assert_eq!(counts.migration_version, latest_migration_version());
+        std::fs::remove_dir_all(&app_dir).unwrap();
+    }
+
+    #[test]
+    fn test_manifest_from_newer_app_rejected() {
+        let dir = temp_dir("backup-newer");
+        let path = dir.join("backup.zip");
+
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        zip.start_file(MANIFEST_ENTRY, FileOptions::default())
+            .unwrap();
+        let manifest = serde_json::json!({
+            "format": FORMAT,
+            "formatVersion": FORMAT_VERSION,
+            "appVersion": "99.0.0",
+            "createdAt": "2030-01-01T00:00:00Z",
+            "migrationVersion": latest_migration_version() + 1,
+            "photosDir": "/tmp/photos",
+            "propertyCount": 0,
+            "listingCount": 0,
+            "photoCount": 0,
+            "fileCount": 1
+        });
+        zip.write_all(manifest.to_string().as_bytes()).unwrap();
+        zip.finish().unwrap();
+
+        let err = read_manifest(&path).unwrap_err();
+        assert!(err.to_string().contains("newer"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_prune_keeps_newest() {
+        let dir = temp_dir("backup-prune");
+        for stamp in ["20240101-000000", "20240102-000000", "20240103-000000"] {
+            std::fs::write(dir.join(format!("auto-backup-{}.zip", stamp)), b"x").unwrap();
+        }
+        std::fs::write(dir.join("manual.zip"), b"x").unwrap();
+
+        assert_eq!(prune_backups(&dir, "auto-backup-", 2).unwrap(), 1);
+        let left: Vec<String> = list_backups(&dir, "auto-backup-")
+            .unwrap()
+            .into_iter()
+            .map(|b| b.file_name)
+            .collect();
+        assert_eq!(
+            left,
+            vec![
+                "auto-backup-20240103-000000.zip",
+                "auto-backup-20240102-000000.zip"
+            ]
+        );
+        assert!(dir.join("manual.zip").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
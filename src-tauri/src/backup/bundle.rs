@@ -0,0 +1,881 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::archive::{zip_error, PHOTOS_ENTRY};
+use crate::ai::prompts::DEFAULT_LANGUAGE;
+use crate::db::brand_voice::{self, BrandVoice};
+use crate::db::listings::{self, Listing};
+use crate::db::photos;
+use crate::db::prompt_presets::{self, PromptPreset};
+use crate::db::properties::{self, Property};
+use crate::error::AppError;
+
+pub const FORMAT: &str = "realestate-property-bundle";
+pub const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "bundle.json";
+
+/// One property with everything needed to recreate it on another install.
+/// Ids are the exporting install's and are only used to link records
+/// within the bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyBundle {
+    pub format: String,
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at: String,
+    pub property: BundleProperty,
+    pub listings: Vec<BundleListing>,
+    pub photos: Vec<BundlePhoto>,
+    /// Brand voices used by the bundled listings
+    pub brand_voices: Vec<BrandVoice>,
+    /// Custom presets the bundled listings were written with
+    #[serde(default)]
+    pub prompt_presets: Vec<PromptPreset>,
+}
+
+/// The bundled property. Agents belong to an install, so the assigned agent
+/// is not carried over.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleProperty {
+    pub id: String,
+    pub address: String,
+    pub city: String,
+    pub state: String,
+    pub zip: String,
+    pub beds: i64,
+    pub baths: f64,
+    pub sqft: i64,
+    pub price: i64,
+    pub property_type: String,
+    pub year_built: Option<i64>,
+    pub lot_size: Option<String>,
+    pub parking: Option<String>,
+    pub key_features: Vec<String>,
+    pub neighborhood: Option<String>,
+    pub neighborhood_highlights: Vec<String>,
+    pub school_district: Option<String>,
+    pub nearby_amenities: Vec<String>,
+    pub agent_notes: Option<String>,
+    pub status: String,
+    pub listed_at: Option<String>,
+    pub pending_at: Option<String>,
+    pub sold_at: Option<String>,
+    pub sold_price: Option<i64>,
+    pub off_market_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleListing {
+    pub id: String,
+    pub content: String,
+    pub generation_type: String,
+    pub style: Option<String>,
+    pub tone: Option<String>,
+    pub length: Option<String>,
+    pub seo_keywords: Vec<String>,
+    pub brand_voice_id: Option<String>,
+    pub tokens_used: i64,
+    pub generation_cost_cents: i64,
+    pub is_favorite: bool,
+    pub created_at: String,
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Bundle id of the listing this one was translated from
+    #[serde(default)]
+    pub source_listing_id: Option<String>,
+    #[serde(default)]
+    pub variant_group_id: Option<String>,
+    /// Bundle id of the custom preset the copy was written with
+    #[serde(default)]
+    pub prompt_preset_id: Option<String>,
+    #[serde(default)]
+    pub voice_score: Option<f64>,
+    #[serde(default)]
+    pub voice_score_details: Option<String>,
+    #[serde(default)]
+    pub unsupported_claims: Option<i64>,
+    #[serde(default)]
+    pub fact_check_details: Option<String>,
+}
+
+fn default_language() -> String {
+    DEFAULT_LANGUAGE.to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundlePhoto {
+    pub id: String,
+    pub filename: String,
+    pub sort_order: i64,
+    pub caption: Option<String>,
+    #[serde(default)]
+    pub alt_text: Option<String>,
+    #[serde(default)]
+    pub room: Option<String>,
+    /// Carried over so the imported photos are still recognized as
+    /// duplicates and don't need analyzing again
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    #[serde(default)]
+    pub detected_features: Vec<String>,
+    #[serde(default)]
+    pub quality_score: Option<i64>,
+    #[serde(default)]
+    pub analyzed_at: Option<String>,
+    #[serde(default)]
+    pub hero_score: Option<f64>,
+    /// Archive entry holding the original image
+    pub original_entry: String,
+    /// Archive entry holding the thumbnail
+    pub thumbnail_entry: String,
+}
+
+fn json_array(value: &str) -> Vec<String> {
+    serde_json::from_str(value).unwrap_or_default()
+}
+
+impl From<&Property> for BundleProperty {
+    fn from(p: &Property) -> Self {
+        BundleProperty {
+            id: p.id.clone(),
+            address: p.address.clone(),
+            city: p.city.clone(),
+            state: p.state.clone(),
+            zip: p.zip.clone(),
+            beds: p.beds,
+            baths: p.baths,
+            sqft: p.sqft,
+            price: p.price,
+            property_type: p.property_type.clone(),
+            year_built: p.year_built,
+            lot_size: p.lot_size.clone(),
+            parking: p.parking.clone(),
+            key_features: json_array(&p.key_features),
+            neighborhood: p.neighborhood.clone(),
+            neighborhood_highlights: json_array(&p.neighborhood_highlights),
+            school_district: p.school_district.clone(),
+            nearby_amenities: json_array(&p.nearby_amenities),
+            agent_notes: p.agent_notes.clone(),
+            status: p.status.clone(),
+            listed_at: p.listed_at.clone(),
+            pending_at: p.pending_at.clone(),
+            sold_at: p.sold_at.clone(),
+            sold_price: p.sold_price,
+            off_market_at: p.off_market_at.clone(),
+        }
+    }
+}
+
+impl From<&Listing> for BundleListing {
+    fn from(l: &Listing) -> Self {
+        BundleListing {
+            id: l.id.clone(),
+            content: l.content.clone(),
+            generation_type: l.generation_type.clone(),
+            style: l.style.clone(),
+            tone: l.tone.clone(),
+            length: l.length.clone(),
+            seo_keywords: json_array(&l.seo_keywords),
+            brand_voice_id: l.brand_voice_id.clone(),
+            tokens_used: l.tokens_used,
+            generation_cost_cents: l.generation_cost_cents,
+            is_favorite: l.is_favorite,
+            created_at: l.created_at.clone(),
+            language: l.language.clone(),
+            source_listing_id: l.source_listing_id.clone(),
+            variant_group_id: l.variant_group_id.clone(),
+            prompt_preset_id: l.prompt_preset_id.clone(),
+            voice_score: l.voice_score,
+            voice_score_details: l.voice_score_details.clone(),
+            unsupported_claims: l.unsupported_claims,
+            fact_check_details: l.fact_check_details.clone(),
+        }
+    }
+}
+
+fn extension(path: &str, default: &str) -> String {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// Write a bundle of one property, its listings, photos, brand voices and
+/// custom presets to `dest`
+pub async fn export_bundle(
+    pool: &SqlitePool,
+    property_id: &str,
+    dest: &Path,
+) -> Result<PropertyBundle, AppError> {
+    let property = properties::get(pool, property_id).await?;
+    let listings = listings::list_by_property(pool, property_id).await?;
+    let photos = photos::list_by_property(pool, property_id).await?;
+    let content_hashes = photos::content_hashes_by_id(pool, property_id).await?;
+
+    let mut brand_voices: Vec<BrandVoice> = Vec::new();
+    for voice_id in listings.iter().filter_map(|l| l.brand_voice_id.as_deref()) {
+        if brand_voices.iter().any(|v| v.id == voice_id) {
+            continue;
+        }
+        // A deleted voice leaves the listing without one
+        if let Ok(voice) = brand_voice::get(pool, voice_id).await {
+            brand_voices.push(voice);
+        }
+    }
+
+    let mut prompt_presets: Vec<PromptPreset> = Vec::new();
+    for preset_id in listings
+        .iter()
+        .filter_map(|l| l.prompt_preset_id.as_deref())
+    {
+        if prompt_presets.iter().any(|p| p.id == preset_id) {
+            continue;
+        }
+        if let Ok(preset) = prompt_presets::get(pool, preset_id).await {
+            prompt_presets.push(preset);
+        }
+    }
+
+    let mut files = Vec::new();
+    let bundle_photos = photos
+        .iter()
+        .map(|photo| {
+            let original_entry = format!(
+                "{}/{}.{}",
+                PHOTOS_ENTRY,
+                photo.id,
+                extension(&photo.original_path, "jpg")
+            );
+            let thumbnail_entry = format!("{}/thumbs/{}.jpg", PHOTOS_ENTRY, photo.id);
+            files.push((original_entry.clone(), PathBuf::from(&photo.original_path)));
+            files.push((
+                thumbnail_entry.clone(),
+                PathBuf::from(&photo.thumbnail_path),
+            ));
+            BundlePhoto {
+                id: photo.id.clone(),
+                filename: photo.filename.clone(),
+                sort_order: photo.sort_order,
+                caption: photo.caption.clone(),
+                alt_text: photo.alt_text.clone(),
+                room: photo.room.clone(),
+                content_hash: content_hashes.get(&photo.id).cloned(),
+                detected_features: json_array(&photo.detected_features),
+                quality_score: photo.quality_score,
+                analyzed_at: photo.analyzed_at.clone(),
+                hero_score: photo.hero_score,
+                original_entry,
+                thumbnail_entry,
+            }
+        })
+        .collect();
+
+    let bundle = PropertyBundle {
+        format: FORMAT.to_string(),
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        property: BundleProperty::from(&property),
+        listings: listings.iter().map(BundleListing::from).collect(),
+        photos: bundle_photos,
+        brand_voices,
+        prompt_presets,
+    };
+
+    let dest = dest.to_path_buf();
+    let manifest = serde_json::to_vec_pretty(&bundle)?;
+    tokio::task::spawn_blocking(move || write_bundle(&dest, &manifest, &files))
+        .await
+        .map_err(|e| AppError::Backup(format!("Export task failed: {}", e)))??;
+
+    Ok(bundle)
+}
+
+fn write_bundle(dest: &Path, manifest: &[u8], files: &[(String, PathBuf)]) -> Result<(), AppError> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let partial = dest.with_extension("partial");
+    let write = || -> Result<(), AppError> {
+        let mut zip = ZipWriter::new(File::create(&partial)?);
+        let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+
+        zip.start_file(MANIFEST_ENTRY, deflated)
+            .map_err(zip_error)?;
+        zip.write_all(manifest)?;
+
+        for (name, path) in files {
+            let mut file = File::open(path).map_err(|e| {
+                AppError::Backup(format!("Failed to read photo {}: {}", path.display(), e))
+            })?;
+            zip.start_file(name.as_str(), stored).map_err(zip_error)?;
+            std::io::copy(&mut file, &mut zip)?;
+        }
+
+        zip.finish().map_err(zip_error)?;
+        Ok(())
+    };
+
+    if let Err(e) = write() {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+
+    std::fs::rename(&partial, dest)?;
+    Ok(())
+}
+
+/// Read and validate the manifest of a property bundle
+pub fn read_bundle(archive: &mut ZipArchive<File>) -> Result<PropertyBundle, AppError> {
+    let mut contents = String::new();
+    archive
+        .by_name(MANIFEST_ENTRY)
+        .map_err(|_| AppError::Backup("Not a property bundle: bundle.json is missing".to_string()))?
+        .read_to_string(&mut contents)?;
+
+    let bundle: PropertyBundle = serde_json::from_str(&contents)
+        .map_err(|e| AppError::Backup(format!("Invalid property bundle: {}", e)))?;
+
+    if bundle.format != FORMAT {
+        return Err(AppError::Backup(format!(
+            "Not a property bundle (format '{}')",
+            bundle.format
+        )));
+    }
+    if bundle.format_version > FORMAT_VERSION {
+        return Err(AppError::Backup(
+            "This bundle was made by a newer version of the app. Please update before importing."
+                .to_string(),
+        ));
+    }
+
+    Ok(bundle)
+}
+
+/// Where an imported photo's files ended up
+struct ImportedPhoto {
+    id: String,
+    original_path: String,
+    thumbnail_path: String,
+}
+
+/// Unpack the bundle's photos into the new property's photo directory,
+/// keyed by the photo id in the bundle
+fn extract_photos(
+    archive_path: &Path,
+    photos_dir: &Path,
+) -> Result<(PropertyBundle, HashMap<String, ImportedPhoto>), AppError> {
+    let mut archive = ZipArchive::new(File::open(archive_path)?).map_err(zip_error)?;
+    let bundle = read_bundle(&mut archive)?;
+
+    let thumbs_dir = photos_dir.join("thumbs");
+    std::fs::create_dir_all(&thumbs_dir)?;
+
+    let mut imported = HashMap::new();
+    for photo in &bundle.photos {
+        let id = uuid::Uuid::new_v4().to_string();
+        let original_path = photos_dir.join(format!(
+            "{}.{}",
+            id,
+            extension(&photo.original_entry, "jpg")
+        ));
+        let thumbnail_path = thumbs_dir.join(format!("{}.jpg", id));
+
+        for (entry, path) in [
+            (&photo.original_entry, &original_path),
+            (&photo.thumbnail_entry, &thumbnail_path),
+        ] {
+            let mut file = archive
+                .by_name(entry)
+                .map_err(|_| AppError::Backup(format!("Bundle is missing {}", entry)))?;
+            std::io::copy(&mut file, &mut File::create(path)?)?;
+        }
+
+        imported.insert(
+            photo.id.clone(),
+            ImportedPhoto {
+                id,
+                original_path: original_path.to_string_lossy().to_string(),
+                thumbnail_path: thumbnail_path.to_string_lossy().to_string(),
+            },
+        );
+    }
+
+    Ok((bundle, imported))
+}
+
+/// Create a property from a bundle with fresh ids. Brand voices that already
+/// exist here (same name and style) and presets with the same kind and key
+/// are reused rather than duplicated.
+pub async fn import_bundle(
+    pool: &SqlitePool,
+    app_data_dir: &Path,
+    archive_path: &Path,
+) -> Result<Property, AppError> {
+    let property_id = uuid::Uuid::new_v4().to_string();
+    let photos_dir = app_data_dir.join(PHOTOS_ENTRY).join(&property_id);
+
+    let extracted = {
+        let archive_path = archive_path.to_path_buf();
+        let photos_dir = photos_dir.clone();
+        tokio::task::spawn_blocking(move || extract_photos(&archive_path, &photos_dir))
+            .await
+            .map_err(|e| AppError::Backup(format!("Import task failed: {}", e)))?
+    };
+
+    let result = match extracted {
+        Ok((bundle, photos)) => insert_bundle(pool, &property_id, &bundle, &photos).await,
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_dir_all(&photos_dir);
+        return Err(e);
+    }
+
+    properties::get(pool, &property_id).await
+}
+
+async fn insert_bundle(
+    pool: &SqlitePool,
+    property_id: &str,
+    bundle: &PropertyBundle,
+    photos: &HashMap<String, ImportedPhoto>,
+) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    let mut voice_ids: HashMap<&str, String> = HashMap::new();
+    for voice in &bundle.brand_voices {
+        let existing: Option<String> = sqlx::query_scalar(
+            "SELECT id FROM brand_voices WHERE name = ? AND extracted_style = ?",
+        )
+        .bind(&voice.name)
+        .bind(&voice.extracted_style)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let id = match existing {
+            Some(id) => id,
+            None => {
+                let id = uuid::Uuid::new_v4().to_string();
+                sqlx::query(
+                    "INSERT INTO brand_voices (id, name, description, extracted_style, source_listings, sample_count, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(&id)
+                .bind(&voice.name)
+                .bind(&voice.description)
+                .bind(&voice.extracted_style)
+                .bind(&voice.source_listings)
+                .bind(voice.sample_count)
+                .bind(&voice.created_at)
+                .bind(&voice.updated_at)
+                .execute(&mut *tx)
+                .await?;
+                // History doesn't travel with the bundle; the imported style
+                // starts a new one
+                brand_voice::record_version(
+                    &mut tx,
+                    &id,
+                    &voice.extracted_style,
+                    voice.sample_count,
+                    "extracted",
+                )
+                .await?;
+                id
+            }
+        };
+        voice_ids.insert(voice.id.as_str(), id);
+    }
+
+    let mut preset_ids: HashMap<&str, String> = HashMap::new();
+    for preset in &bundle.prompt_presets {
+        let existing: Option<String> =
+            sqlx::query_scalar("SELECT id FROM prompt_presets WHERE kind = ? AND key = ?")
+                .bind(&preset.kind)
+                .bind(&preset.key)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        let id = match existing {
+            Some(id) => id,
+            None => {
+                let id = uuid::Uuid::new_v4().to_string();
+                sqlx::query(
+                    "INSERT INTO prompt_presets (id, kind, key, name, instructions, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(&id)
+                .bind(&preset.kind)
+                .bind(&preset.key)
+                .bind(&preset.name)
+                .bind(&preset.instructions)
+                .bind(&preset.created_at)
+                .bind(&preset.updated_at)
+                .execute(&mut *tx)
+                .await?;
+                id
+            }
+        };
+        preset_ids.insert(preset.id.as_str(), id);
+    }
+
+    let p = &bundle.property;
+    if !properties::STATUSES.contains(&p.status.as_str()) {
+        return Err(AppError::Validation(format!(
+            "Unknown property status '{}' in bundle",
+            p.status
+        )));
+    }
+
+    sqlx::query(
+        "INSERT INTO properties (id, address, city, state, zip, beds, baths, sqft, price, property_type, year_built, lot_size, parking, key_features, neighborhood, neighborhood_highlights, school_district, nearby_amenities, agent_notes, status, listed_at, pending_at, sold_at, sold_price, off_market_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(property_id)
+    .bind(&p.address)
+    .bind(&p.city)
+    .bind(&p.state)
+    .bind(&p.zip)
+    .bind(p.beds)
+    .bind(p.baths)
+    .bind(p.sqft)
+    .bind(p.price)
+    .bind(&p.property_type)
+    .bind(p.year_built)
+    .bind(&p.lot_size)
+    .bind(&p.parking)
+    .bind(serde_json::to_string(&p.key_features)?)
+    .bind(&p.neighborhood)
+    .bind(serde_json::to_string(&p.neighborhood_highlights)?)
+    .bind(&p.school_district)
+    .bind(serde_json::to_string(&p.nearby_amenities)?)
+    .bind(&p.agent_notes)
+    .bind(&p.status)
+    .bind(&p.listed_at)
+    .bind(&p.pending_at)
+    .bind(&p.sold_at)
+    .bind(p.sold_price)
+    .bind(&p.off_market_at)
+    .execute(&mut *tx)
+    .await?;
+
+    properties::record_status(
+        &mut tx,
+        property_id,
+        &p.status,
+        p.price,
+        Some("Imported from bundle"),
+        None,
+    )
+    .await?;
+
+    // Listings get new ids; translations are linked to their original's new
+    // id and inserted after it, and variants share a new group id
+    let listing_ids: HashMap<&str, String> = bundle
+        .listings
+        .iter()
+        .map(|l| (l.id.as_str(), uuid::Uuid::new_v4().to_string()))
+        .collect();
+    let variant_group_ids: HashMap<&str, String> = bundle
+        .listings
+        .iter()
+        .filter_map(|l| l.variant_group_id.as_deref())
+        .map(|id| (id, uuid::Uuid::new_v4().to_string()))
+        .collect();
+    let mut ordered: Vec<&BundleListing> = bundle.listings.iter().collect();
+    ordered.sort_by_key(|l| l.source_listing_id.is_some());
+
+    for listing in ordered {
+        let brand_voice_id = listing
+            .brand_voice_id
+            .as_deref()
+            .and_then(|id| voice_ids.get(id));
+        let source_listing_id = listing
+            .source_listing_id
+            .as_deref()
+            .and_then(|id| listing_ids.get(id));
+        let variant_group_id = listing
+            .variant_group_id
+            .as_deref()
+            .and_then(|id| variant_group_ids.get(id));
+        let prompt_preset_id = listing
+            .prompt_preset_id
+            .as_deref()
+            .and_then(|id| preset_ids.get(id));
+
+        sqlx::query(
+            "INSERT INTO listings (id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents, is_favorite, created_at, language, source_listing_id, variant_group_id, prompt_preset_id, voice_score, voice_score_details, unsupported_claims, fact_check_details)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&listing_ids[listing.id.as_str()])
+        .bind(property_id)
+        .bind(&listing.content)
+        .bind(&listing.generation_type)
+        .bind(&listing.style)
+        .bind(&listing.tone)
+        .bind(&listing.length)
+        .bind(serde_json::to_string(&listing.seo_keywords)?)
+        .bind(brand_voice_id)
+        .bind(listing.tokens_used)
+        .bind(listing.generation_cost_cents)
+        .bind(listing.is_favorite)
+        .bind(&listing.created_at)
+        .bind(&listing.language)
+        .bind(source_listing_id)
+        .bind(variant_group_id)
+        .bind(prompt_preset_id)
+        .bind(listing.voice_score)
+        .bind(&listing.voice_score_details)
+        .bind(listing.unsupported_claims)
+        .bind(&listing.fact_check_details)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for photo in &bundle.photos {
+        let files = &photos[&photo.id];
+        sqlx::query(
+            "INSERT INTO photos (id, property_id, filename, original_path, thumbnail_path, sort_order, caption, alt_text, room, content_hash, detected_features, quality_score, analyzed_at, hero_score)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&files.id)
+        .bind(property_id)
+        .bind(&photo.filename)
+        .bind(&files.original_path)
+        .bind(&files.thumbnail_path)
+        .bind(photo.sort_order)
+        .bind(&photo.caption)
+        .bind(&photo.alt_text)
+        .bind(&photo.room)
+        .bind(&photo.content_hash)
+        .bind(serde_json::to_string(&photo.detected_features)?)
+        .bind(photo.quality_score)
+        .bind(&photo.analyzed_at)
+        .bind(photo.hero_score)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::listings::CreateListingInput;
+    use crate::db::test_pool;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("realestate-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    async fn setup(pool: &SqlitePool, app_dir: &Path) -> Property {
+        let property = properties::create(
+            pool,
+            properties::CreatePropertyInput {
+                year_built: Some(1925),
+                key_features: vec!["Bay windows".to_string()],
+                neighborhood: Some("Noe Valley".to_string()),
+                ..crate::db::test_property_input()
+            },
+        )
+        .await
+        .unwrap();
+
+        let voice = brand_voice::create(pool, "Coastal", None, "Breezy and warm", &[])
+            .await
+            .unwrap();
+        let preset = prompt_presets::create(
+            pool,
+            prompt_presets::PromptPresetInput {
+                kind: prompt_presets::PresetKind::Style,
+                name: "Storybook".to_string(),
+                instructions: "Tell the home's story".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+
+        for (content, favorite) in [("Sunny Victorian", true), ("Classic charm", false)] {
+            let listing = listings::save(
+                pool,
+                CreateListingInput {
+                    content: content.to_string(),
+                    seo_keywords: vec!["victorian".to_string()],
+                    brand_voice_id: Some(voice.id.clone()),
+                    tokens_used: 100,
+                    generation_cost_cents: 1,
+                    variant_group_id: Some("group-1".to_string()),
+                    prompt_preset_id: Some(preset.id.clone()),
+                    ..crate::db::test_listing_input(&property.id)
+                },
+            )
+            .await
+            .unwrap();
+            listings::save_voice_score(pool, &listing.id, 82.0, "{}")
+                .await
+                .unwrap();
+            listings::save_fact_check(pool, &listing.id, 0, r#"{"unsupported":0,"claims":[]}"#)
+                .await
+                .unwrap();
+            if favorite {
+                listings::toggle_favorite(pool, &listing.id).await.unwrap();
+            }
+        }
+
+        let photo_dir = app_dir.join("photos").join(&property.id);
+        std::fs::create_dir_all(photo_dir.join("thumbs")).unwrap();
+        for (i, name) in ["front", "kitchen"].iter().enumerate() {
+            let original = photo_dir.join(format!("{}.jpg", name));
+            let thumb = photo_dir.join("thumbs").join(format!("{}.jpg", name));
+            std::fs::write(&original, name.as_bytes()).unwrap();
+            std::fs::write(&thumb, b"thumb").unwrap();
+            photos::insert(
+                pool,
+                name,
+                &property.id,
+                &format!("{}.jpg", name),
+                &original.to_string_lossy(),
+                &thumb.to_string_lossy(),
+                i as i64,
+                Some(&format!("hash-{}", name)),
+            )
+            .await
+            .unwrap();
+        }
+        sqlx::query("UPDATE photos SET caption = 'Curb appeal' WHERE id = 'front'")
+            .execute(pool)
+            .await
+            .unwrap();
+        photos::save_analysis(pool, "front", "exterior_front", &["porch".to_string()], 8)
+            .await
+            .unwrap();
+        photos::save_hero_score(pool, "kitchen", 0.7).await.unwrap();
+
+        property
+    }
+
+    #[tokio::test]
+    async fn test_bundle_roundtrip_creates_new_records() {
+        let source_dir = temp_dir("bundle-source");
+        let target_dir = temp_dir("bundle-target");
+        let source = test_pool().await;
+        let property = setup(&source, &source_dir).await;
+
+        let archive = source_dir.join("oak.zip");
+        let bundle = export_bundle(&source, &property.id, &archive)
+            .await
+            .unwrap();
+        assert_eq!(bundle.listings.len(), 2);
+        assert_eq!(bundle.brand_voices.len(), 1);
+
+        let target = test_pool().await;
+        // The receiving install already has the same voice
+        let existing = brand_voice::create(&target, "Coastal", None, "Breezy and warm", &[])
+            .await
+            .unwrap();
+
+        let imported = import_bundle(&target, &target_dir, &archive).await.unwrap();
+        assert_ne!(imported.id, property.id);
+        assert_eq!(imported.address, "123 Oak Street");
+        assert_eq!(imported.key_features, property.key_features);
+        assert_eq!(imported.status, "active");
+
+        let imported_listings = listings::list_by_property(&target, &imported.id)
+            .await
+            .unwrap();
+        assert_eq!(imported_listings.len(), 2);
+        assert_eq!(
+            imported_listings.iter().filter(|l| l.is_favorite).count(),
+            1
+        );
+        assert!(imported_listings
+            .iter()
+            .all(|l| l.brand_voice_id.as_deref() == Some(existing.id.as_str())));
+        assert_eq!(brand_voice::list_all(&target).await.unwrap().len(), 1);
+        // Variants stay grouped under a new id, with their preset and scores
+        let group = imported_listings[0].variant_group_id.clone();
+        assert!(group.is_some() && group.as_deref() != Some("group-1"));
+        assert!(imported_listings
+            .iter()
+            .all(|l| l.variant_group_id == group));
+        let preset_id = imported_listings[0].prompt_preset_id.clone().unwrap();
+        assert_eq!(
+            prompt_presets::get(&target, &preset_id).await.unwrap().key,
+            "storybook"
+        );
+        assert!(imported_listings.iter().all(|l| l.voice_score == Some(82.0)
+            && l.unsupported_claims == Some(0)
+            && l.fact_check_details.is_some()));
+
+        let imported_photos = photos::list_by_property(&target, &imported.id)
+            .await
+            .unwrap();
+        assert_eq!(imported_photos.len(), 2);
+        assert_eq!(imported_photos[0].filename, "front.jpg");
+        assert_eq!(imported_photos[0].caption.as_deref(), Some("Curb appeal"));
+        assert_eq!(imported_photos[0].quality_score, Some(8));
+        assert_eq!(imported_photos[0].detected_features, r#"["porch"]"#);
+        assert!(imported_photos[0].analyzed_at.is_some());
+        assert_eq!(imported_photos[1].hero_score, Some(0.7));
+        // Re-importing the same files is still caught as a duplicate
+        assert_eq!(
+            photos::content_hashes(&target, &imported.id).await.unwrap(),
+            ["hash-front".to_string(), "hash-kitchen".to_string()]
+                .into_iter()
+                .collect()
+        );
+        assert_ne!(imported_photos[0].id, "front");
+        assert!(imported_photos[0]
+            .original_path
+            .starts_with(&*target_dir.to_string_lossy()));
+        assert_eq!(
+            std::fs::read(&imported_photos[1].original_path).unwrap(),
+            b"kitchen"
+        );
+
+        // Importing twice gives two independent copies
+        let again = import_bundle(&target, &target_dir, &archive).await.unwrap();
+        assert_ne!(again.id, imported.id);
+
+        std::fs::remove_dir_all(&source_dir).unwrap();
+        std::fs::remove_dir_all(&target_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_backup_archives() {
+        let dir = temp_dir("bundle-invalid");
+        let archive = dir.join("backup.zip");
+        let mut zip = ZipWriter::new(File::create(&archive).unwrap());
+        zip.start_file("manifest.json", FileOptions::default())
+            .unwrap();
+        zip.write_all(b"{}").unwrap();
+        zip.finish().unwrap();
+
+        let pool = test_pool().await;
+        assert!(import_bundle(&pool, &dir, &archive).await.is_err());
+        assert!(properties::list_all(&pool).await.unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
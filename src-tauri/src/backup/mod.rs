@@ -0,0 +1,3 @@
+pub mod archive;
+pub mod bundle;
+pub mod restore;
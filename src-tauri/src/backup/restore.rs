@@ -0,0 +1,294 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use zip::ZipArchive;
+
+use super::archive::{read_manifest_from, zip_error, BackupManifest, DB_ENTRY, PHOTOS_ENTRY};
+use crate::error::AppError;
+
+/// Restored data waits here until the next launch, when nothing has the
+/// database open
+const STAGING_DIR: &str = "restore-pending";
+/// The data a restore replaced, kept until the next restore
+const PREVIOUS_DIR: &str = "pre-restore";
+
+/// Unpack a backup into the staging directory and prepare its database for
+/// this machine. The restore takes effect on the next launch via
+/// `apply_pending_restore`.
+pub async fn stage_restore(
+    archive_path: &Path,
+    app_data_dir: &Path,
+) -> Result<BackupManifest, AppError> {
+    let staging = app_data_dir.join(STAGING_DIR);
+
+    let manifest = {
+        let archive_path = archive_path.to_path_buf();
+        let staging = staging.clone();
+        tokio::task::spawn_blocking(move || extract(&archive_path, &staging))
+            .await
+            .map_err(|e| AppError::Backup(format!("Restore task failed: {}", e)))?
+    };
+
+    let result = match manifest {
+        Ok(manifest) => prepare_database(&staging, app_data_dir, &manifest)
+            .await
+            .map(|_| manifest),
+        Err(e) => Err(e),
+    };
+
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(&staging);
+    }
+    result
+}
+
+fn extract(archive_path: &Path, staging: &Path) -> Result<BackupManifest, AppError> {
+    if staging.exists() {
+        std::fs::remove_dir_all(staging)?;
+    }
+    std::fs::create_dir_all(staging)?;
+
+    let mut archive = ZipArchive::new(File::open(archive_path)?).map_err(zip_error)?;
+    let manifest = read_manifest_from(&mut archive)?;
+
+    let mut has_database = false;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(zip_error)?;
+        let name = entry
+            .enclosed_name()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| AppError::Backup(format!("Unsafe path in backup: {}", entry.name())))?;
+
+        if entry.is_dir() {
+            continue;
+        }
+        if name == Path::new(DB_ENTRY) {
+            has_database = true;
+        } else if !name.starts_with(PHOTOS_ENTRY) {
+            // The manifest, or something this version doesn't know about
+            continue;
+        }
+
+        let out_path = staging.join(&name);
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::io::copy(&mut entry, &mut File::create(&out_path)?)?;
+    }
+
+    if !has_database {
+        return Err(AppError::Backup(
+            "Backup does not contain a database".to_string(),
+        ));
+    }
+
+    let mut header = [0u8; 16];
+    File::open(staging.join(DB_ENTRY))?
+        .read_exact(&mut header)
+        .map_err(|_| AppError::Backup("Backup database is corrupt".to_string()))?;
+    if &header != b"SQLite format 3\0" {
+        return Err(AppError::Backup("Backup database is corrupt".to_string()));
+    }
+
+    Ok(manifest)
+}
+
+/// Check the staged database against the manifest and point its photo paths
+/// at this machine's photos directory
+async fn prepare_database(
+    staging: &Path,
+    app_data_dir: &Path,
+    manifest: &BackupManifest,
+) -> Result<(), AppError> {
+    let options = SqliteConnectOptions::new()
+        .filename(staging.join(DB_ENTRY))
+        .journal_mode(SqliteJournalMode::Delete);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await?;
+
+    let version: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(version), 0) FROM _sqlx_migrations WHERE success = 1",
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|_| AppError::Backup("Backup database has no migration history".to_string()))?;
+
+    if version != manifest.migration_version {
+        pool.close().await;
+        return Err(AppError::Backup(format!(
+            "Backup database is at migration {} but the manifest says {}",
+            version, manifest.migration_version
+        )));
+    }
+
+    let photos_dir = app_data_dir
+        .join(PHOTOS_ENTRY)
+        .to_string_lossy()
+        .to_string();
+    if manifest.photos_dir != photos_dir {
+        sqlx::query(
+            "UPDATE photos SET
+                original_path = ?1 || substr(original_path, length(?2) + 1),
+                thumbnail_path = ?1 || substr(thumbnail_path, length(?2) + 1)
+             WHERE substr(original_path, 1, length(?2)) = ?2",
+        )
+        .bind(&photos_dir)
+        .bind(&manifest.photos_dir)
+        .execute(&pool)
+        .await?;
+    }
+
+    pool.close().await;
+    Ok(())
+}
+
+/// Swap a staged restore into place. Must run before the database pool is
+/// opened. Returns whether a restore was applied. The database and photos
+/// are swapped together: if either can't be moved in, the live data is put
+/// back as it was and the restore stays staged.
+pub fn apply_pending_restore(app_data_dir: &Path) -> Result<bool, AppError> {
+    let staging = app_data_dir.join(STAGING_DIR);
+    if !staging.join(DB_ENTRY).exists() {
+        return Ok(false);
+    }
+    // A backup without photos restores to an empty photos directory
+    std::fs::create_dir_all(staging.join(PHOTOS_ENTRY))?;
+
+    let previous = app_data_dir.join(PREVIOUS_DIR);
+    if previous.exists() {
+        std::fs::remove_dir_all(&previous)?;
+    }
+    std::fs::create_dir_all(&previous)?;
+
+    let mut moved_aside = Vec::new();
+    let mut swapped_in = Vec::new();
+    let result = (|| -> Result<(), AppError> {
+        for name in [
+            DB_ENTRY.to_string(),
+            format!("{}-wal", DB_ENTRY),
+            format!("{}-shm", DB_ENTRY),
+            PHOTOS_ENTRY.to_string(),
+        ] {
+            let path = app_data_dir.join(&name);
+            if path.exists() {
+                std::fs::rename(&path, previous.join(&name))?;
+                moved_aside.push(name);
+            }
+        }
+        for name in [DB_ENTRY, PHOTOS_ENTRY] {
+            std::fs::rename(staging.join(name), app_data_dir.join(name))?;
+            swapped_in.push(name);
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        for name in swapped_in {
+            let _ = std::fs::rename(app_data_dir.join(name), staging.join(name));
+        }
+        for name in moved_aside {
+            let _ = std::fs::rename(previous.join(&name), app_data_dir.join(&name));
+        }
+        return Err(e);
+    }
+
+    std::fs::remove_dir_all(&staging)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::archive::create_backup;
+    use crate::db::init_pool;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("realestate-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_restore_onto_another_machine() {
+        let source_dir = temp_dir("restore-source");
+        let target_dir = temp_dir("restore-target");
+
+        let pool = init_pool(&source_dir.join(DB_ENTRY)).await.unwrap();
+        let property = crate::db::properties::create(&pool, crate::db::test_property_input())
+            .await
+            .unwrap();
+
+        let source_photos = source_dir.join("photos").join(&property.id);
+        std::fs::create_dir_all(source_photos.join("thumbs")).unwrap();
+        std::fs::write(source_photos.join("a.jpg"), b"original").unwrap();
+        std::fs::write(source_photos.join("thumbs/a.jpg"), b"thumb").unwrap();
+        crate::db::photos::insert(
+            &pool,
+            "ph1",
+            &property.id,
+            "a.jpg",
+            &source_photos.join("a.jpg").to_string_lossy(),
+            &source_photos.join("thumbs/a.jpg").to_string_lossy(),
+            0,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let archive = source_dir.join("backup.zip");
+        create_backup(&pool, &source_dir, &archive).await.unwrap();
+
+        // The target install already has data that the restore replaces
+        std::fs::write(target_dir.join(DB_ENTRY), b"old database").unwrap();
+
+        let manifest = stage_restore(&archive, &target_dir).await.unwrap();
+        assert_eq!(manifest.property_count, 1);
+        assert!(apply_pending_restore(&target_dir).unwrap());
+        assert!(!apply_pending_restore(&target_dir).unwrap());
+
+        assert_eq!(
+            std::fs::read(target_dir.join("pre-restore").join(DB_ENTRY)).unwrap(),
+            b"old database"
+        );
+        assert_eq!(
+            std::fs::read(target_dir.join("photos").join(&property.id).join("a.jpg")).unwrap(),
+            b"original"
+        );
+
+        let restored = SqlitePoolOptions::new()
+            .connect_with(SqliteConnectOptions::new().filename(target_dir.join(DB_ENTRY)))
+            .await
+            .unwrap();
+        let path: String = sqlx::query_scalar("SELECT original_path FROM photos WHERE id = 'ph1'")
+            .fetch_one(&restored)
+            .await
+            .unwrap();
+        assert_eq!(
+            PathBuf::from(path),
+            target_dir.join("photos").join(&property.id).join("a.jpg")
+        );
+        restored.close().await;
+
+        std::fs::remove_dir_all(&source_dir).unwrap();
+        std::fs::remove_dir_all(&target_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_invalid_archive_leaves_nothing_staged() {
+        let dir = temp_dir("restore-invalid");
+        let archive = dir.join("not-a-backup.zip");
+        std::fs::write(&archive, b"definitely not a zip").unwrap();
+
+        assert!(stage_restore(&archive, &dir).await.is_err());
+        assert!(!dir.join(STAGING_DIR).exists());
+        assert!(!apply_pending_restore(&dir).unwrap());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
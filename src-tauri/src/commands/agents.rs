@@ -0,0 +1,37 @@
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::db::agents::{self, Agent, CreateAgentInput};
+use crate::error::AppError;
+
+#[tauri::command]
+pub async fn create_agent(
+    pool: State<'_, SqlitePool>,
+    input: CreateAgentInput,
+) -> Result<Agent, AppError> {
+    agents::create(&pool, input).await
+}
+
+#[tauri::command]
+pub async fn get_agent(pool: State<'_, SqlitePool>, id: String) -> Result<Agent, AppError> {
+    agents::get(&pool, &id).await
+}
+
+#[tauri::command]
+pub async fn list_agents(pool: State<'_, SqlitePool>) -> Result<Vec<Agent>, AppError> {
+    agents::list_all(&pool).await
+}
+
+#[tauri::command]
+pub async fn update_agent(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    input: CreateAgentInput,
+) -> Result<Agent, AppError> {
+    agents::update(&pool, &id, input).await
+}
+
+#[tauri::command]
+pub async fn delete_agent(pool: State<'_, SqlitePool>, id: String) -> Result<(), AppError> {
+    agents::delete(&pool, &id).await
+}
@@ -0,0 +1,126 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager, State};
+
+use crate::backup::archive::{self, BackupFileInfo, BackupManifest};
+use crate::backup::bundle::{self, PropertyBundle};
+use crate::backup::restore;
+use crate::db::properties::Property;
+use crate::db::settings;
+use crate::error::AppError;
+
+const BACKUPS_DIR: &str = "backups";
+const AUTO_BACKUP_PREFIX: &str = "auto-backup-";
+const SCHEDULER_TICK: Duration = Duration::from_secs(60 * 60);
+
+fn app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Backup(format!("Failed to get app data dir: {}", e)))
+}
+
+#[tauri::command]
+pub async fn create_backup(
+    app_handle: AppHandle,
+    db: State<'_, SqlitePool>,
+    dest_path: String,
+) -> Result<BackupManifest, AppError> {
+    let app_data_dir = app_data_dir(&app_handle)?;
+    archive::create_backup(&db, &app_data_dir, Path::new(&dest_path)).await
+}
+
+/// Stage a backup and restart the app so it is swapped in before the
+/// database is opened again
+#[tauri::command]
+pub async fn restore_backup(
+    app_handle: AppHandle,
+    db: State<'_, SqlitePool>,
+    archive_path: String,
+) -> Result<(), AppError> {
+    let app_data_dir = app_data_dir(&app_handle)?;
+    restore::stage_restore(Path::new(&archive_path), &app_data_dir).await?;
+
+    db.close().await;
+    app_handle.restart()
+}
+
+/// Scheduled backups kept in the app data directory, newest first
+#[tauri::command]
+pub async fn list_backups(app_handle: AppHandle) -> Result<Vec<BackupFileInfo>, AppError> {
+    let dir = app_data_dir(&app_handle)?.join(BACKUPS_DIR);
+    archive::list_backups(&dir, AUTO_BACKUP_PREFIX)
+}
+
+/// Export one property as a bundle another install can import
+#[tauri::command]
+pub async fn export_property_bundle(
+    db: State<'_, SqlitePool>,
+    property_id: String,
+    dest_path: String,
+) -> Result<PropertyBundle, AppError> {
+    bundle::export_bundle(&db, &property_id, Path::new(&dest_path)).await
+}
+
+#[tauri::command]
+pub async fn import_property_bundle(
+    app_handle: AppHandle,
+    db: State<'_, SqlitePool>,
+    archive_path: String,
+) -> Result<Property, AppError> {
+    let app_data_dir = app_data_dir(&app_handle)?;
+    bundle::import_bundle(&db, &app_data_dir, Path::new(&archive_path)).await
+}
+
+async fn setting_or(db: &SqlitePool, key: &str, default: &str) -> String {
+    settings::get(db, key)
+        .await
+        .unwrap_or_else(|_| default.to_string())
+}
+
+/// Make a scheduled backup if they are enabled and one is due
+async fn run_scheduled_backup(db: &SqlitePool, app_data_dir: &Path) -> Result<(), AppError> {
+    if setting_or(db, "auto_backup_enabled", "false").await != "true" {
+        return Ok(());
+    }
+
+    let interval_hours: i64 = setting_or(db, "auto_backup_interval_hours", "24")
+        .await
+        .trim()
+        .parse()
+        .unwrap_or(24)
+        .max(1);
+    let last = setting_or(db, "last_auto_backup_at", "").await;
+    if let Ok(last) = chrono::DateTime::parse_from_rfc3339(&last) {
+        let elapsed = chrono::Utc::now().signed_duration_since(last);
+        if elapsed < chrono::Duration::hours(interval_hours) {
+            return Ok(());
+        }
+    }
+
+    let dir = app_data_dir.join(BACKUPS_DIR);
+    let dest = dir.join(archive::backup_file_name(AUTO_BACKUP_PREFIX));
+    archive::create_backup(db, app_data_dir, &dest).await?;
+    settings::set(db, "last_auto_backup_at", &chrono::Utc::now().to_rfc3339()).await?;
+
+    let keep: usize = setting_or(db, "auto_backup_keep", "7")
+        .await
+        .trim()
+        .parse()
+        .unwrap_or(7)
+        .max(1);
+    archive::prune_backups(&dir, AUTO_BACKUP_PREFIX, keep)?;
+    Ok(())
+}
+
+/// Check hourly for a due scheduled backup. Runs for the life of the app.
+pub(crate) async fn run_scheduled_backups(db: SqlitePool, app_data_dir: PathBuf) {
+    loop {
+        if let Err(e) = run_scheduled_backup(&db, &app_data_dir).await {
+            eprintln!("Scheduled backup failed: {}", e);
+        }
+        tokio::time::sleep(SCHEDULER_TICK).await;
+    }
+}
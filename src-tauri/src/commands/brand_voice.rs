@@ -1,20 +1,32 @@
+use serde::Serialize;
 use sqlx::SqlitePool;
 use tauri::State;
 
-use crate::ai::brand_voice::extract_voice;
+use crate::ai::brand_voice::{extract_voice, merge_voice};
 use crate::ai::client::ClaudeClient;
+use crate::ai::voice_score;
+use crate::db::brand_voice::{BrandVoice, StyleChange, VoiceProfile};
+use crate::db::listings::{self, Listing};
 use crate::db::{brand_voice, settings};
 use crate::error::AppError;
 
-#[tauri::command]
-pub async fn create_brand_voice(
-    db: State<'_, SqlitePool>,
-    name: String,
-    description: Option<String>,
-    sample_listings: Vec<String>,
-) -> Result<brand_voice::BrandVoice, AppError> {
+/// New favorited or edited listings needed before an update is suggested
+const MIN_SUGGESTION_SAMPLES: usize = 2;
+
+/// A retrained style for the agent to review before applying it
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceUpdateSuggestion {
+    pub brand_voice_id: String,
+    /// Listings the proposed style was trained on
+    pub listing_ids: Vec<String>,
+    pub proposed_style: String,
+    pub changes: Vec<StyleChange>,
+}
+
+async fn load_client(db: &SqlitePool) -> Result<ClaudeClient, AppError> {
     // Load API key
-    let api_key = settings::get(&db, "api_key").await?;
+    let api_key = settings::get(db, "api_key").await?;
     if api_key.is_empty() {
         return Err(AppError::Config(
             "No API key configured. Add your Anthropic API key in Settings.".to_string(),
@@ -22,11 +34,21 @@ pub async fn create_brand_voice(
     }
 
     // Load AI model preference
-    let model = settings::get(&db, "ai_model")
+    let model = settings::get(db, "ai_model")
         .await
         .unwrap_or_else(|_| "claude-sonnet-4-5-20250929".to_string());
 
-    let client = ClaudeClient::new(api_key, model);
+    Ok(ClaudeClient::new(api_key, model))
+}
+
+#[tauri::command]
+pub async fn create_brand_voice(
+    db: State<'_, SqlitePool>,
+    name: String,
+    description: Option<String>,
+    sample_listings: Vec<String>,
+) -> Result<brand_voice::BrandVoice, AppError> {
+    let client = load_client(&db).await?;
     let extracted_style = extract_voice(&client, &sample_listings).await?;
 
     let voice = brand_voice::create(
@@ -48,6 +70,223 @@ pub async fn list_brand_voices(
     brand_voice::list_all(&db).await
 }
 
+#[tauri::command]
+pub async fn update_brand_voice(
+    db: State<'_, SqlitePool>,
+    id: String,
+    input: brand_voice::UpdateBrandVoiceInput,
+) -> Result<brand_voice::BrandVoice, AppError> {
+    brand_voice::update(&db, &id, &input).await
+}
+
+/// Add sample listings to a voice and re-extract its style, merged with the
+/// current profile including any manual edits
+#[tauri::command]
+pub async fn retrain_brand_voice(
+    db: State<'_, SqlitePool>,
+    id: String,
+    sample_listings: Vec<String>,
+) -> Result<brand_voice::BrandVoice, AppError> {
+    let sample_listings: Vec<String> = sample_listings
+        .iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let voice = brand_voice::get(&db, &id).await?;
+    let client = load_client(&db).await?;
+    let extracted_style = merge_voice(&client, &voice.extracted_style, &sample_listings).await?;
+
+    brand_voice::add_samples(&db, &id, &sample_listings, &extracted_style).await
+}
+
+#[tauri::command]
+pub async fn list_brand_voice_versions(
+    db: State<'_, SqlitePool>,
+    id: String,
+) -> Result<Vec<brand_voice::BrandVoiceVersion>, AppError> {
+    brand_voice::list_versions(&db, &id).await
+}
+
+#[tauri::command]
+pub async fn restore_brand_voice_version(
+    db: State<'_, SqlitePool>,
+    id: String,
+    version: i64,
+) -> Result<brand_voice::BrandVoice, AppError> {
+    brand_voice::restore_version(&db, &id, version).await
+}
+
+/// Favorited and edited listings the agent can train a voice on
+#[tauri::command]
+pub async fn list_voice_feedback_listings(
+    db: State<'_, SqlitePool>,
+    brand_voice_id: Option<String>,
+) -> Result<Vec<Listing>, AppError> {
+    listings::list_voice_feedback(&db, brand_voice_id.as_deref()).await
+}
+
+/// Listings whose copy isn't already one of the voice's samples
+fn new_samples(voice: &BrandVoice, candidates: Vec<Listing>) -> Vec<Listing> {
+    let sources: Vec<String> = serde_json::from_str(&voice.source_listings).unwrap_or_default();
+    candidates
+        .into_iter()
+        .filter(|l| !sources.iter().any(|s| s.trim() == l.content.trim()))
+        .collect()
+}
+
+/// Build a voice from the agent's own listings, or fold them into an
+/// existing voice when `brand_voice_id` is given
+#[tauri::command]
+pub async fn learn_brand_voice(
+    db: State<'_, SqlitePool>,
+    listing_ids: Vec<String>,
+    brand_voice_id: Option<String>,
+    name: Option<String>,
+) -> Result<BrandVoice, AppError> {
+    let mut selected = Vec::new();
+    for id in &listing_ids {
+        selected.push(listings::get(&db, id).await?);
+    }
+
+    match brand_voice_id {
+        Some(id) => {
+            let voice = brand_voice::get(&db, &id).await?;
+            let samples: Vec<String> = new_samples(&voice, selected)
+                .into_iter()
+                .map(|l| l.content)
+                .collect();
+            if samples.is_empty() {
+                return Err(AppError::Validation(
+                    "The selected listings are already part of this voice.".to_string(),
+                ));
+            }
+            let client = load_client(&db).await?;
+            let extracted_style = merge_voice(&client, &voice.extracted_style, &samples).await?;
+            brand_voice::add_samples(&db, &id, &samples, &extracted_style).await
+        }
+        None => {
+            let name = name
+                .as_deref()
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+                .ok_or_else(|| {
+                    AppError::Validation("A name is required for a new brand voice".to_string())
+                })?;
+            let samples: Vec<String> = selected.into_iter().map(|l| l.content).collect();
+            let client = load_client(&db).await?;
+            let extracted_style = extract_voice(&client, &samples).await?;
+            brand_voice::create(&db, name, None, &extracted_style, &samples).await
+        }
+    }
+}
+
+/// Retrain a voice on listings written with it that the agent has since
+/// favorited or edited, without saving. Returns nothing until enough new
+/// listings have accumulated or when the style wouldn't change; meant to be
+/// checked periodically. The proposed style is kept and returned again until
+/// the feedback or the voice changes, so repeat checks don't retrain.
+#[tauri::command]
+pub async fn suggest_brand_voice_update(
+    db: State<'_, SqlitePool>,
+    id: String,
+) -> Result<Option<VoiceUpdateSuggestion>, AppError> {
+    let voice = brand_voice::get(&db, &id).await?;
+    let candidates = listings::list_voice_feedback(&db, Some(&id)).await?;
+    let fresh = new_samples(&voice, candidates);
+    if fresh.len() < MIN_SUGGESTION_SAMPLES {
+        return Ok(None);
+    }
+
+    let samples: Vec<String> = fresh.iter().map(|l| l.content.clone()).collect();
+    let proposed_style =
+        match brand_voice::get_suggestion(&db, &id, &voice.extracted_style, &samples).await? {
+            Some(style) => style,
+            None => {
+                let client = load_client(&db).await?;
+                let style = merge_voice(&client, &voice.extracted_style, &samples).await?;
+                brand_voice::save_suggestion(&db, &id, &voice.extracted_style, &samples, &style)
+                    .await?;
+                style
+            }
+        };
+    let changes = brand_voice::diff_styles(&voice.extracted_style, &proposed_style)?;
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(VoiceUpdateSuggestion {
+        brand_voice_id: id,
+        listing_ids: fresh.into_iter().map(|l| l.id).collect(),
+        proposed_style,
+        changes,
+    }))
+}
+
+/// Apply a suggestion the agent accepted, adding its listings as samples
+#[tauri::command]
+pub async fn apply_brand_voice_update(
+    db: State<'_, SqlitePool>,
+    id: String,
+    listing_ids: Vec<String>,
+    proposed_style: String,
+) -> Result<BrandVoice, AppError> {
+    let voice = brand_voice::get(&db, &id).await?;
+    let mut profile = VoiceProfile::from_json(&proposed_style)?;
+    profile.normalize();
+    profile.validate()?;
+
+    let mut selected = Vec::new();
+    for listing_id in &listing_ids {
+        selected.push(listings::get(&db, listing_id).await?);
+    }
+    let samples: Vec<String> = new_samples(&voice, selected)
+        .into_iter()
+        .map(|l| l.content)
+        .collect();
+
+    brand_voice::add_samples(&db, &id, &samples, &profile.to_json()?).await
+}
+
+/// Score a listing against a voice and store the result on the listing
+pub(crate) async fn save_voice_score(
+    db: &SqlitePool,
+    listing: &Listing,
+    voice: &BrandVoice,
+    judge_client: Option<&ClaudeClient>,
+) -> Result<Listing, AppError> {
+    let score = voice_score::score(voice, &listing.content, judge_client).await?;
+    let details = serde_json::to_string(&score)?;
+    listings::save_voice_score(db, &listing.id, score.score, &details).await
+}
+
+/// Rate how closely a listing matches a brand voice: the voice it was
+/// generated with unless another is given. `use_judge` adds the LLM rating
+/// to the lexical one.
+#[tauri::command]
+pub async fn score_listing_voice(
+    db: State<'_, SqlitePool>,
+    listing_id: String,
+    brand_voice_id: Option<String>,
+    use_judge: bool,
+) -> Result<Listing, AppError> {
+    let listing = listings::get(&db, &listing_id).await?;
+    let voice_id = brand_voice_id
+        .or_else(|| listing.brand_voice_id.clone())
+        .ok_or_else(|| {
+            AppError::Validation(
+                "Choose a brand voice; this listing wasn't generated with one".to_string(),
+            )
+        })?;
+    let voice = brand_voice::get(&db, &voice_id).await?;
+
+    let client = if use_judge {
+        Some(load_client(&db).await?)
+    } else {
+        None
+    };
+    save_voice_score(&db, &listing, &voice, client.as_ref()).await
+}
+
 #[tauri::command]
 pub async fn delete_brand_voice(
     db: State<'_, SqlitePool>,
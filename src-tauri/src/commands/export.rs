@@ -1,15 +1,91 @@
+use std::path::{Path, PathBuf};
+
 use sqlx::SqlitePool;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
 
+use crate::ai::prompts::language_name;
+use crate::db::listings::Listing;
+use crate::db::photos::Photo;
 use crate::db::{listings, photos, properties};
 use crate::error::AppError;
+use crate::export::social_card::{self, CardContent, CardFormat, SocialBundle, SocialCardOptions};
 use crate::export::{docx, pdf};
 
+use super::fact_check::{ensure_supported, unsupported_claims};
+use super::generate::{assigned_agent, load_agent_info};
+
+/// For a bilingual export, each selected listing's latest translation into
+/// `language` (or its original, when the listing is itself a translation in
+/// another language). Listings without one export on their own.
+async fn bilingual_translations(
+    db: &SqlitePool,
+    selected: &[Listing],
+    language: Option<&str>,
+) -> Result<Vec<Listing>, AppError> {
+    let Some(language) = language else {
+        return Ok(Vec::new());
+    };
+    if language_name(language).is_none() {
+        return Err(AppError::Validation(format!(
+            "Unsupported language '{}'",
+            language
+        )));
+    }
+
+    let mut translations = Vec::new();
+    for listing in selected.iter().filter(|l| l.language != language) {
+        let original_id = listing.source_listing_id.as_deref().unwrap_or(&listing.id);
+        let mut candidates = listings::list_translations(db, original_id).await?;
+        if listing.source_listing_id.is_some() {
+            if let Ok(original) = listings::get(db, original_id).await {
+                candidates.insert(0, original);
+            }
+        }
+        if let Some(translation) = candidates
+            .into_iter()
+            .rev()
+            .find(|t| t.language == language && t.id != listing.id)
+        {
+            translations.push(translation);
+        }
+    }
+    Ok(translations)
+}
+
+/// Claims the property record doesn't support in the listings a PDF or DOCX
+/// export would include, for the agent to review before exporting anyway
+#[tauri::command]
+pub async fn check_export_claims(
+    db: State<'_, SqlitePool>,
+    property_id: String,
+    listing_ids: Vec<String>,
+    secondary_language: Option<String>,
+) -> Result<Vec<String>, AppError> {
+    let property = properties::get(&db, &property_id).await?;
+
+    let mut selected_listings = Vec::new();
+    for id in &listing_ids {
+        selected_listings.push(listings::get(&db, id).await?);
+    }
+    let translations =
+        bilingual_translations(&db, &selected_listings, secondary_language.as_deref()).await?;
+    unsupported_claims(
+        &db,
+        &property,
+        selected_listings.iter().chain(&translations),
+    )
+    .await
+}
+
 #[tauri::command]
 pub async fn export_pdf(
+    app_handle: AppHandle,
     db: State<'_, SqlitePool>,
     property_id: String,
     listing_ids: Vec<String>,
+    secondary_language: Option<String>,
+    allow_unsupported_claims: Option<bool>,
 ) -> Result<Vec<u8>, AppError> {
     let property = properties::get(&db, &property_id).await?;
 
@@ -18,12 +94,33 @@ pub async fn export_pdf(
         let listing = listings::get(&db, id).await?;
         selected_listings.push(listing);
     }
+    let translations =
+        bilingual_translations(&db, &selected_listings, secondary_language.as_deref()).await?;
+    ensure_supported(
+        &db,
+        &property,
+        selected_listings.iter().chain(&translations),
+        allow_unsupported_claims.unwrap_or(false),
+    )
+    .await?;
 
     // Fetch photos for the property
-    let property_photos = photos::list(&db, &property_id).await?;
+    let property_photos = photos::list_by_property(&db, &property_id).await?;
+
+    // Contact details of the property's assigned agent
+    let agent = assigned_agent(&db, &property).await?;
+    let agent_info = load_agent_info(&db, agent.as_ref()).await;
 
+    let app_data_dir = app_data_dir(&app_handle)?;
     let bytes = tokio::task::spawn_blocking(move || {
-        pdf::generate_pdf(&property, &selected_listings, &property_photos)
+        pdf::generate_pdf(
+            &app_data_dir,
+            &property,
+            &selected_listings,
+            &translations,
+            &property_photos,
+            &agent_info,
+        )
     })
     .await
     .map_err(|e| AppError::Export(format!("PDF generation task failed: {}", e)))??;
@@ -33,9 +130,12 @@ pub async fn export_pdf(
 
 #[tauri::command]
 pub async fn export_docx(
+    app_handle: AppHandle,
     db: State<'_, SqlitePool>,
     property_id: String,
     listing_ids: Vec<String>,
+    secondary_language: Option<String>,
+    allow_unsupported_claims: Option<bool>,
 ) -> Result<Vec<u8>, AppError> {
     let property = properties::get(&db, &property_id).await?;
 
@@ -44,12 +144,33 @@ pub async fn export_docx(
         let listing = listings::get(&db, id).await?;
         selected_listings.push(listing);
     }
+    let translations =
+        bilingual_translations(&db, &selected_listings, secondary_language.as_deref()).await?;
+    ensure_supported(
+        &db,
+        &property,
+        selected_listings.iter().chain(&translations),
+        allow_unsupported_claims.unwrap_or(false),
+    )
+    .await?;
 
     // Fetch photos for the property
-    let property_photos = photos::list(&db, &property_id).await?;
+    let property_photos = photos::list_by_property(&db, &property_id).await?;
+
+    // Contact details of the property's assigned agent
+    let agent = assigned_agent(&db, &property).await?;
+    let agent_info = load_agent_info(&db, agent.as_ref()).await;
 
+    let app_data_dir = app_data_dir(&app_handle)?;
     let bytes = tokio::task::spawn_blocking(move || {
-        docx::generate_docx(&property, &selected_listings, &property_photos)
+        docx::generate_docx(
+            &app_data_dir,
+            &property,
+            &selected_listings,
+            &translations,
+            &property_photos,
+            &agent_info,
+        )
     })
     .await
     .map_err(|e| AppError::Export(format!("DOCX generation task failed: {}", e)))??;
@@ -57,10 +178,143 @@ pub async fn export_docx(
     Ok(bytes)
 }
 
+fn app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Export(format!("Failed to get app data dir: {}", e)))
+}
+
+/// The photo chosen in the options, or the property's first photo
+async fn card_photo(
+    db: &SqlitePool,
+    property_id: &str,
+    options: &SocialCardOptions,
+) -> Result<Photo, AppError> {
+    let property_photos = photos::list_by_property(db, property_id).await?;
+    let photo = match &options.photo_id {
+        Some(id) => property_photos.into_iter().find(|p| &p.id == id),
+        None => property_photos.into_iter().next(),
+    };
+    photo.ok_or_else(|| AppError::Validation("The property has no photo for the card".to_string()))
+}
+
+async fn card_content(
+    db: &SqlitePool,
+    property_id: &str,
+    options: &SocialCardOptions,
+) -> Result<CardContent, AppError> {
+    let property = properties::get(db, property_id).await?;
+    let agent = assigned_agent(db, &property).await?;
+    let agent_info = load_agent_info(db, agent.as_ref()).await;
+    Ok(CardContent::new(&property, &agent_info, options.include_agent))
+}
+
+/// Render one image card as PNG bytes
+#[tauri::command]
+pub async fn render_social_card(
+    app_handle: AppHandle,
+    db: State<'_, SqlitePool>,
+    property_id: String,
+    format: CardFormat,
+    options: Option<SocialCardOptions>,
+) -> Result<Vec<u8>, AppError> {
+    let options = options.unwrap_or_default();
+    social_card::validate_options(&options)?;
+    let photo = card_photo(&db, &property_id, &options).await?;
+    let content = card_content(&db, &property_id, &options).await?;
+    let app_data_dir = app_data_dir(&app_handle)?;
+
+    tokio::task::spawn_blocking(move || {
+        social_card::render(&app_data_dir, &photo, &content, &options, format)
+    })
+    .await
+    .map_err(|e| AppError::Export(format!("Card rendering task failed: {}", e)))?
+}
+
+/// Write the cards in every platform size plus a caption, ready to post.
+/// The caption is the given listing, or the property's latest social post.
 #[tauri::command]
-pub async fn copy_to_clipboard(text: String) -> Result<(), AppError> {
-    // Use the clipboard plugin from the frontend side instead
-    // This command is a simple passthrough for cases where we need it from Rust
-    // In practice, the frontend uses @tauri-apps/plugin-clipboard-manager directly
-    Ok(())
+pub async fn export_social_bundle(
+    app_handle: AppHandle,
+    db: State<'_, SqlitePool>,
+    property_id: String,
+    listing_id: Option<String>,
+    dest_path: String,
+    options: Option<SocialCardOptions>,
+    allow_unsupported_claims: Option<bool>,
+) -> Result<SocialBundle, AppError> {
+    let options = options.unwrap_or_default();
+    social_card::validate_options(&options)?;
+    let photo = card_photo(&db, &property_id, &options).await?;
+    let content = card_content(&db, &property_id, &options).await?;
+    let app_data_dir = app_data_dir(&app_handle)?;
+
+    let caption = match listing_id {
+        Some(id) => {
+            let listing = listings::get(&db, &id).await?;
+            if listing.property_id != property_id {
+                return Err(AppError::Validation(format!(
+                    "Listing {} does not belong to property {}",
+                    id, property_id
+                )));
+            }
+            Some(listing)
+        }
+        None => listings::list_by_property(&db, &property_id)
+            .await?
+            .into_iter()
+            .find(|l| l.generation_type.starts_with("social_")),
+    };
+    // The caption is published with the cards, so it's held to the same
+    // check as a PDF or DOCX export
+    if let Some(ref listing) = caption {
+        let property = properties::get(&db, &property_id).await?;
+        ensure_supported(
+            &db,
+            &property,
+            [listing],
+            allow_unsupported_claims.unwrap_or(false),
+        )
+        .await?;
+    }
+    let caption = caption.map(|l| l.content);
+
+    tokio::task::spawn_blocking(move || {
+        social_card::export_bundle(
+            &app_data_dir,
+            &photo,
+            &content,
+            &options,
+            caption.as_deref(),
+            Path::new(&dest_path),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Export(format!("Social bundle task failed: {}", e)))?
+}
+
+/// Copy a listing's text to the clipboard, refusing copy with claims the
+/// property record doesn't support unless the agent chooses to copy anyway
+#[tauri::command]
+pub async fn copy_to_clipboard(
+    app_handle: AppHandle,
+    db: State<'_, SqlitePool>,
+    listing_id: String,
+    allow_unsupported_claims: Option<bool>,
+) -> Result<(), AppError> {
+    let listing = listings::get(&db, &listing_id).await?;
+    let property = properties::get(&db, &listing.property_id).await?;
+    ensure_supported(
+        &db,
+        &property,
+        [&listing],
+        allow_unsupported_claims.unwrap_or(false),
+    )
+    .await?;
+
+    app_handle
+        .clipboard()
+        .write_text(listing.content)
+        .map_err(|e| AppError::Export(format!("Failed to copy to the clipboard: {}", e)))
 }
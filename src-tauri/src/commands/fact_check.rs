@@ -0,0 +1,169 @@
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::ai::client::StreamEvent;
+use crate::ai::fact_check::{self, FactCheck};
+use crate::ai::prompts::language_name;
+use crate::db::listings::{self, Listing};
+use crate::db::price_history;
+use crate::db::properties::{self, Property};
+use crate::error::AppError;
+
+/// Check a listing's claims against its property as the record stands now.
+/// `analysis_json` is the property analysis the copy was written from, when
+/// it's at hand.
+pub(crate) async fn check_listing(
+    db: &SqlitePool,
+    listing: &Listing,
+    property: &Property,
+    analysis_json: Option<&str>,
+) -> Result<FactCheck, AppError> {
+    let history = price_history::list_by_property(db, &property.id).await?;
+    Ok(fact_check::check(
+        property,
+        &history,
+        analysis_json,
+        &listing.content,
+    ))
+}
+
+/// Fact check a listing and store the result on it. Copy in a language the
+/// check can't read is left without one rather than looking clean.
+pub(crate) async fn save_fact_check(
+    db: &SqlitePool,
+    listing: &Listing,
+    property: &Property,
+    analysis_json: Option<&str>,
+) -> Result<Listing, AppError> {
+    if listing.language != fact_check::CHECKED_LANGUAGE {
+        return Ok(listing.clone());
+    }
+    let check = check_listing(db, listing, property, analysis_json).await?;
+    let details = serde_json::to_string(&check)?;
+    listings::save_fact_check(db, &listing.id, check.unsupported as i64, &details).await
+}
+
+/// Fact check freshly generated or edited copy. Like voice scoring, a
+/// failure here doesn't fail the save.
+pub(crate) async fn check_saved_listing(
+    db: &SqlitePool,
+    listing: &Listing,
+    property: &Property,
+    analysis_json: Option<&str>,
+) -> Listing {
+    match save_fact_check(db, listing, property, analysis_json).await {
+        Ok(checked) => checked,
+        Err(e) => {
+            eprintln!("Failed to fact check listing {}: {}", listing.id, e);
+            listing.clone()
+        }
+    }
+}
+
+/// A saved listing's fact check as a stream event, when it has one
+pub(crate) fn fact_checked_event(listing: &Listing) -> Option<StreamEvent> {
+    let fact_check = serde_json::from_str(listing.fact_check_details.as_deref()?).ok()?;
+    Some(StreamEvent::FactChecked {
+        listing_id: listing.id.clone(),
+        fact_check,
+    })
+}
+
+/// Each claim in the copy that the property record doesn't support, with
+/// what the record says, and each listing in a language the check can't read
+pub(crate) async fn unsupported_claims<'a>(
+    db: &SqlitePool,
+    property: &Property,
+    exported: impl IntoIterator<Item = &'a Listing>,
+) -> Result<Vec<String>, AppError> {
+    let mut problems = Vec::new();
+    for listing in exported {
+        if listing.language != fact_check::CHECKED_LANGUAGE {
+            problems.push(format!(
+                "{} copy (not fact checked: only English copy is)",
+                language_name(&listing.language).unwrap_or(&listing.language)
+            ));
+            continue;
+        }
+        let check = check_listing(db, listing, property, None).await?;
+        problems.extend(
+            check
+                .unsupported_claims()
+                .map(|claim| match claim.expected {
+                    Some(ref expected) => {
+                        format!("\"{}\" (property record: {})", claim.text, expected)
+                    }
+                    None => format!("\"{}\" (not in the property record)", claim.text),
+                }),
+        );
+    }
+    Ok(problems)
+}
+
+/// Refuse to export copy that makes claims the property record doesn't
+/// support, naming each one, unless the agent has chosen to export anyway
+pub(crate) async fn ensure_supported<'a>(
+    db: &SqlitePool,
+    property: &Property,
+    exported: impl IntoIterator<Item = &'a Listing>,
+    allow_unsupported_claims: bool,
+) -> Result<(), AppError> {
+    if allow_unsupported_claims {
+        return Ok(());
+    }
+
+    let problems = unsupported_claims(db, property, exported).await?;
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::Validation(format!(
+        "Some of the copy isn't backed by the property record: {}. Correct it or export anyway.",
+        problems.join(", ")
+    )))
+}
+
+/// Re-check a listing against its property, e.g. after the property's
+/// details were corrected
+#[tauri::command]
+pub async fn check_listing_facts(
+    db: State<'_, SqlitePool>,
+    listing_id: String,
+) -> Result<Listing, AppError> {
+    let listing = listings::get(&db, &listing_id).await?;
+    let property = properties::get(&db, &listing.property_id).await?;
+    save_fact_check(&db, &listing, &property, None).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_translated_copy_is_not_passed_as_checked() {
+        let pool = crate::db::test_pool().await;
+        let property = crate::db::test_property();
+        let spanish = Listing {
+            content: "Encantadora casa de 4 dormitorios y 2.5 baños.".to_string(),
+            language: "es".to_string(),
+            source_listing_id: Some("listing0".to_string()),
+            ..crate::db::test_listing()
+        };
+
+        let saved = save_fact_check(&pool, &spanish, &property, None)
+            .await
+            .unwrap();
+        assert_eq!(saved.unsupported_claims, None);
+        assert_eq!(saved.fact_check_details, None);
+
+        let problems = unsupported_claims(&pool, &property, [&spanish])
+            .await
+            .unwrap();
+        assert_eq!(
+            problems,
+            vec!["Spanish copy (not fact checked: only English copy is)"]
+        );
+        assert!(ensure_supported(&pool, &property, [&spanish], false)
+            .await
+            .is_err());
+    }
+}
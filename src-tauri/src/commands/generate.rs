@@ -1,25 +1,178 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use tauri::ipc::Channel;
 use tauri::State;
 
 use crate::ai::client::{ClaudeClient, StreamEvent};
 use crate::ai::email_generator;
-use crate::ai::listing_generator;
+use crate::ai::listing_generator::{self, ListingVariant, VariantEvent};
+use crate::ai::long_form::{self, LongFormDocument, LongFormKind};
+use crate::ai::long_form_generator;
+use crate::ai::price_improvement_generator;
 use crate::ai::social_generator;
-use crate::ai::prompts::{AgentInfo, GenerationOptions};
-use crate::db::{brand_voice, listings, properties, settings};
+use crate::ai::translator::{self, TranslationEvent};
+use crate::ai::prompts::{
+    language_name, AgentInfo, GenerationOptions, DEFAULT_LANGUAGE, LANGUAGES,
+};
+use crate::db::agents::{self, Agent};
+use crate::db::prompt_presets::PresetKind;
+use crate::db::properties::Property;
+use crate::db::{brand_voice, listings, photos, price_history, properties, settings};
 use crate::error::AppError;
 
+use super::brand_voice::save_voice_score;
+use super::fact_check::{check_saved_listing, fact_checked_event};
+use super::prompt_presets::resolve_rendered;
+
+/// Load the agent profile assigned to a property, if any
+pub(crate) async fn assigned_agent(
+    db: &SqlitePool,
+    property: &Property,
+) -> Result<Option<Agent>, AppError> {
+    match property.agent_id {
+        Some(ref agent_id) => Ok(Some(agents::get(db, agent_id).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Agent details for prompts and exports: the assigned agent's profile, or the
+/// single-agent settings keys for properties without one
+pub(crate) async fn load_agent_info(db: &SqlitePool, agent: Option<&Agent>) -> AgentInfo {
+    if let Some(agent) = agent {
+        return AgentInfo::from(agent);
+    }
+
+    AgentInfo {
+        name: settings::get(db, "agent_name").await.unwrap_or_default(),
+        phone: settings::get(db, "agent_phone").await.unwrap_or_default(),
+        email: settings::get(db, "agent_email").await.unwrap_or_default(),
+        brokerage: settings::get(db, "brokerage_name")
+            .await
+            .unwrap_or_default(),
+        signature: String::new(),
+    }
+}
+
+/// Give copy generated with a brand voice its lexical voice score. The
+/// LLM judge is left for the agent to run on demand; a scoring failure
+/// doesn't fail the generation.
+async fn score_generated_listing(db: &SqlitePool, listing: &listings::Listing) {
+    let Some(ref voice_id) = listing.brand_voice_id else {
+        return;
+    };
+    let result = async {
+        let voice = brand_voice::get(db, voice_id).await?;
+        save_voice_score(db, listing, &voice, None).await
+    }
+    .await;
+    if let Err(e) = result {
+        eprintln!(
+            "Failed to score listing {} against its brand voice: {}",
+            listing.id, e
+        );
+    }
+}
+
+/// The language requested for generated copy, English when none is given
+fn generation_language(language: Option<&str>) -> Result<String, AppError> {
+    let language = language.unwrap_or(DEFAULT_LANGUAGE);
+    if language_name(language).is_none() {
+        return Err(AppError::Validation(format!(
+            "Unsupported language '{}'. Choose one of: {}",
+            language,
+            LANGUAGES
+                .iter()
+                .map(|(code, _)| *code)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+    Ok(language.to_string())
+}
+
+/// The style, tone and length to write with: what was asked for, else the
+/// assigned agent's defaults, else the first built-in of each
+pub(crate) fn listing_choices(
+    agent: Option<&Agent>,
+    style: Option<&str>,
+    tone: Option<&str>,
+    length: Option<&str>,
+) -> (String, String, String) {
+    let choose = |requested: Option<&str>, agent_default: Option<&str>, kind: PresetKind| {
+        requested
+            .or(agent_default)
+            .unwrap_or(kind.builtin_keys()[0])
+            .to_string()
+    };
+    (
+        choose(
+            style,
+            agent.and_then(|a| a.default_style.as_deref()),
+            PresetKind::Style,
+        ),
+        choose(
+            tone,
+            agent.and_then(|a| a.default_tone.as_deref()),
+            PresetKind::Tone,
+        ),
+        choose(
+            length,
+            agent.and_then(|a| a.default_length.as_deref()),
+            PresetKind::Length,
+        ),
+    )
+}
+
+/// Listing options for a property, with any custom style, tone or length
+/// preset rendered in place of the built-in instructions. Also returns the
+/// id of the first custom preset (style, then tone, then length) for the
+/// listing to keep once the preset's key no longer resolves.
+#[allow(clippy::too_many_arguments)]
+async fn listing_options(
+    db: &SqlitePool,
+    property: &Property,
+    agent_info: &AgentInfo,
+    style: &str,
+    tone: &str,
+    length: &str,
+    seo_keywords: &[String],
+    language: &str,
+) -> Result<(GenerationOptions, Option<String>), AppError> {
+    let custom_style = resolve_rendered(db, PresetKind::Style, style, property, agent_info).await?;
+    let custom_tone = resolve_rendered(db, PresetKind::Tone, tone, property, agent_info).await?;
+    let custom_length =
+        resolve_rendered(db, PresetKind::Length, length, property, agent_info).await?;
+    let preset_id = [&custom_style, &custom_tone, &custom_length]
+        .into_iter()
+        .flatten()
+        .next()
+        .map(|p| p.id.clone());
+    let options = GenerationOptions {
+        style: style.to_string(),
+        tone: tone.to_string(),
+        length: length.to_string(),
+        seo_keywords: seo_keywords.to_vec(),
+        custom_style: custom_style.map(|p| p.instructions),
+        custom_tone: custom_tone.map(|p| p.instructions),
+        custom_length: custom_length.map(|p| p.instructions),
+        language: language.to_string(),
+    };
+    Ok((options, preset_id))
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GenerateListingArgs {
     pub property_id: String,
-    pub style: String,
-    pub tone: String,
-    pub length: String,
+    /// Style, tone and length default to the assigned agent's, then the
+    /// built-ins
+    pub style: Option<String>,
+    pub tone: Option<String>,
+    pub length: Option<String>,
     pub seo_keywords: Vec<String>,
     pub brand_voice_id: Option<String>,
+    /// Language code from `prompts::LANGUAGES`; English when not given
+    pub language: Option<String>,
 }
 
 #[tauri::command]
@@ -28,6 +181,7 @@ pub async fn generate_listing(
     args: GenerateListingArgs,
     on_event: Channel<StreamEvent>,
 ) -> Result<(), AppError> {
+    let language = generation_language(args.language.as_deref())?;
     // Load property
     let property = properties::get(&db, &args.property_id)
         .await
@@ -40,30 +194,39 @@ pub async fn generate_listing(
     }
     let api_key = api_key.unwrap();
 
-    // Load agent info
-    let agent_info = AgentInfo {
-        name: settings::get(&db, "agent_name").await.unwrap_or_default(),
-        phone: settings::get(&db, "agent_phone").await.unwrap_or_default(),
-        email: settings::get(&db, "agent_email").await.unwrap_or_default(),
-        brokerage: settings::get(&db, "brokerage_name")
-            .await
-            .unwrap_or_default(),
-    };
+    // Load the assigned agent (falls back to the settings-based agent info)
+    let agent = assigned_agent(&db, &property).await?;
+    let agent_info = load_agent_info(&db, agent.as_ref()).await;
 
-    // Load brand voice if specified
-    let voice_block = if let Some(ref voice_id) = args.brand_voice_id {
+    // Load brand voice if specified, otherwise the agent's default voice
+    let brand_voice_id = args
+        .brand_voice_id
+        .clone()
+        .or_else(|| agent.as_ref().and_then(|a| a.default_brand_voice_id.clone()));
+    let voice_block = if let Some(ref voice_id) = brand_voice_id {
         let voice = brand_voice::get(&db, voice_id).await?;
         crate::ai::prompts::build_voice_block(&voice.extracted_style)
     } else {
         None
     };
 
-    let options = GenerationOptions {
-        style: args.style.clone(),
-        tone: args.tone.clone(),
-        length: args.length.clone(),
-        seo_keywords: args.seo_keywords.clone(),
-    };
+    let (style, tone, length) = listing_choices(
+        agent.as_ref(),
+        args.style.as_deref(),
+        args.tone.as_deref(),
+        args.length.as_deref(),
+    );
+    let (options, prompt_preset_id) = listing_options(
+        &db,
+        &property,
+        &agent_info,
+        &style,
+        &tone,
+        &length,
+        &args.seo_keywords,
+        &language,
+    )
+    .await?;
 
     // Load AI model preference
     let model = settings::get(&db, "ai_model")
@@ -83,32 +246,232 @@ pub async fn generate_listing(
     .await?;
 
     // Save to database
-    listings::save(
+    let listing = listings::save(
         &db,
         listings::CreateListingInput {
             property_id: args.property_id,
             content: result.full_text,
             generation_type: "listing".to_string(),
-            style: Some(args.style),
-            tone: Some(args.tone),
-            length: Some(args.length),
+            style: Some(style),
+            tone: Some(tone),
+            length: Some(length),
             seo_keywords: args.seo_keywords,
-            brand_voice_id: args.brand_voice_id,
+            brand_voice_id,
             tokens_used: (result.input_tokens + result.output_tokens) as i64,
             generation_cost_cents: result.cost_cents as i64,
+            variant_group_id: None,
+            prompt_preset_id,
+            language,
+            source_listing_id: None,
         },
     )
     .await?;
+    let listing = check_saved_listing(&db, &listing, &property, Some(&result.analysis_json)).await;
+    if let Some(event) = fact_checked_event(&listing) {
+        let _ = on_event.send(event);
+    }
+    score_generated_listing(&db, &listing).await;
 
     Ok(())
 }
 
+/// Most variants one request can compare side by side
+const MAX_VARIANTS: usize = 4;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantSpec {
+    pub style: Option<String>,
+    pub tone: Option<String>,
+    pub length: Option<String>,
+    pub brand_voice_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateVariantsArgs {
+    pub property_id: String,
+    pub seo_keywords: Vec<String>,
+    /// Language code from `prompts::LANGUAGES`; English when not given
+    pub language: Option<String>,
+    pub variants: Vec<VariantSpec>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantGroup {
+    pub group_id: String,
+    pub listings: Vec<listings::Listing>,
+}
+
+/// Write several variants of a listing from one property analysis and save
+/// them as a variant group. Variants that fail are reported on the channel
+/// and left out of the group; the command only fails when none succeed.
+#[tauri::command]
+pub async fn generate_listing_variants(
+    db: State<'_, SqlitePool>,
+    args: GenerateVariantsArgs,
+    on_event: Channel<VariantEvent>,
+) -> Result<VariantGroup, AppError> {
+    let language = generation_language(args.language.as_deref())?;
+    if !(2..=MAX_VARIANTS).contains(&args.variants.len()) {
+        return Err(AppError::Validation(format!(
+            "Choose between 2 and {} variants to compare",
+            MAX_VARIANTS
+        )));
+    }
+
+    let property = properties::get(&db, &args.property_id)
+        .await
+        .map_err(|_| AppError::PropertyNotFound(args.property_id.clone()))?;
+
+    let api_key = settings::get(&db, "api_key").await.ok();
+    if api_key.is_none() || api_key.as_ref().unwrap().is_empty() {
+        return Err(AppError::MissingApiKey);
+    }
+    let api_key = api_key.unwrap();
+
+    let agent = assigned_agent(&db, &property).await?;
+    let agent_info = load_agent_info(&db, agent.as_ref()).await;
+    let default_voice_id = agent
+        .as_ref()
+        .and_then(|a| a.default_brand_voice_id.clone());
+
+    // Resolve each variant's voice up front so a bad id fails before any
+    // tokens are spent
+    let mut voice_ids = Vec::with_capacity(args.variants.len());
+    let mut choices = Vec::with_capacity(args.variants.len());
+    let mut variants = Vec::with_capacity(args.variants.len());
+    for spec in &args.variants {
+        let brand_voice_id = spec
+            .brand_voice_id
+            .clone()
+            .or_else(|| default_voice_id.clone());
+        let brand_voice_block = match brand_voice_id {
+            Some(ref voice_id) => {
+                let voice = brand_voice::get(&db, voice_id).await?;
+                crate::ai::prompts::build_voice_block(&voice.extracted_style)
+            }
+            None => None,
+        };
+        let (style, tone, length) = listing_choices(
+            agent.as_ref(),
+            spec.style.as_deref(),
+            spec.tone.as_deref(),
+            spec.length.as_deref(),
+        );
+        let (options, prompt_preset_id) = listing_options(
+            &db,
+            &property,
+            &agent_info,
+            &style,
+            &tone,
+            &length,
+            &args.seo_keywords,
+            &language,
+        )
+        .await?;
+        voice_ids.push(brand_voice_id);
+        variants.push(ListingVariant {
+            options,
+            brand_voice_block,
+        });
+        choices.push((style, tone, length, prompt_preset_id));
+    }
+
+    let model = settings::get(&db, "ai_model")
+        .await
+        .unwrap_or_else(|_| "claude-sonnet-4-5-20250929".to_string());
+
+    let client = ClaudeClient::new(api_key, model);
+
+    let results =
+        listing_generator::generate_variants(&client, &property, &variants, &agent_info, &on_event)
+            .await?;
+
+    let group_id = uuid::Uuid::new_v4().to_string();
+    let mut saved = Vec::new();
+    let mut last_error = None;
+    for (index, (result, brand_voice_id)) in results.into_iter().zip(voice_ids).enumerate() {
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                last_error = Some(e);
+                continue;
+            }
+        };
+        let (style, tone, length, prompt_preset_id) = &choices[index];
+        let listing = listings::save(
+            &db,
+            listings::CreateListingInput {
+                property_id: args.property_id.clone(),
+                content: result.full_text,
+                generation_type: "listing".to_string(),
+                style: Some(style.clone()),
+                tone: Some(tone.clone()),
+                length: Some(length.clone()),
+                seo_keywords: args.seo_keywords.clone(),
+                brand_voice_id,
+                tokens_used: (result.input_tokens + result.output_tokens) as i64,
+                generation_cost_cents: result.cost_cents as i64,
+                variant_group_id: Some(group_id.clone()),
+                prompt_preset_id: prompt_preset_id.clone(),
+                language: language.clone(),
+                source_listing_id: None,
+            },
+        )
+        .await;
+        // Keep the variants already saved when a later one can't be
+        let listing = match listing {
+            Ok(listing) => listing,
+            Err(e) => {
+                let _ = on_event.send(VariantEvent::Failed {
+                    variant: index,
+                    message: e.to_string(),
+                });
+                last_error = Some(e);
+                continue;
+            }
+        };
+        let listing =
+            check_saved_listing(&db, &listing, &property, Some(&result.analysis_json)).await;
+        if let Some(event) = fact_checked_event(&listing) {
+            let _ = on_event.send(VariantEvent::Stream {
+                variant: index,
+                event,
+            });
+        }
+        score_generated_listing(&db, &listing).await;
+        let _ = on_event.send(VariantEvent::Saved {
+            variant: index,
+            listing_id: listing.id.clone(),
+        });
+        saved.push(listing);
+    }
+
+    if saved.is_empty() {
+        return Err(
+            last_error.unwrap_or_else(|| AppError::Api("No variants were generated".to_string()))
+        );
+    }
+
+    let _ = on_event.send(VariantEvent::Finished {
+        group_id: group_id.clone(),
+    });
+
+    // Re-read so the returned listings carry their voice scores and fact checks
+    let listings = listings::list_variant_group(&db, &group_id).await?;
+    Ok(VariantGroup { group_id, listings })
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GenerateSocialArgs {
     pub property_id: String,
     pub platform: String,
     pub brand_voice_id: Option<String>,
+    /// Language code from `prompts::LANGUAGES`; English when not given
+    pub language: Option<String>,
 }
 
 #[tauri::command]
@@ -117,6 +480,7 @@ pub async fn generate_social(
     args: GenerateSocialArgs,
     on_event: Channel<StreamEvent>,
 ) -> Result<(), AppError> {
+    let language = generation_language(args.language.as_deref())?;
     // Load property
     let property = properties::get(&db, &args.property_id)
         .await
@@ -129,18 +493,26 @@ pub async fn generate_social(
     }
     let api_key = api_key.unwrap();
 
-    // Load agent info
-    let agent_info = AgentInfo {
-        name: settings::get(&db, "agent_name").await.unwrap_or_default(),
-        phone: settings::get(&db, "agent_phone").await.unwrap_or_default(),
-        email: settings::get(&db, "agent_email").await.unwrap_or_default(),
-        brokerage: settings::get(&db, "brokerage_name")
-            .await
-            .unwrap_or_default(),
-    };
+    // Load the assigned agent (falls back to the settings-based agent info)
+    let agent = assigned_agent(&db, &property).await?;
+    let agent_info = load_agent_info(&db, agent.as_ref()).await;
 
-    // Load brand voice if specified
-    let voice_block = if let Some(ref voice_id) = args.brand_voice_id {
+    // A platform that isn't built in must be a custom preset
+    let custom = resolve_rendered(
+        &db,
+        PresetKind::Platform,
+        &args.platform,
+        &property,
+        &agent_info,
+    )
+    .await?;
+
+    // Load brand voice if specified, otherwise the agent's default voice
+    let brand_voice_id = args
+        .brand_voice_id
+        .clone()
+        .or_else(|| agent.as_ref().and_then(|a| a.default_brand_voice_id.clone()));
+    let voice_block = if let Some(ref voice_id) = brand_voice_id {
         let voice = brand_voice::get(&db, voice_id).await?;
         crate::ai::prompts::build_voice_block(&voice.extracted_style)
     } else {
@@ -158,15 +530,20 @@ pub async fn generate_social(
         &client,
         &property,
         &args.platform,
+        custom.as_ref(),
         voice_block.as_deref(),
+        &language,
         &agent_info,
         &on_event,
     )
     .await?;
 
     // Save to database
-    let generation_type = format!("social_{}", args.platform);
-    listings::save(
+    let generation_type = match custom {
+        Some(_) => "social_custom".to_string(),
+        None => format!("social_{}", args.platform),
+    };
+    let listing = listings::save(
         &db,
         listings::CreateListingInput {
             property_id: args.property_id,
@@ -176,12 +553,21 @@ pub async fn generate_social(
             tone: None,
             length: None,
             seo_keywords: vec![],
-            brand_voice_id: args.brand_voice_id,
+            brand_voice_id,
             tokens_used: (result.input_tokens + result.output_tokens) as i64,
             generation_cost_cents: result.cost_cents as i64,
+            variant_group_id: None,
+            prompt_preset_id: custom.map(|p| p.id),
+            language,
+            source_listing_id: None,
         },
     )
     .await?;
+    let listing = check_saved_listing(&db, &listing, &property, Some(&result.analysis_json)).await;
+    if let Some(event) = fact_checked_event(&listing) {
+        let _ = on_event.send(event);
+    }
+    score_generated_listing(&db, &listing).await;
 
     Ok(())
 }
@@ -194,6 +580,15 @@ pub async fn list_listings(
     listings::list_by_property(&db, &property_id).await
 }
 
+/// Listings generated together, for side-by-side comparison
+#[tauri::command]
+pub async fn list_variant_group(
+    db: State<'_, SqlitePool>,
+    group_id: String,
+) -> Result<Vec<listings::Listing>, AppError> {
+    listings::list_variant_group(&db, &group_id).await
+}
+
 #[tauri::command]
 pub async fn toggle_listing_favorite(
     db: State<'_, SqlitePool>,
@@ -202,6 +597,22 @@ pub async fn toggle_listing_favorite(
     listings::toggle_favorite(&db, &id).await
 }
 
+/// Save the agent's edits to generated copy and fact check the new copy
+#[tauri::command]
+pub async fn update_listing(
+    db: State<'_, SqlitePool>,
+    id: String,
+    content: String,
+) -> Result<listings::Listing, AppError> {
+    let listing = listings::update_content(&db, &id, &content).await?;
+    // Unchanged copy keeps its fact check; edited copy is checked again
+    if listing.fact_check_details.is_some() {
+        return Ok(listing);
+    }
+    let property = properties::get(&db, &listing.property_id).await?;
+    Ok(check_saved_listing(&db, &listing, &property, None).await)
+}
+
 #[tauri::command]
 pub async fn delete_listing(db: State<'_, SqlitePool>, id: String) -> Result<(), AppError> {
     listings::delete(&db, &id).await
@@ -213,6 +624,8 @@ pub struct GenerateEmailArgs {
     pub property_id: String,
     pub template_type: String,
     pub brand_voice_id: Option<String>,
+    /// Language code from `prompts::LANGUAGES`; English when not given
+    pub language: Option<String>,
 }
 
 #[tauri::command]
@@ -221,6 +634,7 @@ pub async fn generate_email(
     args: GenerateEmailArgs,
     on_event: Channel<StreamEvent>,
 ) -> Result<(), AppError> {
+    let language = generation_language(args.language.as_deref())?;
     // Load property
     let property = properties::get(&db, &args.property_id)
         .await
@@ -233,18 +647,26 @@ pub async fn generate_email(
     }
     let api_key = api_key.unwrap();
 
-    // Load agent info
-    let agent_info = AgentInfo {
-        name: settings::get(&db, "agent_name").await.unwrap_or_default(),
-        phone: settings::get(&db, "agent_phone").await.unwrap_or_default(),
-        email: settings::get(&db, "agent_email").await.unwrap_or_default(),
-        brokerage: settings::get(&db, "brokerage_name")
-            .await
-            .unwrap_or_default(),
-    };
+    // Load the assigned agent (falls back to the settings-based agent info)
+    let agent = assigned_agent(&db, &property).await?;
+    let agent_info = load_agent_info(&db, agent.as_ref()).await;
 
-    // Load brand voice if specified
-    let voice_block = if let Some(ref voice_id) = args.brand_voice_id {
+    // A template that isn't built in must be a custom preset
+    let custom = resolve_rendered(
+        &db,
+        PresetKind::EmailTemplate,
+        &args.template_type,
+        &property,
+        &agent_info,
+    )
+    .await?;
+
+    // Load brand voice if specified, otherwise the agent's default voice
+    let brand_voice_id = args
+        .brand_voice_id
+        .clone()
+        .or_else(|| agent.as_ref().and_then(|a| a.default_brand_voice_id.clone()));
+    let voice_block = if let Some(ref voice_id) = brand_voice_id {
         let voice = brand_voice::get(&db, voice_id).await?;
         crate::ai::prompts::build_voice_block(&voice.extracted_style)
     } else {
@@ -262,15 +684,21 @@ pub async fn generate_email(
         &client,
         &property,
         &args.template_type,
+        custom.as_ref(),
         voice_block.as_deref(),
+        &language,
         &agent_info,
         &on_event,
     )
     .await?;
 
-    // Save to database with generation_type = "email_{template_type}"
-    let generation_type = format!("email_{}", args.template_type);
-    listings::save(
+    // Save to database with generation_type = "email_{template_type}", or
+    // "email_custom" for a custom template
+    let generation_type = match custom {
+        Some(_) => "email_custom".to_string(),
+        None => format!("email_{}", args.template_type),
+    };
+    let listing = listings::save(
         &db,
         listings::CreateListingInput {
             property_id: args.property_id,
@@ -280,12 +708,419 @@ pub async fn generate_email(
             tone: None,
             length: None,
             seo_keywords: vec![],
-            brand_voice_id: args.brand_voice_id,
+            brand_voice_id,
             tokens_used: (result.input_tokens + result.output_tokens) as i64,
             generation_cost_cents: result.cost_cents as i64,
+            variant_group_id: None,
+            prompt_preset_id: custom.map(|p| p.id),
+            language,
+            source_listing_id: None,
         },
     )
     .await?;
+    let listing = check_saved_listing(&db, &listing, &property, Some(&result.analysis_json)).await;
+    if let Some(event) = fact_checked_event(&listing) {
+        let _ = on_event.send(event);
+    }
+    score_generated_listing(&db, &listing).await;
 
     Ok(())
 }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratePriceImprovementArgs {
+    pub property_id: String,
+    /// `email` or a social platform (`instagram`, `facebook`, `linkedin`)
+    pub channel: String,
+    pub brand_voice_id: Option<String>,
+    /// Language code from `prompts::LANGUAGES`; English when not given
+    pub language: Option<String>,
+}
+
+#[tauri::command]
+pub async fn generate_price_improvement(
+    db: State<'_, SqlitePool>,
+    args: GeneratePriceImprovementArgs,
+    on_event: Channel<StreamEvent>,
+) -> Result<(), AppError> {
+    let language = generation_language(args.language.as_deref())?;
+    if !price_improvement_generator::CHANNELS.contains(&args.channel.as_str()) {
+        return Err(AppError::Validation(format!(
+            "Unknown price improvement channel '{}'",
+            args.channel
+        )));
+    }
+
+    // Load property
+    let property = properties::get(&db, &args.property_id)
+        .await
+        .map_err(|_| AppError::PropertyNotFound(args.property_id.clone()))?;
+
+    // The campaign is about the most recent price change, which must be a reduction
+    let change = price_history::latest(&db, &args.property_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::Validation("No price change has been recorded for this property".to_string())
+        })?;
+    if change.reduction() <= 0 {
+        return Err(AppError::Validation(
+            "The latest price change is not a price improvement".to_string(),
+        ));
+    }
+
+    // Load API key
+    let api_key = settings::get(&db, "api_key").await.ok();
+    if api_key.is_none() || api_key.as_ref().unwrap().is_empty() {
+        return Err(AppError::MissingApiKey);
+    }
+    let api_key = api_key.unwrap();
+
+    // Load the assigned agent (falls back to the settings-based agent info)
+    let agent = assigned_agent(&db, &property).await?;
+    let agent_info = load_agent_info(&db, agent.as_ref()).await;
+
+    // Load brand voice if specified, otherwise the agent's default voice
+    let brand_voice_id = args
+        .brand_voice_id
+        .clone()
+        .or_else(|| agent.as_ref().and_then(|a| a.default_brand_voice_id.clone()));
+    let voice_block = if let Some(ref voice_id) = brand_voice_id {
+        let voice = brand_voice::get(&db, voice_id).await?;
+        crate::ai::prompts::build_voice_block(&voice.extracted_style)
+    } else {
+        None
+    };
+
+    // Load AI model preference
+    let model = settings::get(&db, "ai_model")
+        .await
+        .unwrap_or_else(|_| "claude-sonnet-4-5-20250929".to_string());
+
+    let client = ClaudeClient::new(api_key, model);
+
+    let result = price_improvement_generator::generate_price_improvement(
+        &client,
+        &property,
+        &args.channel,
+        &change,
+        voice_block.as_deref(),
+        &language,
+        &agent_info,
+        &on_event,
+    )
+    .await?;
+
+    // Save to database
+    let generation_type = if args.channel == "email" {
+        "email_price_improvement"
+    } else {
+        "social_price_improvement"
+    };
+    let listing = listings::save(
+        &db,
+        listings::CreateListingInput {
+            property_id: args.property_id,
+            content: result.full_text,
+            generation_type: generation_type.to_string(),
+            style: None,
+            tone: None,
+            length: None,
+            seo_keywords: vec![],
+            brand_voice_id,
+            tokens_used: (result.input_tokens + result.output_tokens) as i64,
+            generation_cost_cents: result.cost_cents as i64,
+            variant_group_id: None,
+            prompt_preset_id: None,
+            language,
+            source_listing_id: None,
+        },
+    )
+    .await?;
+    let listing = check_saved_listing(&db, &listing, &property, Some(&result.analysis_json)).await;
+    if let Some(event) = fact_checked_event(&listing) {
+        let _ = on_event.send(event);
+    }
+    score_generated_listing(&db, &listing).await;
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateLongFormArgs {
+    pub property_id: String,
+    pub kind: LongFormKind,
+    pub brand_voice_id: Option<String>,
+    /// Language code from `prompts::LANGUAGES`; English when not given
+    pub language: Option<String>,
+    /// Print ads only: hard limit on the body, in characters
+    pub char_limit: Option<usize>,
+}
+
+#[tauri::command]
+pub async fn generate_long_form(
+    db: State<'_, SqlitePool>,
+    args: GenerateLongFormArgs,
+    on_event: Channel<StreamEvent>,
+) -> Result<(), AppError> {
+    let language = generation_language(args.language.as_deref())?;
+    let char_limit = args.char_limit.unwrap_or(long_form::DEFAULT_PRINT_AD_LIMIT);
+    if args.kind == LongFormKind::PrintAd
+        && !(long_form::MIN_PRINT_AD_LIMIT..=long_form::MAX_PRINT_AD_LIMIT).contains(&char_limit)
+    {
+        return Err(AppError::Validation(format!(
+            "Print ad character limit must be between {} and {}",
+            long_form::MIN_PRINT_AD_LIMIT,
+            long_form::MAX_PRINT_AD_LIMIT
+        )));
+    }
+
+    // Load property
+    let property = properties::get(&db, &args.property_id)
+        .await
+        .map_err(|_| AppError::PropertyNotFound(args.property_id.clone()))?;
+
+    // Load API key
+    let api_key = settings::get(&db, "api_key").await.ok();
+    if api_key.is_none() || api_key.as_ref().unwrap().is_empty() {
+        return Err(AppError::MissingApiKey);
+    }
+    let api_key = api_key.unwrap();
+
+    // Load the assigned agent (falls back to the settings-based agent info)
+    let agent = assigned_agent(&db, &property).await?;
+    let agent_info = load_agent_info(&db, agent.as_ref()).await;
+
+    // Load brand voice if specified, otherwise the agent's default voice
+    let brand_voice_id = args
+        .brand_voice_id
+        .clone()
+        .or_else(|| agent.as_ref().and_then(|a| a.default_brand_voice_id.clone()));
+    let voice_block = if let Some(ref voice_id) = brand_voice_id {
+        let voice = brand_voice::get(&db, voice_id).await?;
+        crate::ai::prompts::build_voice_block(&voice.extracted_style)
+    } else {
+        None
+    };
+
+    // Analyzed photos tell the script and feature sheet which rooms exist
+    let photos = photos::list_by_property(&db, &args.property_id).await?;
+
+    // Load AI model preference
+    let model = settings::get(&db, "ai_model")
+        .await
+        .unwrap_or_else(|_| "claude-sonnet-4-5-20250929".to_string());
+
+    let client = ClaudeClient::new(api_key, model);
+
+    let result = long_form_generator::generate_long_form(
+        &client,
+        &property,
+        args.kind,
+        &photos,
+        char_limit,
+        voice_block.as_deref(),
+        &language,
+        &agent_info,
+        &on_event,
+    )
+    .await?;
+
+    // Save to database
+    let listing = listings::save(
+        &db,
+        listings::CreateListingInput {
+            property_id: args.property_id,
+            content: result.full_text,
+            generation_type: args.kind.generation_type().to_string(),
+            style: None,
+            tone: None,
+            length: None,
+            seo_keywords: vec![],
+            brand_voice_id,
+            tokens_used: (result.input_tokens + result.output_tokens) as i64,
+            generation_cost_cents: result.cost_cents as i64,
+            variant_group_id: None,
+            prompt_preset_id: None,
+            language,
+            source_listing_id: None,
+        },
+    )
+    .await?;
+    let listing = check_saved_listing(&db, &listing, &property, Some(&result.analysis_json)).await;
+    if let Some(event) = fact_checked_event(&listing) {
+        let _ = on_event.send(event);
+    }
+    score_generated_listing(&db, &listing).await;
+
+    Ok(())
+}
+
+/// A long-form listing parsed into its structure (scenes, sections, rooms
+/// or the print ad's headline and body)
+#[tauri::command]
+pub async fn get_long_form_document(
+    db: State<'_, SqlitePool>,
+    listing_id: String,
+) -> Result<LongFormDocument, AppError> {
+    let listing = listings::get(&db, &listing_id).await?;
+    let kind = LongFormKind::from_generation_type(&listing.generation_type).ok_or_else(|| {
+        AppError::Validation(format!(
+            "'{}' copy has no long-form structure",
+            listing.generation_type
+        ))
+    })?;
+    long_form::parse(kind, &listing.content)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslateListingArgs {
+    pub listing_id: String,
+    /// Language codes from `prompts::LANGUAGES`
+    pub languages: Vec<String>,
+}
+
+/// Translate a listing into other languages, keeping its brand voice and
+/// every number, and save each translation linked to the original.
+/// Translating a translation starts again from its original. Languages
+/// that fail are reported on the channel; the command only fails when none
+/// succeed.
+#[tauri::command]
+pub async fn translate_listing(
+    db: State<'_, SqlitePool>,
+    args: TranslateListingArgs,
+    on_event: Channel<TranslationEvent>,
+) -> Result<Vec<listings::Listing>, AppError> {
+    let listing = listings::get(&db, &args.listing_id).await?;
+    let original = match listing.source_listing_id {
+        Some(ref source_id) => listings::get(&db, source_id).await.unwrap_or(listing),
+        None => listing,
+    };
+
+    if args.languages.is_empty() {
+        return Err(AppError::Validation(
+            "Choose at least one language to translate into".to_string(),
+        ));
+    }
+    let mut languages: Vec<String> = Vec::with_capacity(args.languages.len());
+    for language in &args.languages {
+        let language = generation_language(Some(language))?;
+        if language == original.language {
+            return Err(AppError::Validation(format!(
+                "The listing is already in {}",
+                language_name(&language).unwrap_or(&language)
+            )));
+        }
+        if !languages.contains(&language) {
+            languages.push(language);
+        }
+    }
+
+    // Load API key
+    let api_key = settings::get(&db, "api_key").await.ok();
+    if api_key.is_none() || api_key.as_ref().unwrap().is_empty() {
+        return Err(AppError::MissingApiKey);
+    }
+    let api_key = api_key.unwrap();
+
+    // Translations carry the original's brand voice
+    let voice_block = if let Some(ref voice_id) = original.brand_voice_id {
+        let voice = brand_voice::get(&db, voice_id).await?;
+        crate::ai::prompts::build_voice_block(&voice.extracted_style)
+    } else {
+        None
+    };
+
+    // Load AI model preference
+    let model = settings::get(&db, "ai_model")
+        .await
+        .unwrap_or_else(|_| "claude-sonnet-4-5-20250929".to_string());
+
+    let client = ClaudeClient::new(api_key, model);
+    let property = properties::get(&db, &original.property_id).await?;
+
+    let results = translator::translate_all(
+        &client,
+        &original.content,
+        &original.language,
+        &languages,
+        voice_block.as_deref(),
+        &on_event,
+    )
+    .await;
+
+    // Translations aren't voice scored: voice profiles are learned from, and
+    // judge against, copy in the original language. They are fact checked,
+    // though outside English only dollar amounts are recognized.
+    let mut saved = Vec::new();
+    let mut last_error = None;
+    for (result, language) in results.into_iter().zip(languages) {
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                last_error = Some(e);
+                continue;
+            }
+        };
+        let translation = listings::save(
+            &db,
+            listings::CreateListingInput {
+                property_id: original.property_id.clone(),
+                content: result.full_text,
+                generation_type: original.generation_type.clone(),
+                style: original.style.clone(),
+                tone: original.tone.clone(),
+                length: original.length.clone(),
+                seo_keywords: serde_json::from_str(&original.seo_keywords).unwrap_or_default(),
+                brand_voice_id: original.brand_voice_id.clone(),
+                tokens_used: (result.input_tokens + result.output_tokens) as i64,
+                generation_cost_cents: result.cost_cents as i64,
+                variant_group_id: None,
+                prompt_preset_id: original.prompt_preset_id.clone(),
+                language: language.clone(),
+                source_listing_id: Some(original.id.clone()),
+            },
+        )
+        .await;
+        // One language failing to save doesn't lose the others
+        let translation = match translation {
+            Ok(translation) => translation,
+            Err(e) => {
+                let _ = on_event.send(TranslationEvent::Failed {
+                    language,
+                    message: e.to_string(),
+                });
+                last_error = Some(e);
+                continue;
+            }
+        };
+        let translation = check_saved_listing(&db, &translation, &property, None).await;
+        let _ = on_event.send(TranslationEvent::Saved {
+            language,
+            listing_id: translation.id.clone(),
+        });
+        saved.push(translation);
+    }
+
+    let _ = on_event.send(TranslationEvent::Finished);
+
+    if saved.is_empty() {
+        return Err(last_error
+            .unwrap_or_else(|| AppError::Api("No translations were generated".to_string())));
+    }
+
+    Ok(saved)
+}
+
+/// Translations of a listing, or of the original a translation was made from
+#[tauri::command]
+pub async fn list_listing_translations(
+    db: State<'_, SqlitePool>,
+    listing_id: String,
+) -> Result<Vec<listings::Listing>, AppError> {
+    let listing = listings::get(&db, &listing_id).await?;
+    let source_id = listing.source_listing_id.unwrap_or(listing.id);
+    listings::list_translations(&db, &source_id).await
+}
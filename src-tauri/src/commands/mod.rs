@@ -1,9 +1,14 @@
+pub mod agents;
 pub mod analytics;
+pub mod backup;
 pub mod brand_voice;
 pub mod export;
+pub mod fact_check;
 pub mod generate;
 pub mod import;
 pub mod license;
 pub mod photos;
 pub mod property;
+pub mod prompt_presets;
 pub mod settings;
+pub mod trash;
@@ -1,17 +1,30 @@
+use std::path::{Path, PathBuf};
+
 use sqlx::SqlitePool;
+use tauri::ipc::Channel;
 use tauri::{AppHandle, Manager, State};
 use tauri_plugin_dialog::DialogExt;
 
-use crate::db::photos::{self, Photo};
+use crate::ai::client::ClaudeClient;
+use crate::ai::photo_analyzer::{self, PhotoAnalysisReport};
+use crate::ai::photo_caption_generator::{self, CaptionDraft};
+use crate::db::photos::{self, Photo, UpdatePhotoInput};
+use crate::db::properties::{self, Property};
+use crate::db::settings;
 use crate::error::AppError;
-use crate::photos::manager;
+use crate::photos::derivatives::{self, DerivativePreset, DerivativeSpec, PhotoDerivative};
+use crate::photos::import::{self, PhotoImportEvent, PhotoImportReport};
+use crate::photos::mls_export::{self, MlsExportOptions, MlsExportReport};
+use crate::photos::storage::{self, StorageCleanup, StorageReport};
+use crate::photos::{arrange, manager, quality};
 
+/// Pick photos with the native file dialog and import them
 #[tauri::command]
 pub async fn import_photos(
     app_handle: AppHandle,
     pool: State<'_, SqlitePool>,
     property_id: String,
-) -> Result<Vec<Photo>, AppError> {
+) -> Result<PhotoImportReport, AppError> {
     // Open file dialog on a blocking thread (dialog must not run on main thread)
     let file_paths = {
         let handle = app_handle.clone();
@@ -19,7 +32,7 @@ pub async fn import_photos(
             handle
                 .dialog()
                 .file()
-                .add_filter("Images", &["jpg", "jpeg", "png", "gif", "webp", "bmp", "tiff"])
+                .add_filter("Images", manager::IMPORT_EXTENSIONS)
                 .set_title("Select Property Photos")
                 .blocking_pick_files()
         })
@@ -27,72 +40,41 @@ pub async fn import_photos(
         .map_err(|e| AppError::Photo(format!("Dialog thread error: {}", e)))?
     };
 
-    let file_paths = match file_paths {
-        Some(paths) => paths,
-        None => return Ok(Vec::new()), // User cancelled
-    };
-
-    // Convert FilePaths to PathBufs
-    let source_paths: Vec<std::path::PathBuf> = file_paths
+    // Convert FilePaths to PathBufs; None means the user cancelled
+    let source_paths: Vec<PathBuf> = file_paths
+        .unwrap_or_default()
         .into_iter()
         .filter_map(|fp| fp.into_path().ok())
         .collect();
 
     if source_paths.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    // Check existing photo count
-    let existing = photos::list_by_property(&pool, &property_id).await?;
-    let remaining_slots = 20_usize.saturating_sub(existing.len());
-    if remaining_slots == 0 {
-        return Err(AppError::Validation(
-            "Maximum of 20 photos reached for this property".to_string(),
-        ));
+        return Ok(PhotoImportReport {
+            imported: Vec::new(),
+            files: Vec::new(),
+        });
     }
 
-    let paths_to_import = if source_paths.len() > remaining_slots {
-        source_paths[..remaining_slots].to_vec()
-    } else {
-        source_paths
-    };
-
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| AppError::Photo(format!("Failed to get app data dir: {}", e)))?;
-
-    // Determine starting sort order
-    let start_order = existing.len() as i64;
-
-    // Run file I/O (copy + thumbnail) on blocking thread
-    let records = {
-        let pid = property_id.clone();
-        let paths = paths_to_import;
-        tauri::async_runtime::spawn_blocking(move || {
-            manager::import_photos(&app_data_dir, &pid, &paths)
-        })
-        .await
-        .map_err(|e| AppError::Photo(format!("Import thread error: {}", e)))??
-    };
-
-    // Insert into DB
-    let mut inserted = Vec::new();
-    for record in records {
-        let photo = photos::insert(
-            &pool,
-            &record.id,
-            &record.property_id,
-            &record.filename,
-            &record.original_path,
-            &record.thumbnail_path,
-            start_order + record.sort_order,
-        )
-        .await?;
-        inserted.push(photo);
-    }
+    let app_data_dir = app_data_dir(&app_handle)?;
+    import::import_paths(&pool, &app_data_dir, &property_id, &source_paths, |_| {}).await
+}
 
-    Ok(inserted)
+/// Import photos from explicit paths, e.g. files dropped on the window.
+/// Directories are searched recursively for images. Progress is reported
+/// per file over `on_event`.
+#[tauri::command]
+pub async fn import_photo_paths(
+    app_handle: AppHandle,
+    pool: State<'_, SqlitePool>,
+    property_id: String,
+    paths: Vec<String>,
+    on_event: Channel<PhotoImportEvent>,
+) -> Result<PhotoImportReport, AppError> {
+    let paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+    let app_data_dir = app_data_dir(&app_handle)?;
+    import::import_paths(&pool, &app_data_dir, &property_id, &paths, move |event| {
+        let _ = on_event.send(event);
+    })
+    .await
 }
 
 #[tauri::command]
@@ -103,26 +85,10 @@ pub async fn list_photos(
     photos::list_by_property(&pool, &property_id).await
 }
 
+/// Move a photo to the trash. Its files are only removed when the trash is
+/// purged (see `commands::trash`).
 #[tauri::command]
-pub async fn delete_photo(
-    app_handle: AppHandle,
-    pool: State<'_, SqlitePool>,
-    id: String,
-) -> Result<(), AppError> {
-    let photo = photos::get(&pool, &id).await?;
-
-    // Delete files from disk
-    let original = photo.original_path.clone();
-    let thumb = photo.thumbnail_path.clone();
-    let _handle = app_handle; // keep alive for lifetime
-
-    tauri::async_runtime::spawn_blocking(move || {
-        manager::delete_photo_files(&original, &thumb)
-    })
-    .await
-    .map_err(|e| AppError::Photo(format!("Delete thread error: {}", e)))??;
-
-    // Delete from DB
+pub async fn delete_photo(pool: State<'_, SqlitePool>, id: String) -> Result<(), AppError> {
     photos::delete(&pool, &id).await
 }
 
@@ -153,3 +119,243 @@ pub async fn reorder_photos(
 
     Ok(())
 }
+
+#[tauri::command]
+pub async fn update_photo_details(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    input: UpdatePhotoInput,
+) -> Result<Photo, AppError> {
+    photos::update_details(&pool, &id, &input).await
+}
+
+/// Draft captions and alt text for a property's photos. Nothing is saved;
+/// the agent reviews the drafts and saves them with `update_photo_details`.
+#[tauri::command]
+pub async fn generate_photo_captions(
+    pool: State<'_, SqlitePool>,
+    property_id: String,
+    photo_ids: Option<Vec<String>>,
+) -> Result<Vec<CaptionDraft>, AppError> {
+    let property = properties::get(&pool, &property_id)
+        .await
+        .map_err(|_| AppError::PropertyNotFound(property_id.clone()))?;
+
+    // Drafting always sees every photo so positions stay meaningful
+    let all_photos = photos::list_by_property(&pool, &property_id).await?;
+    let selected: Vec<Photo> = match photo_ids {
+        Some(ids) => all_photos
+            .into_iter()
+            .filter(|p| ids.contains(&p.id))
+            .collect(),
+        None => all_photos,
+    };
+    if selected.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let client = load_client(&pool).await?;
+    photo_caption_generator::draft_captions(&client, &property, &selected).await
+}
+
+/// Run vision analysis over a property's photos, store the room, features and
+/// quality score for each, and propose new key features. Nothing is added to
+/// the property until the agent accepts suggestions with
+/// `accept_suggested_features`.
+#[tauri::command]
+pub async fn analyze_photos(
+    pool: State<'_, SqlitePool>,
+    property_id: String,
+    photo_ids: Option<Vec<String>>,
+) -> Result<PhotoAnalysisReport, AppError> {
+    let property = properties::get(&pool, &property_id)
+        .await
+        .map_err(|_| AppError::PropertyNotFound(property_id.clone()))?;
+
+    let mut selected = photos::list_by_property(&pool, &property_id).await?;
+    if let Some(ids) = photo_ids {
+        selected.retain(|p| ids.contains(&p.id));
+    }
+    if selected.is_empty() {
+        return Err(AppError::Validation(
+            "No photos to analyze for this property".to_string(),
+        ));
+    }
+
+    let client = load_client(&pool).await?;
+    let report = photo_analyzer::analyze_photos(&client, &property, &selected).await?;
+
+    for analysis in &report.photos {
+        photos::save_analysis(
+            &pool,
+            &analysis.photo_id,
+            &analysis.room,
+            &analysis.features,
+            analysis.quality_score,
+        )
+        .await?;
+    }
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn accept_suggested_features(
+    pool: State<'_, SqlitePool>,
+    property_id: String,
+    features: Vec<String>,
+) -> Result<Property, AppError> {
+    properties::add_key_features(&pool, &property_id, &features).await
+}
+
+/// Put a property's photos in MLS order with the best lead image first.
+/// Photos are measured once for sharpness, exposure and resolution; the
+/// score is cached on the photo and blended with any vision quality score.
+#[tauri::command]
+pub async fn auto_arrange_photos(
+    pool: State<'_, SqlitePool>,
+    property_id: String,
+) -> Result<Vec<Photo>, AppError> {
+    let mut all_photos = photos::list_by_property(&pool, &property_id).await?;
+
+    let unmeasured: Vec<(String, PathBuf)> = all_photos
+        .iter()
+        .filter(|p| p.hero_score.is_none())
+        .map(|p| (p.id.clone(), PathBuf::from(&p.original_path)))
+        .collect();
+    let scores = tauri::async_runtime::spawn_blocking(move || {
+        unmeasured
+            .into_iter()
+            .map(|(id, path)| {
+                // An unreadable file still gets placed, just never as the hero
+                let score = match quality::measure(&path) {
+                    Ok(metrics) => metrics.hero_score(),
+                    Err(e) => {
+                        eprintln!("Failed to measure photo {}: {}", id, e);
+                        0.0
+                    }
+                };
+                (id, score)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| AppError::Photo(format!("Measurement thread error: {}", e)))?;
+
+    for (id, score) in &scores {
+        photos::save_hero_score(&pool, id, *score).await?;
+        if let Some(photo) = all_photos.iter_mut().find(|p| &p.id == id) {
+            photo.hero_score = Some(*score);
+        }
+    }
+
+    for (index, photo_id) in arrange::arrange(&all_photos).iter().enumerate() {
+        photos::update_sort_order(&pool, photo_id, index as i64).await?;
+    }
+
+    photos::list_by_property(&pool, &property_id).await
+}
+
+/// Write resized, EXIF-free, renamed copies of a property's photos for MLS
+/// upload, in their current order. `dest_path` is a folder, or a zip archive
+/// if it ends in `.zip`.
+#[tauri::command]
+pub async fn export_mls_photos(
+    pool: State<'_, SqlitePool>,
+    property_id: String,
+    dest_path: String,
+    photo_ids: Option<Vec<String>>,
+    options: Option<MlsExportOptions>,
+) -> Result<MlsExportReport, AppError> {
+    let mut selected = photos::list_by_property(&pool, &property_id).await?;
+    if let Some(ids) = photo_ids {
+        selected.retain(|p| ids.contains(&p.id));
+    }
+    if selected.is_empty() {
+        return Err(AppError::Validation(
+            "No photos to export for this property".to_string(),
+        ));
+    }
+
+    let options = options.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        mls_export::export_photos(&selected, Path::new(&dest_path), &options)
+    })
+    .await
+    .map_err(|e| AppError::Export(format!("Photo export task failed: {}", e)))?
+}
+
+/// Sized and cropped copies of photos, e.g. square images for Instagram.
+/// Pass a `preset` or a custom `spec`. Copies are cached on disk and
+/// regenerated when the original changes.
+#[tauri::command]
+pub async fn get_photo_derivatives(
+    app_handle: AppHandle,
+    pool: State<'_, SqlitePool>,
+    photo_ids: Vec<String>,
+    preset: Option<DerivativePreset>,
+    spec: Option<DerivativeSpec>,
+) -> Result<Vec<PhotoDerivative>, AppError> {
+    let spec = match (preset, spec) {
+        (_, Some(spec)) => spec,
+        (Some(preset), None) => preset.spec(),
+        (None, None) => {
+            return Err(AppError::Validation(
+                "Either a preset or a size is required".to_string(),
+            ))
+        }
+    };
+    spec.validate()?;
+
+    let mut selected = Vec::new();
+    for id in &photo_ids {
+        selected.push(photos::get(&pool, id).await?);
+    }
+
+    let app_data_dir = app_data_dir(&app_handle)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        selected
+            .iter()
+            .map(|photo| derivatives::get_or_create(&app_data_dir, photo, &spec))
+            .collect::<Result<Vec<_>, AppError>>()
+    })
+    .await
+    .map_err(|e| AppError::Photo(format!("Image resize thread error: {}", e)))?
+}
+
+/// Disk usage of the photo library, plus files and rows that have lost
+/// their counterpart
+#[tauri::command]
+pub async fn get_photo_storage_report(
+    app_handle: AppHandle,
+    pool: State<'_, SqlitePool>,
+) -> Result<StorageReport, AppError> {
+    storage::storage_report(&pool, &app_data_dir(&app_handle)?).await
+}
+
+#[tauri::command]
+pub async fn clean_up_photo_storage(
+    app_handle: AppHandle,
+    pool: State<'_, SqlitePool>,
+) -> Result<StorageCleanup, AppError> {
+    storage::clean_up(&pool, &app_data_dir(&app_handle)?).await
+}
+
+fn app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Photo(format!("Failed to get app data dir: {}", e)))
+}
+
+async fn load_client(pool: &SqlitePool) -> Result<ClaudeClient, AppError> {
+    let api_key = settings::get(pool, "api_key").await.unwrap_or_default();
+    if api_key.is_empty() {
+        return Err(AppError::MissingApiKey);
+    }
+    let model = settings::get(pool, "ai_model")
+        .await
+        .unwrap_or_else(|_| "claude-sonnet-4-5-20250929".to_string());
+
+    Ok(ClaudeClient::new(api_key, model))
+}
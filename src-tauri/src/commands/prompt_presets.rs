@@ -0,0 +1,144 @@
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::ai::prompts::{
+    build_email_prompt, build_listing_prompt, build_social_prompt, render_preset, AgentInfo,
+    GenerationOptions, DEFAULT_LANGUAGE,
+};
+use crate::db::prompt_presets::{
+    self, PresetKind, PromptPreset, PromptPresetInput, UpdatePromptPresetInput,
+};
+use crate::db::properties::{self, Property};
+use crate::error::AppError;
+
+use super::generate::{assigned_agent, listing_choices, load_agent_info};
+
+/// Stands in for the property analysis, which only exists once generation runs
+const PREVIEW_ANALYSIS: &str = "(The property analysis is generated when the copy is written.)";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptPreview {
+    /// The preset's instructions with the property's variables filled in
+    pub instructions: String,
+    pub system: String,
+    pub user: String,
+}
+
+/// The preset behind a key with its variables filled in for this property;
+/// `None` for built-in keys
+pub(crate) async fn resolve_rendered(
+    db: &SqlitePool,
+    kind: PresetKind,
+    key: &str,
+    property: &Property,
+    agent_info: &AgentInfo,
+) -> Result<Option<PromptPreset>, AppError> {
+    let preset = prompt_presets::resolve(db, kind, key).await?;
+    Ok(preset.map(|mut preset| {
+        preset.instructions = render_preset(&preset.instructions, property, agent_info);
+        preset
+    }))
+}
+
+#[tauri::command]
+pub async fn create_prompt_preset(
+    db: State<'_, SqlitePool>,
+    input: PromptPresetInput,
+) -> Result<PromptPreset, AppError> {
+    prompt_presets::create(&db, input).await
+}
+
+#[tauri::command]
+pub async fn list_prompt_presets(
+    db: State<'_, SqlitePool>,
+    kind: Option<PresetKind>,
+) -> Result<Vec<PromptPreset>, AppError> {
+    prompt_presets::list(&db, kind).await
+}
+
+#[tauri::command]
+pub async fn update_prompt_preset(
+    db: State<'_, SqlitePool>,
+    id: String,
+    input: UpdatePromptPresetInput,
+) -> Result<PromptPreset, AppError> {
+    prompt_presets::update(&db, &id, input).await
+}
+
+#[tauri::command]
+pub async fn delete_prompt_preset(db: State<'_, SqlitePool>, id: String) -> Result<(), AppError> {
+    prompt_presets::delete(&db, &id).await
+}
+
+/// Render the prompt a preset would produce for a property, before or after
+/// it is saved. Listing presets are shown with the agent's defaults (or the
+/// first built-in option) for the other settings.
+#[tauri::command]
+pub async fn preview_prompt_preset(
+    db: State<'_, SqlitePool>,
+    property_id: String,
+    input: PromptPresetInput,
+) -> Result<PromptPreview, AppError> {
+    prompt_presets::validate(&input.name, &input.instructions)?;
+
+    let property = properties::get(&db, &property_id)
+        .await
+        .map_err(|_| AppError::PropertyNotFound(property_id.clone()))?;
+    let agent = assigned_agent(&db, &property).await?;
+    let agent_info = load_agent_info(&db, agent.as_ref()).await;
+
+    let instructions = render_preset(input.instructions.trim(), &property, &agent_info);
+    let preset = PromptPreset {
+        id: String::new(),
+        kind: input.kind.as_str().to_string(),
+        key: String::new(),
+        name: input.name.trim().to_string(),
+        instructions: instructions.clone(),
+        created_at: String::new(),
+        updated_at: String::new(),
+    };
+
+    let (system, user) = match input.kind {
+        PresetKind::Style | PresetKind::Tone | PresetKind::Length => {
+            let (style, tone, length) = listing_choices(agent.as_ref(), None, None, None);
+            let custom = |kind: PresetKind| (input.kind == kind).then(|| instructions.clone());
+            let options = GenerationOptions {
+                style,
+                tone,
+                length,
+                seo_keywords: vec![],
+                custom_style: custom(PresetKind::Style),
+                custom_tone: custom(PresetKind::Tone),
+                custom_length: custom(PresetKind::Length),
+                language: DEFAULT_LANGUAGE.to_string(),
+            };
+            build_listing_prompt(&property, PREVIEW_ANALYSIS, &options, None, &agent_info)
+        }
+        PresetKind::Platform => build_social_prompt(
+            &property,
+            PREVIEW_ANALYSIS,
+            &preset.name,
+            Some(&preset),
+            None,
+            DEFAULT_LANGUAGE,
+            &agent_info,
+        ),
+        PresetKind::EmailTemplate => build_email_prompt(
+            &property,
+            PREVIEW_ANALYSIS,
+            &preset.name,
+            Some(&preset),
+            None,
+            DEFAULT_LANGUAGE,
+            &agent_info,
+        ),
+    };
+
+    Ok(PromptPreview {
+        instructions,
+        system,
+        user,
+    })
+}
@@ -1,7 +1,11 @@
 use sqlx::SqlitePool;
 use tauri::State;
 
-use crate::db::properties::{self, CreatePropertyInput, Property};
+use crate::db::properties::{
+    self, CreatePropertyInput, Property, PropertyStatusEvent, SetStatusInput,
+};
+use crate::db::price_history::{self, PriceChange};
+use crate::db::search::{self, PropertySearchPage, PropertySearchQuery};
 use crate::error::AppError;
 
 #[tauri::command]
@@ -27,6 +31,14 @@ pub async fn list_properties(
     properties::list_all(&pool).await
 }
 
+#[tauri::command]
+pub async fn search_properties(
+    pool: State<'_, SqlitePool>,
+    query: PropertySearchQuery,
+) -> Result<PropertySearchPage, AppError> {
+    search::search_properties(&pool, &query).await
+}
+
 #[tauri::command]
 pub async fn update_property(
     pool: State<'_, SqlitePool>,
@@ -43,3 +55,28 @@ pub async fn delete_property(
 ) -> Result<(), AppError> {
     properties::delete(&pool, &id).await
 }
+
+#[tauri::command]
+pub async fn set_property_status(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    input: SetStatusInput,
+) -> Result<Property, AppError> {
+    properties::set_status(&pool, &id, input).await
+}
+
+#[tauri::command]
+pub async fn list_property_status_history(
+    pool: State<'_, SqlitePool>,
+    property_id: String,
+) -> Result<Vec<PropertyStatusEvent>, AppError> {
+    properties::list_status_history(&pool, &property_id).await
+}
+
+#[tauri::command]
+pub async fn list_price_history(
+    pool: State<'_, SqlitePool>,
+    property_id: String,
+) -> Result<Vec<PriceChange>, AppError> {
+    price_history::list_by_property(&pool, &property_id).await
+}
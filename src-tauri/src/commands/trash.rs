@@ -0,0 +1,94 @@
+use std::path::{Path, PathBuf};
+
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::photos::Photo;
+use crate::db::settings;
+use crate::db::trash::{self, TrashItem, TrashKind, DEFAULT_RETENTION_DAYS};
+use crate::error::AppError;
+use crate::photos::{derivatives, manager};
+
+async fn retention_days(db: &SqlitePool) -> i64 {
+    settings::get(db, "trash_retention_days")
+        .await
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|&days| days >= 1)
+        .unwrap_or(DEFAULT_RETENTION_DAYS)
+}
+
+fn app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Photo(format!("Failed to get app data dir: {}", e)))
+}
+
+/// Remove the files and cached derivatives of purged photos. Failures are
+/// logged rather than returned since the database rows are already gone.
+async fn delete_files(app_data_dir: &Path, photos: Vec<Photo>) {
+    if photos.is_empty() {
+        return;
+    }
+
+    let app_data_dir = app_data_dir.to_path_buf();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        for photo in photos {
+            if let Err(e) = manager::delete_photo_files(&photo.original_path, &photo.thumbnail_path)
+            {
+                eprintln!("Failed to delete files for photo {}: {}", photo.id, e);
+            }
+            if let Err(e) = derivatives::invalidate(&app_data_dir, &photo.id) {
+                eprintln!("Failed to clear cached images for photo {}: {}", photo.id, e);
+            }
+        }
+    })
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("Photo cleanup thread error: {}", e);
+    }
+}
+
+/// Purge everything older than the retention setting. Runs at startup.
+pub(crate) async fn purge_expired(db: &SqlitePool, app_data_dir: &Path) -> Result<(), AppError> {
+    let days = retention_days(db).await;
+    let photos = trash::purge_expired(db, days).await?;
+    delete_files(app_data_dir, photos).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_trash(db: State<'_, SqlitePool>) -> Result<Vec<TrashItem>, AppError> {
+    let days = retention_days(&db).await;
+    trash::list(&db, days).await
+}
+
+#[tauri::command]
+pub async fn restore_from_trash(
+    db: State<'_, SqlitePool>,
+    kind: TrashKind,
+    id: String,
+) -> Result<(), AppError> {
+    trash::restore(&db, kind, &id).await
+}
+
+#[tauri::command]
+pub async fn delete_from_trash(
+    app_handle: AppHandle,
+    db: State<'_, SqlitePool>,
+    kind: TrashKind,
+    id: String,
+) -> Result<(), AppError> {
+    let photos = trash::purge(&db, kind, &id).await?;
+    delete_files(&app_data_dir(&app_handle)?, photos).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn empty_trash(app_handle: AppHandle, db: State<'_, SqlitePool>) -> Result<(), AppError> {
+    let photos = trash::purge_expired(&db, 0).await?;
+    delete_files(&app_data_dir(&app_handle)?, photos).await;
+    Ok(())
+}
@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Agent {
+    pub id: String,
+    pub name: String,
+    pub phone: String,
+    pub email: String,
+    pub brokerage_name: String,
+    pub license_number: Option<String>,
+    pub signature: Option<String>,
+    pub default_brand_voice_id: Option<String>,
+    pub default_style: Option<String>,
+    pub default_tone: Option<String>,
+    pub default_length: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAgentInput {
+    pub name: String,
+    #[serde(default)]
+    pub phone: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub brokerage_name: String,
+    pub license_number: Option<String>,
+    pub signature: Option<String>,
+    pub default_brand_voice_id: Option<String>,
+    pub default_style: Option<String>,
+    pub default_tone: Option<String>,
+    pub default_length: Option<String>,
+}
+
+const SELECT_COLUMNS: &str = "SELECT id, name, phone, email, brokerage_name, license_number, signature, default_brand_voice_id, default_style, default_tone, default_length, created_at, updated_at FROM agents";
+
+pub async fn create(pool: &SqlitePool, input: CreateAgentInput) -> Result<Agent, AppError> {
+    if input.name.trim().is_empty() {
+        return Err(AppError::Validation("Agent name is required".to_string()));
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+
+    sqlx::query(
+        "INSERT INTO agents (id, name, phone, email, brokerage_name, license_number, signature, default_brand_voice_id, default_style, default_tone, default_length)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(input.name.trim())
+    .bind(&input.phone)
+    .bind(&input.email)
+    .bind(&input.brokerage_name)
+    .bind(&input.license_number)
+    .bind(&input.signature)
+    .bind(&input.default_brand_voice_id)
+    .bind(&input.default_style)
+    .bind(&input.default_tone)
+    .bind(&input.default_length)
+    .execute(pool)
+    .await?;
+
+    get(pool, &id).await
+}
+
+pub async fn get(pool: &SqlitePool, id: &str) -> Result<Agent, AppError> {
+    sqlx::query_as::<_, Agent>(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::AgentNotFound(id.to_string()))
+}
+
+pub async fn list_all(pool: &SqlitePool) -> Result<Vec<Agent>, AppError> {
+    let agents = sqlx::query_as::<_, Agent>(&format!("{} ORDER BY name ASC", SELECT_COLUMNS))
+        .fetch_all(pool)
+        .await?;
+
+    Ok(agents)
+}
+
+pub async fn update(
+    pool: &SqlitePool,
+    id: &str,
+    input: CreateAgentInput,
+) -> Result<Agent, AppError> {
+    if input.name.trim().is_empty() {
+        return Err(AppError::Validation("Agent name is required".to_string()));
+    }
+
+    let result = sqlx::query(
+        "UPDATE agents SET name = ?, phone = ?, email = ?, brokerage_name = ?, license_number = ?, signature = ?, default_brand_voice_id = ?, default_style = ?, default_tone = ?, default_length = ?, updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(input.name.trim())
+    .bind(&input.phone)
+    .bind(&input.email)
+    .bind(&input.brokerage_name)
+    .bind(&input.license_number)
+    .bind(&input.signature)
+    .bind(&input.default_brand_voice_id)
+    .bind(&input.default_style)
+    .bind(&input.default_tone)
+    .bind(&input.default_length)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::AgentNotFound(id.to_string()));
+    }
+
+    get(pool, id).await
+}
+
+/// Delete an agent profile. Properties assigned to it fall back to the
+/// settings-based agent info (`ON DELETE SET NULL`).
+pub async fn delete(pool: &SqlitePool, id: &str) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM agents WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{properties, test_pool};
+
+    fn sample_input() -> CreateAgentInput {
+        CreateAgentInput {
+            name: "Jane Smith".to_string(),
+            phone: "555-1234".to_string(),
+            email: "jane@example.com".to_string(),
+            brokerage_name: "RE/MAX".to_string(),
+            license_number: Some("DRE #01234567".to_string()),
+            signature: Some("Jane Smith | Your Bay Area Home Expert".to_string()),
+            default_brand_voice_id: None,
+            default_style: Some("luxury".to_string()),
+            default_tone: Some("warm".to_string()),
+            default_length: Some("medium".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get() {
+        let pool = test_pool().await;
+        let agent = create(&pool, sample_input()).await.unwrap();
+
+        assert_eq!(agent.name, "Jane Smith");
+        assert_eq!(agent.default_style.as_deref(), Some("luxury"));
+
+        let fetched = get(&pool, &agent.id).await.unwrap();
+        assert_eq!(fetched.id, agent.id);
+        assert_eq!(fetched.signature, agent.signature);
+    }
+
+    #[tokio::test]
+    async fn test_create_requires_name() {
+        let pool = test_pool().await;
+        let mut input = sample_input();
+        input.name = "   ".to_string();
+
+        assert!(create(&pool, input).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_default_style_rejected() {
+        let pool = test_pool().await;
+        let mut input = sample_input();
+        input.default_style = Some("professional".to_string());
+
+        assert!(create(&pool, input).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_and_list() {
+        let pool = test_pool().await;
+        let agent = create(&pool, sample_input()).await.unwrap();
+
+        let mut input = sample_input();
+        input.name = "Alex Lee".to_string();
+        input.default_tone = Some("exciting".to_string());
+        let updated = update(&pool, &agent.id, input).await.unwrap();
+        assert_eq!(updated.name, "Alex Lee");
+        assert_eq!(updated.default_tone.as_deref(), Some("exciting"));
+
+        create(&pool, sample_input()).await.unwrap();
+        let all = list_all(&pool).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].name, "Alex Lee");
+
+        let missing = update(&pool, "missing", sample_input()).await;
+        assert!(matches!(missing, Err(AppError::AgentNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_unassigns_properties() {
+        let pool = test_pool().await;
+        let agent = create(&pool, sample_input()).await.unwrap();
+
+        let property = properties::create(
+            &pool,
+            properties::CreatePropertyInput {
+                agent_id: Some(Some(agent.id.clone())),
+                ..crate::db::test_property_input()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(property.agent_id.as_deref(), Some(agent.id.as_str()));
+
+        delete(&pool, &agent.id).await.unwrap();
+        assert!(get(&pool, &agent.id).await.is_err());
+
+        let property = properties::get(&pool, &property.id).await.unwrap();
+        assert!(property.agent_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_property_edit_keeps_agent_unless_sent() {
+        let pool = test_pool().await;
+        let agent = create(&pool, sample_input()).await.unwrap();
+
+        let mut input = serde_json::json!({
+            "address": "123 Oak Street",
+            "city": "San Francisco",
+            "state": "CA",
+            "zip": "94105",
+            "beds": 3,
+            "baths": 2.5,
+            "sqft": 1800,
+            "price": 95000000,
+            "propertyType": "single_family",
+            "keyFeatures": [],
+            "neighborhoodHighlights": [],
+            "nearbyAmenities": [],
+            "agentId": agent.id,
+        });
+        let property = properties::create(&pool, serde_json::from_value(input.clone()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(property.agent_id.as_deref(), Some(agent.id.as_str()));
+
+        // An edit that doesn't mention the agent leaves it assigned
+        input.as_object_mut().unwrap().remove("agentId");
+        let edited = properties::update(
+            &pool,
+            &property.id,
+            serde_json::from_value(input.clone()).unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(edited.agent_id.as_deref(), Some(agent.id.as_str()));
+
+        input["agentId"] = serde_json::Value::Null;
+        let edited =
+            properties::update(&pool, &property.id, serde_json::from_value(input).unwrap())
+                .await
+                .unwrap();
+        assert!(edited.agent_id.is_none());
+    }
+}
@@ -1,5 +1,8 @@
+use std::collections::BTreeSet;
+
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use serde_json::{Map, Value};
+use sqlx::{FromRow, Sqlite, SqlitePool, Transaction};
 
 use crate::error::AppError;
 
@@ -16,6 +19,226 @@ pub struct BrandVoice {
     pub updated_at: String,
 }
 
+/// The writing style extracted from an agent's listings, stored as JSON in
+/// `extracted_style`. Every field defaults to empty so older and hand-edited
+/// profiles still load; `validate` enforces the schema on new ones.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoiceProfile {
+    pub tone: String,
+    pub vocabulary: Vec<String>,
+    pub sentence_patterns: String,
+    pub themes: Vec<String>,
+    pub signature_phrases: Vec<String>,
+    /// Words and patterns the agent never uses
+    pub avoids: Vec<String>,
+    /// How descriptions are structured
+    pub formatting: String,
+}
+
+impl VoiceProfile {
+    pub const FIELDS: [&'static str; 7] = [
+        "tone",
+        "vocabulary",
+        "sentence_patterns",
+        "themes",
+        "signature_phrases",
+        "avoids",
+        "formatting",
+    ];
+
+    /// Parse a stored profile; missing fields are left empty
+    pub fn from_json(json: &str) -> Result<Self, AppError> {
+        serde_json::from_str(json)
+            .map_err(|e| AppError::Validation(format!("Invalid voice profile: {}", e)))
+    }
+
+    pub fn to_json(&self) -> Result<String, AppError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Trim every entry and drop blank and repeated list items
+    pub fn normalize(&mut self) {
+        for text in [
+            &mut self.tone,
+            &mut self.sentence_patterns,
+            &mut self.formatting,
+        ] {
+            *text = text.trim().to_string();
+        }
+        for list in [
+            &mut self.vocabulary,
+            &mut self.themes,
+            &mut self.signature_phrases,
+            &mut self.avoids,
+        ] {
+            let mut seen = Vec::new();
+            for item in list.drain(..) {
+                let item = item.trim().to_string();
+                let repeated = seen.iter().any(|s: &String| s.eq_ignore_ascii_case(&item));
+                if !item.is_empty() && !repeated {
+                    seen.push(item);
+                }
+            }
+            *list = seen;
+        }
+    }
+
+    /// Required fields that are empty
+    pub fn missing_fields(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if self.tone.is_empty() {
+            missing.push("tone");
+        }
+        if self.vocabulary.is_empty() {
+            missing.push("vocabulary");
+        }
+        if self.sentence_patterns.is_empty() {
+            missing.push("sentence_patterns");
+        }
+        if self.themes.is_empty() {
+            missing.push("themes");
+        }
+        missing
+    }
+
+    pub fn validate(&self) -> Result<(), AppError> {
+        let missing = self.missing_fields();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(format!(
+                "Voice profile is missing {}",
+                missing.join(", ")
+            )))
+        }
+    }
+}
+
+/// A snapshot of a voice's extracted style, recorded on every change
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct BrandVoiceVersion {
+    pub id: String,
+    pub brand_voice_id: String,
+    pub version: i64,
+    pub extracted_style: String,
+    pub sample_count: i64,
+    /// "extracted", "edited", "retrained" or "restored"
+    pub change_source: String,
+    pub created_at: String,
+}
+
+/// Omitted fields are left unchanged. `style` is merged into the voice
+/// profile field by field; a null value clears the field.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBrandVoiceInput {
+    pub name: Option<String>,
+    /// Empty string clears the description
+    pub description: Option<String>,
+    pub style: Option<Map<String, Value>>,
+}
+
+/// One field that differs between two extracted styles
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleChange {
+    pub field: String,
+    /// None when the field is new
+    pub before: Option<Value>,
+    /// None when the field was dropped
+    pub after: Option<Value>,
+    /// For list fields, entries only in the new style
+    pub added: Vec<String>,
+    /// For list fields, entries only in the old style
+    pub removed: Vec<String>,
+}
+
+pub(crate) async fn record_version(
+    tx: &mut Transaction<'_, Sqlite>,
+    brand_voice_id: &str,
+    extracted_style: &str,
+    sample_count: i64,
+    change_source: &str,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO brand_voice_versions (id, brand_voice_id, version, extracted_style, sample_count, change_source)
+         SELECT ?, ?, COALESCE(MAX(version), 0) + 1, ?, ?, ? FROM brand_voice_versions WHERE brand_voice_id = ?",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(brand_voice_id)
+    .bind(extracted_style)
+    .bind(sample_count)
+    .bind(change_source)
+    .bind(brand_voice_id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Apply a field-by-field patch to an extracted style JSON object
+pub fn merge_style(extracted_style: &str, patch: &Map<String, Value>) -> Result<String, AppError> {
+    let mut style: Map<String, Value> = serde_json::from_str(extracted_style).map_err(|e| {
+        AppError::Validation(format!("Stored voice style is not a JSON object: {}", e))
+    })?;
+    for (key, value) in patch {
+        if value.is_null() {
+            style.remove(key);
+        } else {
+            style.insert(key.clone(), value.clone());
+        }
+    }
+    Ok(serde_json::to_string(&style)?)
+}
+
+/// Field-by-field differences between two extracted styles, in field order
+pub fn diff_styles(before: &str, after: &str) -> Result<Vec<StyleChange>, AppError> {
+    let parse = |style: &str| -> Result<Map<String, Value>, AppError> {
+        serde_json::from_str(style)
+            .map_err(|e| AppError::Validation(format!("Voice style is not a JSON object: {}", e)))
+    };
+    let before = parse(before)?;
+    let after = parse(after)?;
+
+    let fields: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    let mut changes = Vec::new();
+    for field in fields {
+        let old = before.get(field);
+        let new = after.get(field);
+        if old == new {
+            continue;
+        }
+        let items = |value: Option<&Value>| -> Vec<String> {
+            value
+                .and_then(Value::as_array)
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|v| v.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let (old_items, new_items) = (items(old), items(new));
+        changes.push(StyleChange {
+            field: field.clone(),
+            before: old.cloned(),
+            after: new.cloned(),
+            added: new_items
+                .iter()
+                .filter(|i| !old_items.contains(i))
+                .cloned()
+                .collect(),
+            removed: old_items
+                .iter()
+                .filter(|i| !new_items.contains(i))
+                .cloned()
+                .collect(),
+        });
+    }
+    Ok(changes)
+}
+
 pub async fn create(
     pool: &SqlitePool,
     name: &str,
@@ -27,6 +250,7 @@ pub async fn create(
     let source_json = serde_json::to_string(source_listings)?;
     let sample_count = source_listings.len() as i64;
 
+    let mut tx = pool.begin().await?;
     sqlx::query(
         "INSERT INTO brand_voices (id, name, description, extracted_style, source_listings, sample_count)
          VALUES (?, ?, ?, ?, ?, ?)",
@@ -37,8 +261,10 @@ pub async fn create(
     .bind(extracted_style)
     .bind(&source_json)
     .bind(sample_count)
-    .execute(pool)
+    .execute(&mut *tx)
     .await?;
+    record_version(&mut tx, &id, extracted_style, sample_count, "extracted").await?;
+    tx.commit().await?;
 
     get(pool, &id).await
 }
@@ -64,6 +290,196 @@ pub async fn list_all(pool: &SqlitePool) -> Result<Vec<BrandVoice>, AppError> {
     Ok(voices)
 }
 
+pub async fn update(
+    pool: &SqlitePool,
+    id: &str,
+    input: &UpdateBrandVoiceInput,
+) -> Result<BrandVoice, AppError> {
+    let voice = get(pool, id).await?;
+
+    let name = match input.name.as_deref().map(str::trim) {
+        Some("") => {
+            return Err(AppError::Validation(
+                "Brand voice name cannot be empty".to_string(),
+            ))
+        }
+        Some(name) => name.to_string(),
+        None => voice.name.clone(),
+    };
+    let description = match input.description.as_deref().map(str::trim) {
+        Some("") => None,
+        Some(description) => Some(description.to_string()),
+        None => voice.description.clone(),
+    };
+    let extracted_style = match &input.style {
+        Some(patch) => {
+            if let Some(field) = patch
+                .keys()
+                .find(|k| !VoiceProfile::FIELDS.contains(&k.as_str()))
+            {
+                return Err(AppError::Validation(format!(
+                    "Unknown voice profile field '{}'",
+                    field
+                )));
+            }
+            let mut profile =
+                VoiceProfile::from_json(&merge_style(&voice.extracted_style, patch)?)?;
+            profile.normalize();
+            profile.validate()?;
+            profile.to_json()?
+        }
+        None => voice.extracted_style.clone(),
+    };
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "UPDATE brand_voices SET name = ?, description = ?, extracted_style = ?, updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(&name)
+    .bind(&description)
+    .bind(&extracted_style)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+    if extracted_style != voice.extracted_style {
+        record_version(&mut tx, id, &extracted_style, voice.sample_count, "edited").await?;
+    }
+    tx.commit().await?;
+
+    get(pool, id).await
+}
+
+/// Replace the style with one retrained on additional samples, which are
+/// appended to the voice's source listings
+pub async fn add_samples(
+    pool: &SqlitePool,
+    id: &str,
+    new_samples: &[String],
+    extracted_style: &str,
+) -> Result<BrandVoice, AppError> {
+    let voice = get(pool, id).await?;
+    let mut sources: Vec<String> = serde_json::from_str(&voice.source_listings)?;
+    sources.extend(new_samples.iter().cloned());
+    let sample_count = sources.len() as i64;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "UPDATE brand_voices SET extracted_style = ?, source_listings = ?, sample_count = ?, updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(extracted_style)
+    .bind(serde_json::to_string(&sources)?)
+    .bind(sample_count)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+    record_version(&mut tx, id, extracted_style, sample_count, "retrained").await?;
+    tx.commit().await?;
+
+    get(pool, id).await
+}
+
+pub async fn list_versions(
+    pool: &SqlitePool,
+    brand_voice_id: &str,
+) -> Result<Vec<BrandVoiceVersion>, AppError> {
+    let versions = sqlx::query_as::<_, BrandVoiceVersion>(
+        "SELECT id, brand_voice_id, version, extracted_style, sample_count, change_source, created_at FROM brand_voice_versions WHERE brand_voice_id = ? ORDER BY version DESC",
+    )
+    .bind(brand_voice_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(versions)
+}
+
+/// Make an earlier version's style current again. Recorded as a new version
+/// so the history stays linear.
+pub async fn restore_version(
+    pool: &SqlitePool,
+    brand_voice_id: &str,
+    version: i64,
+) -> Result<BrandVoice, AppError> {
+    let voice = get(pool, brand_voice_id).await?;
+    let (extracted_style,): (String,) = sqlx::query_as(
+        "SELECT extracted_style FROM brand_voice_versions WHERE brand_voice_id = ? AND version = ?",
+    )
+    .bind(brand_voice_id)
+    .bind(version)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::Validation(format!("Brand voice has no version {}", version)))?;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "UPDATE brand_voices SET extracted_style = ?, updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(&extracted_style)
+    .bind(brand_voice_id)
+    .execute(&mut *tx)
+    .await?;
+    record_version(
+        &mut tx,
+        brand_voice_id,
+        &extracted_style,
+        voice.sample_count,
+        "restored",
+    )
+    .await?;
+    tx.commit().await?;
+
+    get(pool, brand_voice_id).await
+}
+
+/// Samples as stored with a suggestion: sorted, so the same feedback matches
+/// whatever order it was listed in
+fn samples_key(samples: &[String]) -> Result<String, AppError> {
+    let mut sorted: Vec<&str> = samples.iter().map(|s| s.trim()).collect();
+    sorted.sort_unstable();
+    Ok(serde_json::to_string(&sorted)?)
+}
+
+/// The style last proposed for a voice, if it was trained from
+/// `base_style` on exactly these samples
+pub async fn get_suggestion(
+    pool: &SqlitePool,
+    brand_voice_id: &str,
+    base_style: &str,
+    samples: &[String],
+) -> Result<Option<String>, AppError> {
+    let proposed: Option<(String,)> = sqlx::query_as(
+        "SELECT proposed_style FROM brand_voice_suggestions WHERE brand_voice_id = ? AND base_style = ? AND samples = ?",
+    )
+    .bind(brand_voice_id)
+    .bind(base_style)
+    .bind(samples_key(samples)?)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(proposed.map(|(style,)| style))
+}
+
+/// Remember a proposed style, replacing the voice's previous suggestion
+pub async fn save_suggestion(
+    pool: &SqlitePool,
+    brand_voice_id: &str,
+    base_style: &str,
+    samples: &[String],
+    proposed_style: &str,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO brand_voice_suggestions (brand_voice_id, base_style, samples, proposed_style)
+         VALUES (?, ?, ?, ?)
+         ON CONFLICT(brand_voice_id) DO UPDATE SET base_style = excluded.base_style, samples = excluded.samples, proposed_style = excluded.proposed_style, created_at = datetime('now')",
+    )
+    .bind(brand_voice_id)
+    .bind(base_style)
+    .bind(samples_key(samples)?)
+    .bind(proposed_style)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
 pub async fn delete(pool: &SqlitePool, id: &str) -> Result<(), AppError> {
     sqlx::query("DELETE FROM brand_voices WHERE id = ?")
         .bind(id)
@@ -113,4 +529,175 @@ mod tests {
         let all = list_all(&pool).await.unwrap();
         assert_eq!(all.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_suggestion_is_kept_until_samples_or_style_change() {
+        let pool = test_pool().await;
+        let style = r#"{"tone":"warm"}"#;
+        let voice = create(&pool, "Voice", None, style, &["sample".to_string()])
+            .await
+            .unwrap();
+        let samples = vec!["First".to_string(), "Second".to_string()];
+        let proposed = r#"{"tone":"bright"}"#;
+
+        assert_eq!(
+            get_suggestion(&pool, &voice.id, style, &samples)
+                .await
+                .unwrap(),
+            None
+        );
+        save_suggestion(&pool, &voice.id, style, &samples, proposed)
+            .await
+            .unwrap();
+        let reordered = vec!["Second".to_string(), "First".to_string()];
+        assert_eq!(
+            get_suggestion(&pool, &voice.id, style, &reordered)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some(proposed)
+        );
+
+        // New feedback or a changed voice needs a fresh suggestion
+        let more = vec![
+            "First".to_string(),
+            "Second".to_string(),
+            "Third".to_string(),
+        ];
+        assert_eq!(
+            get_suggestion(&pool, &voice.id, style, &more)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            get_suggestion(&pool, &voice.id, proposed, &samples)
+                .await
+                .unwrap(),
+            None
+        );
+
+        delete(&pool, &voice.id).await.unwrap();
+        assert_eq!(
+            get_suggestion(&pool, &voice.id, style, &samples)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_merges_style_and_records_versions() {
+        let pool = test_pool().await;
+        let style = r#"{"tone":"warm","vocabulary":["stunning"],"sentence_patterns":"short","themes":["light"],"signature_phrases":[],"avoids":["cozy"],"formatting":""}"#;
+        let voice = create(
+            &pool,
+            "Mine",
+            Some("Old"),
+            style,
+            &["a".to_string(), "b".to_string()],
+        )
+        .await
+        .unwrap();
+
+        let mut patch = Map::new();
+        patch.insert("tone".to_string(), Value::from("crisp and confident"));
+        patch.insert("avoids".to_string(), Value::Null);
+        let updated = update(
+            &pool,
+            &voice.id,
+            &UpdateBrandVoiceInput {
+                name: Some(" Renamed ".to_string()),
+                description: Some(String::new()),
+                style: Some(patch),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.name, "Renamed");
+        assert_eq!(updated.description, None);
+        let merged: Value = serde_json::from_str(&updated.extracted_style).unwrap();
+        assert_eq!(merged["tone"], "crisp and confident");
+        assert_eq!(merged["vocabulary"][0], "stunning");
+        assert_eq!(merged["avoids"], serde_json::json!([]));
+
+        // Edits can't break the schema
+        let mut patch = Map::new();
+        patch.insert("tone".to_string(), Value::Null);
+        let input = UpdateBrandVoiceInput {
+            style: Some(patch),
+            ..Default::default()
+        };
+        assert!(update(&pool, &voice.id, &input).await.is_err());
+        let mut patch = Map::new();
+        patch.insert("mood".to_string(), Value::from("sunny"));
+        let input = UpdateBrandVoiceInput {
+            style: Some(patch),
+            ..Default::default()
+        };
+        assert!(update(&pool, &voice.id, &input).await.is_err());
+
+        // A rename alone doesn't add a version
+        update(
+            &pool,
+            &voice.id,
+            &UpdateBrandVoiceInput {
+                name: Some("Again".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let retrained = add_samples(&pool, &voice.id, &["c".to_string()], r#"{"tone":"new"}"#)
+            .await
+            .unwrap();
+        assert_eq!(retrained.sample_count, 3);
+
+        let versions = list_versions(&pool, &voice.id).await.unwrap();
+        let sources: Vec<&str> = versions.iter().map(|v| v.change_source.as_str()).collect();
+        assert_eq!(sources, vec!["retrained", "edited", "extracted"]);
+        assert_eq!(versions[0].version, 3);
+
+        let restored = restore_version(&pool, &voice.id, 1).await.unwrap();
+        assert_eq!(restored.extracted_style, style);
+        assert_eq!(
+            list_versions(&pool, &voice.id).await.unwrap()[0].change_source,
+            "restored"
+        );
+        assert!(restore_version(&pool, &voice.id, 99).await.is_err());
+    }
+
+    #[test]
+    fn test_diff_styles() {
+        let before = r#"{"tone":"warm","vocabulary":["stunning","nestled"],"avoids":["cozy"]}"#;
+        let after =
+            r#"{"tone":"warm","vocabulary":["stunning","sun-drenched"],"formatting":"short"}"#;
+        let changes = diff_styles(before, after).unwrap();
+
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["avoids", "formatting", "vocabulary"]);
+        assert_eq!(changes[0].after, None);
+        assert_eq!(changes[1].before, None);
+        assert_eq!(changes[2].added, vec!["sun-drenched"]);
+        assert_eq!(changes[2].removed, vec!["nestled"]);
+    }
+
+    #[test]
+    fn test_voice_profile_normalizes_and_validates() {
+        let mut profile = VoiceProfile::from_json(
+            r#"{"tone":" warm ","vocabulary":["Stunning","stunning"," "]}"#,
+        )
+        .unwrap();
+        profile.normalize();
+        assert_eq!(profile.tone, "warm");
+        assert_eq!(profile.vocabulary, vec!["Stunning"]);
+        assert_eq!(
+            profile.missing_fields(),
+            vec!["sentence_patterns", "themes"]
+        );
+        assert!(profile.validate().is_err());
+
+        assert!(VoiceProfile::from_json(r#"{"tone":5}"#).is_err());
+    }
 }
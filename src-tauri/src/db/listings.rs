@@ -28,6 +28,25 @@ pub struct Listing {
     pub generation_cost_cents: i64,
     pub is_favorite: bool,
     pub created_at: String,
+    /// Set when the agent edits the generated copy
+    pub edited_at: Option<String>,
+    /// Brand voice consistency, 0-100, from the latest scoring pass
+    pub voice_score: Option<f64>,
+    /// JSON breakdown of the voice score
+    pub voice_score_details: Option<String>,
+    /// Shared by listings generated together as variants
+    pub variant_group_id: Option<String>,
+    /// Custom platform or email template preset the copy was written with
+    pub prompt_preset_id: Option<String>,
+    /// Language code of the copy, from `prompts::LANGUAGES`
+    pub language: String,
+    /// Listing this one was translated from
+    pub source_listing_id: Option<String>,
+    /// Claims in the copy the property record doesn't support, from the
+    /// latest fact check
+    pub unsupported_claims: Option<i64>,
+    /// JSON `FactCheck` with every claim found
+    pub fact_check_details: Option<String>,
 }
 
 pub struct CreateListingInput {
@@ -41,6 +60,10 @@ pub struct CreateListingInput {
     pub brand_voice_id: Option<String>,
     pub tokens_used: i64,
     pub generation_cost_cents: i64,
+    pub variant_group_id: Option<String>,
+    pub prompt_preset_id: Option<String>,
+    pub language: String,
+    pub source_listing_id: Option<String>,
 }
 
 pub async fn save(pool: &SqlitePool, input: CreateListingInput) -> Result<Listing, AppError> {
@@ -48,8 +71,8 @@ pub async fn save(pool: &SqlitePool, input: CreateListingInput) -> Result<Listin
     let seo_keywords = serde_json::to_string(&input.seo_keywords)?;
 
     sqlx::query(
-        "INSERT INTO listings (id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        "INSERT INTO listings (id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents, variant_group_id, prompt_preset_id, language, source_listing_id)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(&id)
     .bind(&input.property_id)
@@ -62,6 +85,10 @@ pub async fn save(pool: &SqlitePool, input: CreateListingInput) -> Result<Listin
     .bind(&input.brand_voice_id)
     .bind(input.tokens_used)
     .bind(input.generation_cost_cents)
+    .bind(&input.variant_group_id)
+    .bind(&input.prompt_preset_id)
+    .bind(&input.language)
+    .bind(&input.source_listing_id)
     .execute(pool)
     .await?;
 
@@ -70,7 +97,7 @@ pub async fn save(pool: &SqlitePool, input: CreateListingInput) -> Result<Listin
 
 pub async fn get(pool: &SqlitePool, id: &str) -> Result<Listing, AppError> {
     let row = sqlx::query(
-        "SELECT id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents, is_favorite, created_at FROM listings WHERE id = ?"
+        "SELECT id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents, is_favorite, created_at, edited_at, voice_score, voice_score_details, variant_group_id, prompt_preset_id, language, source_listing_id, unsupported_claims, fact_check_details FROM listings WHERE id = ? AND deleted_at IS NULL"
     )
     .bind(id)
     .fetch_one(pool)
@@ -84,7 +111,7 @@ pub async fn list_by_property(
     property_id: &str,
 ) -> Result<Vec<Listing>, AppError> {
     let rows = sqlx::query(
-        "SELECT id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents, is_favorite, created_at FROM listings WHERE property_id = ? ORDER BY created_at DESC"
+        "SELECT id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents, is_favorite, created_at, edited_at, voice_score, voice_score_details, variant_group_id, prompt_preset_id, language, source_listing_id, unsupported_claims, fact_check_details FROM listings WHERE property_id = ? AND deleted_at IS NULL ORDER BY created_at DESC"
     )
     .bind(property_id)
     .fetch_all(pool)
@@ -93,6 +120,36 @@ pub async fn list_by_property(
     Ok(rows.iter().map(listing_from_row).collect())
 }
 
+/// Listings generated together as variants, in the order they were saved
+pub async fn list_variant_group(
+    pool: &SqlitePool,
+    group_id: &str,
+) -> Result<Vec<Listing>, AppError> {
+    let rows = sqlx::query(
+        "SELECT id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents, is_favorite, created_at, edited_at, voice_score, voice_score_details, variant_group_id, prompt_preset_id, language, source_listing_id, unsupported_claims, fact_check_details FROM listings WHERE variant_group_id = ? AND deleted_at IS NULL ORDER BY created_at, rowid"
+    )
+    .bind(group_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(listing_from_row).collect())
+}
+
+/// Translations of a listing, in the order they were saved
+pub async fn list_translations(
+    pool: &SqlitePool,
+    source_listing_id: &str,
+) -> Result<Vec<Listing>, AppError> {
+    let rows = sqlx::query(
+        "SELECT id, property_id, content, generation_type, style, tone, length, seo_keywords, brand_voice_id, tokens_used, generation_cost_cents, is_favorite, created_at, edited_at, voice_score, voice_score_details, variant_group_id, prompt_preset_id, language, source_listing_id, unsupported_claims, fact_check_details FROM listings WHERE source_listing_id = ? AND deleted_at IS NULL ORDER BY created_at, rowid"
+    )
+    .bind(source_listing_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(listing_from_row).collect())
+}
+
 fn listing_from_row(row: &sqlx::sqlite::SqliteRow) -> Listing {
     Listing {
         id: row.get("id"),
@@ -108,6 +165,15 @@ fn listing_from_row(row: &sqlx::sqlite::SqliteRow) -> Listing {
         generation_cost_cents: row.get("generation_cost_cents"),
         is_favorite: row.get::<i32, _>("is_favorite") != 0,
         created_at: row.get("created_at"),
+        edited_at: row.get("edited_at"),
+        voice_score: row.get("voice_score"),
+        voice_score_details: row.get("voice_score_details"),
+        variant_group_id: row.get("variant_group_id"),
+        prompt_preset_id: row.get("prompt_preset_id"),
+        language: row.get("language"),
+        source_listing_id: row.get("source_listing_id"),
+        unsupported_claims: row.get("unsupported_claims"),
+        fact_check_details: row.get("fact_check_details"),
     }
 }
 
@@ -119,8 +185,87 @@ pub async fn toggle_favorite(pool: &SqlitePool, id: &str) -> Result<(), AppError
     Ok(())
 }
 
+/// Replace a listing's copy with the agent's edited version
+pub async fn update_content(
+    pool: &SqlitePool,
+    id: &str,
+    content: &str,
+) -> Result<Listing, AppError> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(AppError::Validation(
+            "Listing content cannot be empty".to_string(),
+        ));
+    }
+
+    sqlx::query(
+        "UPDATE listings SET content = ?, edited_at = datetime('now'), voice_score = NULL, voice_score_details = NULL, unsupported_claims = NULL, fact_check_details = NULL WHERE id = ? AND deleted_at IS NULL AND content != ?",
+    )
+    .bind(content)
+    .bind(id)
+    .bind(content)
+    .execute(pool)
+    .await?;
+
+    get(pool, id).await
+}
+
+pub async fn save_voice_score(
+    pool: &SqlitePool,
+    id: &str,
+    score: f64,
+    details: &str,
+) -> Result<Listing, AppError> {
+    sqlx::query("UPDATE listings SET voice_score = ?, voice_score_details = ? WHERE id = ?")
+        .bind(score)
+        .bind(details)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    get(pool, id).await
+}
+
+pub async fn save_fact_check(
+    pool: &SqlitePool,
+    id: &str,
+    unsupported_claims: i64,
+    details: &str,
+) -> Result<Listing, AppError> {
+    sqlx::query("UPDATE listings SET unsupported_claims = ?, fact_check_details = ? WHERE id = ?")
+        .bind(unsupported_claims)
+        .bind(details)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    get(pool, id).await
+}
+
+/// Listing descriptions the agent has favorited or edited, newest first:
+/// copy they approve of, used to train brand voices. Social posts, emails,
+/// translations and listings of trashed properties are left out. Limited to one voice's
+/// output when `brand_voice_id` is given.
+pub async fn list_voice_feedback(
+    pool: &SqlitePool,
+    brand_voice_id: Option<&str>,
+) -> Result<Vec<Listing>, AppError> {
+    let rows = sqlx::query(
+        "SELECT l.id, l.property_id, l.content, l.generation_type, l.style, l.tone, l.length, l.seo_keywords, l.brand_voice_id, l.tokens_used, l.generation_cost_cents, l.is_favorite, l.created_at, l.edited_at, l.voice_score, l.voice_score_details, l.variant_group_id, l.prompt_preset_id, l.language, l.source_listing_id, l.unsupported_claims, l.fact_check_details FROM listings l
+         JOIN properties p ON p.id = l.property_id AND p.deleted_at IS NULL
+         WHERE l.deleted_at IS NULL AND l.generation_type = 'listing' AND l.source_listing_id IS NULL AND (l.is_favorite = 1 OR l.edited_at IS NOT NULL) AND (?1 IS NULL OR l.brand_voice_id = ?1)
+         ORDER BY COALESCE(l.edited_at, l.created_at) DESC",
+    )
+    .bind(brand_voice_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(listing_from_row).collect())
+}
+
+/// Move a listing to the trash
 pub async fn delete(pool: &SqlitePool, id: &str) -> Result<(), AppError> {
-    sqlx::query("DELETE FROM listings WHERE id = ?")
+    sqlx::query("UPDATE listings SET deleted_at = datetime('now') WHERE id = ? AND deleted_at IS NULL")
         .bind(id)
         .execute(pool)
         .await?;
@@ -152,6 +297,8 @@ mod tests {
             school_district: None,
             nearby_amenities: vec![],
             agent_notes: None,
+            agent_id: None,
+            status: None,
         };
         let property = properties::create(pool, input).await.unwrap();
         property.id
@@ -175,6 +322,10 @@ mod tests {
                 brand_voice_id: None,
                 tokens_used: 500,
                 generation_cost_cents: 1,
+                variant_group_id: None,
+                prompt_preset_id: None,
+                language: "en".to_string(),
+                source_listing_id: None,
             },
         )
         .await
@@ -206,6 +357,10 @@ mod tests {
                     brand_voice_id: None,
                     tokens_used: 100,
                     generation_cost_cents: 1,
+                    variant_group_id: None,
+                    prompt_preset_id: None,
+                    language: "en".to_string(),
+                    source_listing_id: None,
                 },
             )
             .await
@@ -234,6 +389,10 @@ mod tests {
                 brand_voice_id: None,
                 tokens_used: 100,
                 generation_cost_cents: 1,
+                variant_group_id: None,
+                prompt_preset_id: None,
+                language: "en".to_string(),
+                source_listing_id: None,
             },
         )
         .await
@@ -249,4 +408,200 @@ mod tests {
         let fetched = get(&pool, &listing.id).await.unwrap();
         assert!(!fetched.is_favorite);
     }
+
+    #[tokio::test]
+    async fn test_generation_type_check() {
+        let pool = test_pool().await;
+        let property_id = create_test_property(&pool).await;
+
+        let input = |generation_type: &str| CreateListingInput {
+            property_id: property_id.clone(),
+            content: "New price!".to_string(),
+            generation_type: generation_type.to_string(),
+            style: None,
+            tone: None,
+            length: None,
+            seo_keywords: vec![],
+            brand_voice_id: None,
+            tokens_used: 100,
+            generation_cost_cents: 1,
+            variant_group_id: None,
+            prompt_preset_id: None,
+            language: "en".to_string(),
+            source_listing_id: None,
+        };
+
+        assert!(save(&pool, input("email_price_improvement")).await.is_ok());
+        assert!(save(&pool, input("social_price_improvement")).await.is_ok());
+        assert!(save(&pool, input("social_custom")).await.is_ok());
+        assert!(save(&pool, input("email_custom")).await.is_ok());
+        for kind in crate::ai::long_form::LongFormKind::ALL {
+            assert!(save(&pool, input(kind.generation_type())).await.is_ok());
+        }
+        assert!(save(&pool, input("email_newsletter")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_edits_and_favorites_are_voice_feedback() {
+        let pool = test_pool().await;
+        let property_id = create_test_property(&pool).await;
+
+        let input = |content: &str| CreateListingInput {
+            property_id: property_id.clone(),
+            content: content.to_string(),
+            generation_type: "listing".to_string(),
+            style: None,
+            tone: None,
+            length: None,
+            seo_keywords: vec![],
+            brand_voice_id: None,
+            tokens_used: 100,
+            generation_cost_cents: 1,
+            variant_group_id: None,
+            prompt_preset_id: None,
+            language: "en".to_string(),
+            source_listing_id: None,
+        };
+        let plain = save(&pool, input("Plain")).await.unwrap();
+        let favorite = save(&pool, input("Liked")).await.unwrap();
+        let edited = save(&pool, input("Draft")).await.unwrap();
+        toggle_favorite(&pool, &favorite.id).await.unwrap();
+
+        // Saving unchanged copy isn't an edit
+        let unchanged = update_content(&pool, &plain.id, "Plain").await.unwrap();
+        assert_eq!(unchanged.edited_at, None);
+
+        let scored = save_voice_score(&pool, &edited.id, 72.5, "{}").await.unwrap();
+        assert_eq!(scored.voice_score, Some(72.5));
+        let checked = save_fact_check(&pool, &edited.id, 2, "{}").await.unwrap();
+        assert_eq!(checked.unsupported_claims, Some(2));
+
+        // Editing makes the voice score and fact check stale
+        let updated = update_content(&pool, &edited.id, " Rewritten ")
+            .await
+            .unwrap();
+        assert_eq!(updated.content, "Rewritten");
+        assert_eq!(updated.voice_score, None);
+        assert_eq!(updated.unsupported_claims, None);
+        assert!(updated.edited_at.is_some());
+        assert!(update_content(&pool, &edited.id, "  ").await.is_err());
+
+        let feedback = list_voice_feedback(&pool, None).await.unwrap();
+        let mut ids: Vec<&str> = feedback.iter().map(|l| l.id.as_str()).collect();
+        ids.sort();
+        let mut expected = vec![favorite.id.as_str(), edited.id.as_str()];
+        expected.sort();
+        assert_eq!(ids, expected);
+        assert!(list_voice_feedback(&pool, Some("voice1"))
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Favorited social posts, translations and listings of trashed
+        // properties don't count
+        let social = save(
+            &pool,
+            CreateListingInput {
+                generation_type: "social_instagram".to_string(),
+                ..input("Just listed!")
+            },
+        )
+        .await
+        .unwrap();
+        toggle_favorite(&pool, &social.id).await.unwrap();
+        let translation = save(
+            &pool,
+            CreateListingInput {
+                language: "es".to_string(),
+                source_listing_id: Some(favorite.id.clone()),
+                ..input("Me gusta")
+            },
+        )
+        .await
+        .unwrap();
+        toggle_favorite(&pool, &translation.id).await.unwrap();
+        assert_eq!(list_voice_feedback(&pool, None).await.unwrap().len(), 2);
+        properties::delete(&pool, &property_id).await.unwrap();
+        assert!(list_voice_feedback(&pool, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_variant_group() {
+        let pool = test_pool().await;
+        let property_id = create_test_property(&pool).await;
+
+        let input = |content: &str, group: Option<&str>| CreateListingInput {
+            property_id: property_id.clone(),
+            content: content.to_string(),
+            generation_type: "listing".to_string(),
+            style: None,
+            tone: None,
+            length: None,
+            seo_keywords: vec![],
+            brand_voice_id: None,
+            tokens_used: 100,
+            generation_cost_cents: 1,
+            variant_group_id: group.map(String::from),
+            prompt_preset_id: None,
+            language: "en".to_string(),
+            source_listing_id: None,
+        };
+        let first = save(&pool, input("First", Some("group1"))).await.unwrap();
+        let second = save(&pool, input("Second", Some("group1"))).await.unwrap();
+        save(&pool, input("Other", Some("group2"))).await.unwrap();
+        save(&pool, input("Single", None)).await.unwrap();
+        assert_eq!(first.variant_group_id.as_deref(), Some("group1"));
+
+        let group = list_variant_group(&pool, "group1").await.unwrap();
+        let ids: Vec<&str> = group.iter().map(|l| l.id.as_str()).collect();
+        assert_eq!(ids, vec![first.id.as_str(), second.id.as_str()]);
+
+        delete(&pool, &second.id).await.unwrap();
+        assert_eq!(list_variant_group(&pool, "group1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_translations() {
+        let pool = test_pool().await;
+        let property_id = create_test_property(&pool).await;
+
+        let input = |content: &str, language: &str, source: Option<&str>| CreateListingInput {
+            property_id: property_id.clone(),
+            content: content.to_string(),
+            generation_type: "listing".to_string(),
+            style: None,
+            tone: None,
+            length: None,
+            seo_keywords: vec![],
+            brand_voice_id: None,
+            tokens_used: 100,
+            generation_cost_cents: 1,
+            variant_group_id: None,
+            prompt_preset_id: None,
+            language: language.to_string(),
+            source_listing_id: source.map(String::from),
+        };
+        let original = save(&pool, input("Sunny home", "en", None)).await.unwrap();
+        assert_eq!(original.language, "en");
+        let spanish = save(&pool, input("Casa soleada", "es", Some(&original.id)))
+            .await
+            .unwrap();
+        let vietnamese = save(&pool, input("Nhà đầy nắng", "vi", Some(&original.id)))
+            .await
+            .unwrap();
+        assert_eq!(
+            spanish.source_listing_id.as_deref(),
+            Some(original.id.as_str())
+        );
+
+        let translations = list_translations(&pool, &original.id).await.unwrap();
+        let languages: Vec<&str> = translations.iter().map(|l| l.language.as_str()).collect();
+        assert_eq!(languages, vec!["es", "vi"]);
+
+        delete(&pool, &vietnamese.id).await.unwrap();
+        assert_eq!(
+            list_translations(&pool, &original.id).await.unwrap().len(),
+            1
+        );
+    }
 }
@@ -1,9 +1,14 @@
+pub mod agents;
 pub mod analytics;
 pub mod brand_voice;
 pub mod listings;
 pub mod photos;
+pub mod price_history;
+pub mod prompt_presets;
 pub mod properties;
+pub mod search;
 pub mod settings;
+pub mod trash;
 
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use sqlx::SqlitePool;
@@ -46,3 +51,140 @@ pub async fn test_pool() -> SqlitePool {
 
     pool
 }
+
+/// A bare active property for tests; override fields with struct update syntax
+#[cfg(test)]
+pub fn test_property() -> properties::Property {
+    properties::Property {
+        id: "prop1".to_string(),
+        address: "123 Oak Street".to_string(),
+        city: "San Francisco".to_string(),
+        state: "CA".to_string(),
+        zip: "94105".to_string(),
+        beds: 3,
+        baths: 2.5,
+        sqft: 1800,
+        price: 95000000,
+        property_type: "single_family".to_string(),
+        year_built: None,
+        lot_size: None,
+        parking: None,
+        key_features: "[]".to_string(),
+        neighborhood: None,
+        neighborhood_highlights: "[]".to_string(),
+        school_district: None,
+        nearby_amenities: "[]".to_string(),
+        agent_notes: None,
+        agent_id: None,
+        status: "active".to_string(),
+        listed_at: None,
+        pending_at: None,
+        sold_at: None,
+        sold_price: None,
+        off_market_at: None,
+        days_on_market: None,
+        created_at: String::new(),
+        updated_at: String::new(),
+    }
+}
+
+/// The input that creates a property like `test_property()`, for tests that
+/// go through `properties::create`
+#[cfg(test)]
+pub fn test_property_input() -> properties::CreatePropertyInput {
+    let property = test_property();
+    properties::CreatePropertyInput {
+        address: property.address,
+        city: property.city,
+        state: property.state,
+        zip: property.zip,
+        beds: property.beds,
+        baths: property.baths,
+        sqft: property.sqft,
+        price: property.price,
+        property_type: property.property_type,
+        year_built: property.year_built,
+        lot_size: property.lot_size,
+        parking: property.parking,
+        key_features: vec![],
+        neighborhood: property.neighborhood,
+        neighborhood_highlights: vec![],
+        school_district: property.school_district,
+        nearby_amenities: vec![],
+        agent_notes: property.agent_notes,
+        agent_id: None,
+        status: None,
+    }
+}
+
+/// An unanalyzed photo of `test_property()` for tests
+#[cfg(test)]
+pub fn test_photo() -> photos::Photo {
+    photos::Photo {
+        id: "photo1".to_string(),
+        property_id: "prop1".to_string(),
+        filename: "photo1.jpg".to_string(),
+        original_path: String::new(),
+        thumbnail_path: String::new(),
+        sort_order: 0,
+        caption: None,
+        alt_text: None,
+        room: None,
+        detected_features: "[]".to_string(),
+        quality_score: None,
+        analyzed_at: None,
+        hero_score: None,
+        created_at: String::new(),
+    }
+}
+
+/// An empty English listing description of `test_property()` for tests
+#[cfg(test)]
+pub fn test_listing() -> listings::Listing {
+    listings::Listing {
+        id: "listing1".to_string(),
+        property_id: "prop1".to_string(),
+        content: String::new(),
+        generation_type: "listing".to_string(),
+        style: None,
+        tone: None,
+        length: None,
+        seo_keywords: "[]".to_string(),
+        brand_voice_id: None,
+        tokens_used: 0,
+        generation_cost_cents: 0,
+        is_favorite: false,
+        created_at: String::new(),
+        edited_at: None,
+        voice_score: None,
+        voice_score_details: None,
+        variant_group_id: None,
+        prompt_preset_id: None,
+        language: "en".to_string(),
+        source_listing_id: None,
+        unsupported_claims: None,
+        fact_check_details: None,
+    }
+}
+
+/// The input that saves a listing like `test_listing()` for `property_id`
+#[cfg(test)]
+pub fn test_listing_input(property_id: &str) -> listings::CreateListingInput {
+    let listing = test_listing();
+    listings::CreateListingInput {
+        property_id: property_id.to_string(),
+        content: listing.content,
+        generation_type: listing.generation_type,
+        style: listing.style,
+        tone: listing.tone,
+        length: listing.length,
+        seo_keywords: vec![],
+        brand_voice_id: listing.brand_voice_id,
+        tokens_used: listing.tokens_used,
+        generation_cost_cents: listing.generation_cost_cents,
+        variant_group_id: listing.variant_group_id,
+        prompt_preset_id: listing.prompt_preset_id,
+        language: listing.language,
+        source_listing_id: listing.source_listing_id,
+    }
+}
@@ -1,8 +1,40 @@
-use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize, Serializer};
 use sqlx::{FromRow, SqlitePool};
 
 use crate::error::AppError;
 
+/// Room tags, in the order MLS photo sets usually follow
+pub const ROOMS: &[&str] = &[
+    "exterior_front",
+    "living_room",
+    "family_room",
+    "kitchen",
+    "dining_room",
+    "primary_bedroom",
+    "bedroom",
+    "primary_bathroom",
+    "bathroom",
+    "office",
+    "laundry",
+    "garage",
+    "exterior_back",
+    "pool",
+    "view",
+    "community",
+    "floor_plan",
+    "other",
+];
+
+fn serialize_json_array<S>(value: &str, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let arr: Vec<String> = serde_json::from_str(value).unwrap_or_default();
+    arr.serialize(serializer)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct Photo {
@@ -13,9 +45,34 @@ pub struct Photo {
     pub thumbnail_path: String,
     pub sort_order: i64,
     pub caption: Option<String>,
+    /// Accessibility description of what the photo shows
+    pub alt_text: Option<String>,
+    /// Room or area shown, one of `ROOMS`
+    pub room: Option<String>,
+    /// Features spotted by vision analysis, as a JSON array
+    #[serde(serialize_with = "serialize_json_array")]
+    pub detected_features: String,
+    /// Photo quality from vision analysis, 1-10
+    pub quality_score: Option<i64>,
+    pub analyzed_at: Option<String>,
+    /// Measured image quality, 0-1; see `photos::quality`
+    pub hero_score: Option<f64>,
     pub created_at: String,
 }
 
+pub(crate) const COLUMNS: &str =
+    "id, property_id, filename, original_path, thumbnail_path, sort_order, caption, alt_text, room, detected_features, quality_score, analyzed_at, hero_score, created_at";
+
+/// Editable photo details. Empty strings clear a field.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePhotoInput {
+    pub caption: Option<String>,
+    pub alt_text: Option<String>,
+    pub room: Option<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn insert(
     pool: &SqlitePool,
     id: &str,
@@ -24,10 +81,11 @@ pub async fn insert(
     original_path: &str,
     thumbnail_path: &str,
     sort_order: i64,
+    content_hash: Option<&str>,
 ) -> Result<Photo, AppError> {
     sqlx::query(
-        "INSERT INTO photos (id, property_id, filename, original_path, thumbnail_path, sort_order)
-         VALUES (?, ?, ?, ?, ?, ?)",
+        "INSERT INTO photos (id, property_id, filename, original_path, thumbnail_path, sort_order, content_hash)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
     )
     .bind(id)
     .bind(property_id)
@@ -35,6 +93,7 @@ pub async fn insert(
     .bind(original_path)
     .bind(thumbnail_path)
     .bind(sort_order)
+    .bind(content_hash)
     .execute(pool)
     .await?;
 
@@ -45,10 +104,10 @@ pub async fn list_by_property(
     pool: &SqlitePool,
     property_id: &str,
 ) -> Result<Vec<Photo>, AppError> {
-    let photos = sqlx::query_as::<_, Photo>(
-        "SELECT id, property_id, filename, original_path, thumbnail_path, sort_order, caption, created_at
-         FROM photos WHERE property_id = ? ORDER BY sort_order ASC",
-    )
+    let photos = sqlx::query_as::<_, Photo>(&format!(
+        "SELECT {} FROM photos WHERE property_id = ? AND deleted_at IS NULL ORDER BY sort_order ASC",
+        COLUMNS
+    ))
     .bind(property_id)
     .fetch_all(pool)
     .await?;
@@ -56,11 +115,54 @@ pub async fn list_by_property(
     Ok(photos)
 }
 
-pub async fn get(pool: &SqlitePool, id: &str) -> Result<Photo, AppError> {
-    let photo = sqlx::query_as::<_, Photo>(
-        "SELECT id, property_id, filename, original_path, thumbnail_path, sort_order, caption, created_at
-         FROM photos WHERE id = ?",
+/// Every photo row, trashed ones included, for checking files on disk
+pub async fn list_all(pool: &SqlitePool) -> Result<Vec<Photo>, AppError> {
+    let photos = sqlx::query_as::<_, Photo>(&format!(
+        "SELECT {} FROM photos ORDER BY property_id, sort_order",
+        COLUMNS
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(photos)
+}
+
+/// Content hashes of a property's photos, for skipping re-imports. Trashed
+/// photos count too, since restoring one would bring the duplicate back.
+pub async fn content_hashes(
+    pool: &SqlitePool,
+    property_id: &str,
+) -> Result<HashSet<String>, AppError> {
+    let hashes: Vec<String> = sqlx::query_scalar(
+        "SELECT content_hash FROM photos WHERE property_id = ? AND content_hash IS NOT NULL",
     )
+    .bind(property_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(hashes.into_iter().collect())
+}
+
+/// Content hash of each of a property's photos that has one, by photo id
+pub async fn content_hashes_by_id(
+    pool: &SqlitePool,
+    property_id: &str,
+) -> Result<HashMap<String, String>, AppError> {
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        "SELECT id, content_hash FROM photos WHERE property_id = ? AND content_hash IS NOT NULL",
+    )
+    .bind(property_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+pub async fn get(pool: &SqlitePool, id: &str) -> Result<Photo, AppError> {
+    let photo = sqlx::query_as::<_, Photo>(&format!(
+        "SELECT {} FROM photos WHERE id = ? AND deleted_at IS NULL",
+        COLUMNS
+    ))
     .bind(id)
     .fetch_one(pool)
     .await?;
@@ -68,7 +170,19 @@ pub async fn get(pool: &SqlitePool, id: &str) -> Result<Photo, AppError> {
     Ok(photo)
 }
 
+/// Move a photo to the trash. The files stay on disk until the trash is
+/// purged.
 pub async fn delete(pool: &SqlitePool, id: &str) -> Result<(), AppError> {
+    sqlx::query("UPDATE photos SET deleted_at = datetime('now') WHERE id = ? AND deleted_at IS NULL")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Remove a photo row outright, bypassing the trash. Used when its original
+/// file is gone and the row can't be shown or restored.
+pub async fn delete_permanently(pool: &SqlitePool, id: &str) -> Result<(), AppError> {
     sqlx::query("DELETE FROM photos WHERE id = ?")
         .bind(id)
         .execute(pool)
@@ -89,6 +203,73 @@ pub async fn update_sort_order(
     Ok(())
 }
 
+/// Update a photo's caption, alt text and room tag. Fields left as `None`
+/// are unchanged; empty strings clear them.
+pub async fn update_details(
+    pool: &SqlitePool,
+    id: &str,
+    input: &UpdatePhotoInput,
+) -> Result<Photo, AppError> {
+    let room = input.room.as_deref().map(|r| r.trim().to_lowercase());
+    if let Some(ref room) = room {
+        if !room.is_empty() && !ROOMS.contains(&room.as_str()) {
+            return Err(AppError::Validation(format!("Unknown room '{}'", room)));
+        }
+    }
+
+    let result = sqlx::query(
+        "UPDATE photos SET
+            caption = CASE WHEN ?1 IS NULL THEN caption ELSE NULLIF(trim(?1), '') END,
+            alt_text = CASE WHEN ?2 IS NULL THEN alt_text ELSE NULLIF(trim(?2), '') END,
+            room = CASE WHEN ?3 IS NULL THEN room ELSE NULLIF(?3, '') END
+         WHERE id = ?4 AND deleted_at IS NULL",
+    )
+    .bind(&input.caption)
+    .bind(&input.alt_text)
+    .bind(&room)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::Validation(format!("Photo {} not found", id)));
+    }
+
+    get(pool, id).await
+}
+
+/// Store the vision analysis of a photo. A room the agent already tagged is
+/// kept. The cached hero score is cleared so the next arrange re-measures
+/// the photo alongside its new quality score.
+pub async fn save_analysis(
+    pool: &SqlitePool,
+    id: &str,
+    room: &str,
+    detected_features: &[String],
+    quality_score: i64,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE photos SET room = COALESCE(room, ?), detected_features = ?, quality_score = ?, analyzed_at = datetime('now'), hero_score = NULL
+         WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(room)
+    .bind(serde_json::to_string(detected_features)?)
+    .bind(quality_score)
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn save_hero_score(pool: &SqlitePool, id: &str, hero_score: f64) -> Result<(), AppError> {
+    sqlx::query("UPDATE photos SET hero_score = ? WHERE id = ?")
+        .bind(hero_score)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,6 +296,7 @@ mod tests {
             "/path/to/original.jpg",
             "/path/to/thumb.jpg",
             0,
+            Some("abc123"),
         )
         .await
         .unwrap();
@@ -125,6 +307,44 @@ mod tests {
 
         let fetched = get(&pool, "photo1").await.unwrap();
         assert_eq!(fetched.id, photo.id);
+
+        let hashes = content_hashes(&pool, "prop1").await.unwrap();
+        assert!(hashes.contains("abc123"));
+    }
+
+    #[tokio::test]
+    async fn test_save_analysis_clears_hero_score() {
+        let pool = test_pool().await;
+
+        sqlx::query(
+            "INSERT INTO properties (id, address, city, state, zip, beds, baths, sqft, price, property_type, key_features, neighborhood_highlights, nearby_amenities)
+             VALUES ('prop1', '123 Main', 'City', 'ST', '12345', 3, 2.0, 1500, 50000000, 'single_family', '[]', '[]', '[]')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        insert(&pool, "p1", "prop1", "a.jpg", "/a", "/ta", 0, None)
+            .await
+            .unwrap();
+        save_hero_score(&pool, "p1", 0.8).await.unwrap();
+        save_analysis(&pool, "p1", "kitchen", &["island".to_string()], 7)
+            .await
+            .unwrap();
+
+        let photo = get(&pool, "p1").await.unwrap();
+        assert_eq!(photo.quality_score, Some(7));
+        assert!(photo.hero_score.is_none());
+
+        // A trashed photo keeps whatever it had
+        delete(&pool, "p1").await.unwrap();
+        save_analysis(&pool, "p1", "kitchen", &[], 2).await.unwrap();
+        let quality: Option<i64> =
+            sqlx::query_scalar("SELECT quality_score FROM photos WHERE id = 'p1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(quality, Some(7));
     }
 
     #[tokio::test]
@@ -139,8 +359,12 @@ mod tests {
         .await
         .unwrap();
 
-        insert(&pool, "p1", "prop1", "a.jpg", "/a", "/ta", 1).await.unwrap();
-        insert(&pool, "p2", "prop1", "b.jpg", "/b", "/tb", 0).await.unwrap();
+        insert(&pool, "p1", "prop1", "a.jpg", "/a", "/ta", 1, None)
+            .await
+            .unwrap();
+        insert(&pool, "p2", "prop1", "b.jpg", "/b", "/tb", 0, None)
+            .await
+            .unwrap();
 
         let photos = list_by_property(&pool, "prop1").await.unwrap();
         assert_eq!(photos.len(), 2);
@@ -161,7 +385,9 @@ mod tests {
         .await
         .unwrap();
 
-        insert(&pool, "p1", "prop1", "a.jpg", "/a", "/ta", 0).await.unwrap();
+        insert(&pool, "p1", "prop1", "a.jpg", "/a", "/ta", 0, None)
+            .await
+            .unwrap();
         delete(&pool, "p1").await.unwrap();
 
         let result = get(&pool, "p1").await;
@@ -180,10 +406,72 @@ mod tests {
         .await
         .unwrap();
 
-        insert(&pool, "p1", "prop1", "a.jpg", "/a", "/ta", 0).await.unwrap();
+        insert(&pool, "p1", "prop1", "a.jpg", "/a", "/ta", 0, None)
+            .await
+            .unwrap();
         update_sort_order(&pool, "p1", 5).await.unwrap();
 
         let photo = get(&pool, "p1").await.unwrap();
         assert_eq!(photo.sort_order, 5);
     }
+
+    #[tokio::test]
+    async fn test_update_details() {
+        let pool = test_pool().await;
+
+        sqlx::query(
+            "INSERT INTO properties (id, address, city, state, zip, beds, baths, sqft, price, property_type, key_features, neighborhood_highlights, nearby_amenities)
+             VALUES ('prop1', '123 Main', 'City', 'ST', '12345', 3, 2.0, 1500, 50000000, 'single_family', '[]', '[]', '[]')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        insert(&pool, "p1", "prop1", "a.jpg", "/a", "/ta", 0, None)
+            .await
+            .unwrap();
+
+        let photo = update_details(
+            &pool,
+            "p1",
+            &UpdatePhotoInput {
+                caption: Some("Sun-filled kitchen".to_string()),
+                alt_text: Some("Kitchen with white cabinets and an island".to_string()),
+                room: Some(" Kitchen ".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(photo.caption.as_deref(), Some("Sun-filled kitchen"));
+        assert_eq!(photo.room.as_deref(), Some("kitchen"));
+
+        // Unset fields are kept, empty strings clear
+        let photo = update_details(
+            &pool,
+            "p1",
+            &UpdatePhotoInput {
+                caption: Some(String::new()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(photo.caption, None);
+        assert!(photo.alt_text.is_some());
+        assert_eq!(photo.room.as_deref(), Some("kitchen"));
+
+        assert!(update_details(
+            &pool,
+            "p1",
+            &UpdatePhotoInput {
+                room: Some("ballroom".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .is_err());
+        assert!(update_details(&pool, "missing", &UpdatePhotoInput::default())
+            .await
+            .is_err());
+    }
 }
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::error::AppError;
+
+/// A change to a property's asking price. Rows are written by the
+/// `properties_price_history` trigger whenever `properties.price` changes.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceChange {
+    pub id: String,
+    pub property_id: String,
+    pub old_price: i64,
+    pub new_price: i64,
+    pub changed_at: String,
+}
+
+impl PriceChange {
+    /// Reduction in cents; negative for a price increase
+    pub fn reduction(&self) -> i64 {
+        self.old_price - self.new_price
+    }
+
+    pub fn reduction_percent(&self) -> f64 {
+        if self.old_price == 0 {
+            return 0.0;
+        }
+        self.reduction() as f64 / self.old_price as f64 * 100.0
+    }
+}
+
+pub async fn list_by_property(
+    pool: &SqlitePool,
+    property_id: &str,
+) -> Result<Vec<PriceChange>, AppError> {
+    let changes = sqlx::query_as::<_, PriceChange>(
+        "SELECT id, property_id, old_price, new_price, changed_at FROM price_history WHERE property_id = ? ORDER BY changed_at ASC, rowid ASC",
+    )
+    .bind(property_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(changes)
+}
+
+pub async fn latest(pool: &SqlitePool, property_id: &str) -> Result<Option<PriceChange>, AppError> {
+    let change = sqlx::query_as::<_, PriceChange>(
+        "SELECT id, property_id, old_price, new_price, changed_at FROM price_history WHERE property_id = ? ORDER BY changed_at DESC, rowid DESC LIMIT 1",
+    )
+    .bind(property_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(change)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::properties::{self, CreatePropertyInput};
+    use crate::db::test_pool;
+
+    fn sample_input(price: i64) -> CreatePropertyInput {
+        CreatePropertyInput {
+            price,
+            key_features: vec!["hardwood floors".to_string()],
+            ..crate::db::test_property_input()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_price_changes_are_recorded() {
+        let pool = test_pool().await;
+        let property = properties::create(&pool, sample_input(100000000))
+            .await
+            .unwrap();
+        assert!(latest(&pool, &property.id).await.unwrap().is_none());
+
+        // Editing other fields doesn't record anything
+        let mut input = sample_input(100000000);
+        input.beds = 4;
+        properties::update(&pool, &property.id, input)
+            .await
+            .unwrap();
+        assert!(list_by_property(&pool, &property.id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        properties::update(&pool, &property.id, sample_input(95000000))
+            .await
+            .unwrap();
+        properties::update(&pool, &property.id, sample_input(92500000))
+            .await
+            .unwrap();
+
+        let history = list_by_property(&pool, &property.id).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].old_price, 100000000);
+        assert_eq!(history[0].new_price, 95000000);
+
+        let last = latest(&pool, &property.id).await.unwrap().unwrap();
+        assert_eq!(last.new_price, 92500000);
+        assert_eq!(last.reduction(), 2500000);
+        assert!((last.reduction_percent() - 2.63).abs() < 0.01);
+    }
+}
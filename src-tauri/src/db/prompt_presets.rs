@@ -0,0 +1,352 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::ai::prompts::{
+    unknown_preset_variables, EMAIL_TEMPLATES, LENGTHS, PLATFORMS, PRESET_VARIABLES, STYLES, TONES,
+};
+use crate::error::AppError;
+
+const MAX_NAME_LEN: usize = 60;
+
+/// What a preset stands in for in the prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresetKind {
+    Style,
+    Tone,
+    Length,
+    Platform,
+    EmailTemplate,
+}
+
+impl PresetKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PresetKind::Style => "style",
+            PresetKind::Tone => "tone",
+            PresetKind::Length => "length",
+            PresetKind::Platform => "platform",
+            PresetKind::EmailTemplate => "email_template",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            PresetKind::EmailTemplate => "email template",
+            kind => kind.as_str(),
+        }
+    }
+
+    pub fn builtin_keys(self) -> &'static [&'static str] {
+        match self {
+            PresetKind::Style => STYLES,
+            PresetKind::Tone => TONES,
+            PresetKind::Length => LENGTHS,
+            PresetKind::Platform => PLATFORMS,
+            PresetKind::EmailTemplate => EMAIL_TEMPLATES,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptPreset {
+    pub id: String,
+    pub kind: String,
+    /// Stored on listings in place of a built-in style, tone or length; set
+    /// from the name when the preset is created and never changed
+    pub key: String,
+    pub name: String,
+    /// Prompt text; may use `{{variables}}` from `PRESET_VARIABLES`
+    pub instructions: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptPresetInput {
+    pub kind: PresetKind,
+    pub name: String,
+    pub instructions: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePromptPresetInput {
+    pub name: String,
+    pub instructions: String,
+}
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, kind, key, name, instructions, created_at, updated_at FROM prompt_presets";
+
+/// Check a preset's name and instructions, including that every variable
+/// it uses exists
+pub fn validate(name: &str, instructions: &str) -> Result<(), AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("Preset name is required".to_string()));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::Validation(format!(
+            "Preset name must be {} characters or fewer",
+            MAX_NAME_LEN
+        )));
+    }
+    if instructions.trim().is_empty() {
+        return Err(AppError::Validation(
+            "Preset instructions are required".to_string(),
+        ));
+    }
+    let unknown = unknown_preset_variables(instructions);
+    if !unknown.is_empty() {
+        return Err(AppError::Validation(format!(
+            "Unknown variables: {}. Available variables: {}",
+            unknown
+                .iter()
+                .map(|v| format!("{{{{{}}}}}", v))
+                .collect::<Vec<_>>()
+                .join(", "),
+            PRESET_VARIABLES.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Lowercase words joined by underscores, like the built-in keys
+fn key_from_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+pub async fn create(pool: &SqlitePool, input: PromptPresetInput) -> Result<PromptPreset, AppError> {
+    validate(&input.name, &input.instructions)?;
+
+    let key = key_from_name(&input.name);
+    if key.is_empty() {
+        return Err(AppError::Validation(
+            "Preset name must contain letters or numbers".to_string(),
+        ));
+    }
+    if input.kind.builtin_keys().contains(&key.as_str())
+        || get_by_key(pool, input.kind, &key).await?.is_some()
+    {
+        return Err(AppError::Validation(format!(
+            "A {} named '{}' already exists",
+            input.kind.label(),
+            input.name.trim()
+        )));
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO prompt_presets (id, kind, key, name, instructions) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(input.kind.as_str())
+    .bind(&key)
+    .bind(input.name.trim())
+    .bind(input.instructions.trim())
+    .execute(pool)
+    .await?;
+
+    get(pool, &id).await
+}
+
+pub async fn get(pool: &SqlitePool, id: &str) -> Result<PromptPreset, AppError> {
+    sqlx::query_as::<_, PromptPreset>(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::PromptPresetNotFound(id.to_string()))
+}
+
+pub async fn get_by_key(
+    pool: &SqlitePool,
+    kind: PresetKind,
+    key: &str,
+) -> Result<Option<PromptPreset>, AppError> {
+    let preset = sqlx::query_as::<_, PromptPreset>(&format!(
+        "{} WHERE kind = ? AND key = ?",
+        SELECT_COLUMNS
+    ))
+    .bind(kind.as_str())
+    .bind(key)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(preset)
+}
+
+/// All presets, or one kind's, by name
+pub async fn list(
+    pool: &SqlitePool,
+    kind: Option<PresetKind>,
+) -> Result<Vec<PromptPreset>, AppError> {
+    let presets = sqlx::query_as::<_, PromptPreset>(&format!(
+        "{} WHERE (?1 IS NULL OR kind = ?1) ORDER BY kind, name COLLATE NOCASE",
+        SELECT_COLUMNS
+    ))
+    .bind(kind.map(PresetKind::as_str))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(presets)
+}
+
+pub async fn update(
+    pool: &SqlitePool,
+    id: &str,
+    input: UpdatePromptPresetInput,
+) -> Result<PromptPreset, AppError> {
+    validate(&input.name, &input.instructions)?;
+
+    let result = sqlx::query(
+        "UPDATE prompt_presets SET name = ?, instructions = ?, updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(input.name.trim())
+    .bind(input.instructions.trim())
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::PromptPresetNotFound(id.to_string()));
+    }
+
+    get(pool, id).await
+}
+
+/// Delete a preset. Listings written with it keep their copy and key
+/// (`ON DELETE SET NULL`).
+pub async fn delete(pool: &SqlitePool, id: &str) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM prompt_presets WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::PromptPresetNotFound(id.to_string()));
+    }
+    Ok(())
+}
+
+/// Look up the preset behind a style, tone, length, platform or email
+/// template key: `None` for built-in keys, an error for unknown ones
+pub async fn resolve(
+    pool: &SqlitePool,
+    kind: PresetKind,
+    key: &str,
+) -> Result<Option<PromptPreset>, AppError> {
+    if kind.builtin_keys().contains(&key) {
+        return Ok(None);
+    }
+    match get_by_key(pool, kind, key).await? {
+        Some(preset) => Ok(Some(preset)),
+        None => Err(AppError::Validation(format!(
+            "Unknown {} '{}'",
+            kind.label(),
+            key
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    fn input(kind: PresetKind, name: &str) -> PromptPresetInput {
+        PromptPresetInput {
+            kind,
+            name: name.to_string(),
+            instructions: "Write for buyers in {{city}}.".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_update_and_resolve() {
+        let pool = test_pool().await;
+        let preset = create(&pool, input(PresetKind::Style, " New Construction "))
+            .await
+            .unwrap();
+        assert_eq!(preset.key, "new_construction");
+        assert_eq!(preset.name, "New Construction");
+        assert_eq!(preset.kind, "style");
+
+        // The key is fixed at creation
+        let updated = update(
+            &pool,
+            &preset.id,
+            UpdatePromptPresetInput {
+                name: "New Build".to_string(),
+                instructions: "Stress warranties.".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.key, "new_construction");
+        assert_eq!(updated.instructions, "Stress warranties.");
+
+        assert!(resolve(&pool, PresetKind::Style, "luxury")
+            .await
+            .unwrap()
+            .is_none());
+        let resolved = resolve(&pool, PresetKind::Style, "new_construction")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resolved.id, preset.id);
+        // Keys are per kind
+        assert!(resolve(&pool, PresetKind::Tone, "new_construction")
+            .await
+            .is_err());
+
+        create(&pool, input(PresetKind::Platform, "TikTok script"))
+            .await
+            .unwrap();
+        assert_eq!(list(&pool, None).await.unwrap().len(), 2);
+        assert_eq!(
+            list(&pool, Some(PresetKind::Platform)).await.unwrap()[0].key,
+            "tiktok_script"
+        );
+
+        delete(&pool, &preset.id).await.unwrap();
+        assert!(matches!(
+            get(&pool, &preset.id).await,
+            Err(AppError::PromptPresetNotFound(_))
+        ));
+        assert!(matches!(
+            delete(&pool, &preset.id).await,
+            Err(AppError::PromptPresetNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_rejects_duplicates_and_bad_templates() {
+        let pool = test_pool().await;
+        assert!(create(&pool, input(PresetKind::Style, "Luxury"))
+            .await
+            .is_err());
+        create(&pool, input(PresetKind::EmailTemplate, "Just Listed"))
+            .await
+            .unwrap();
+        assert!(
+            create(&pool, input(PresetKind::EmailTemplate, "just-listed"))
+                .await
+                .is_err()
+        );
+        assert!(create(&pool, input(PresetKind::Style, "!!!"))
+            .await
+            .is_err());
+
+        let mut bad = input(PresetKind::Tone, "Breezy");
+        bad.instructions = "Mention {{pool_size}}".to_string();
+        let err = create(&pool, bad).await.unwrap_err().to_string();
+        assert!(err.contains("{{pool_size}}"));
+    }
+}
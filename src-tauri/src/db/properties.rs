@@ -1,8 +1,27 @@
 use serde::{Deserialize, Serialize, Serializer};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
 
 use crate::error::AppError;
 
+/// Statuses a property can be created in; the rest are reached through
+/// `set_status`
+pub const INITIAL_STATUSES: &[&str] = &["draft", "coming_soon", "active"];
+
+pub const STATUSES: &[&str] = &[
+    "draft",
+    "coming_soon",
+    "active",
+    "pending",
+    "sold",
+    "withdrawn",
+    "expired",
+];
+
+/// Property columns for `FROM properties p`, including the computed
+/// `days_on_market`. The clock runs from `listed_at` until the property goes
+/// pending, sells or comes off the market.
+pub(crate) const COLUMNS: &str = "p.id, p.address, p.city, p.state, p.zip, p.beds, p.baths, p.sqft, p.price, p.property_type, p.year_built, p.lot_size, p.parking, p.key_features, p.neighborhood, p.neighborhood_highlights, p.school_district, p.nearby_amenities, p.agent_notes, p.agent_id, p.status, p.listed_at, p.pending_at, p.sold_at, p.sold_price, p.off_market_at, CASE WHEN p.listed_at IS NULL THEN NULL ELSE MAX(0, CAST(julianday(COALESCE(p.pending_at, p.sold_at, p.off_market_at, datetime('now'))) - julianday(p.listed_at) AS INTEGER)) END AS days_on_market, p.created_at, p.updated_at";
+
 /// Serialize a JSON string column as a proper JSON array
 fn serialize_json_array<S>(value: &str, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -37,6 +56,14 @@ pub struct Property {
     #[serde(serialize_with = "serialize_json_array")]
     pub nearby_amenities: String,
     pub agent_notes: Option<String>,
+    pub agent_id: Option<String>,
+    pub status: String,
+    pub listed_at: Option<String>,
+    pub pending_at: Option<String>,
+    pub sold_at: Option<String>,
+    pub sold_price: Option<i64>,
+    pub off_market_at: Option<String>,
+    pub days_on_market: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -62,17 +89,70 @@ pub struct CreatePropertyInput {
     pub school_district: Option<String>,
     pub nearby_amenities: Vec<String>,
     pub agent_notes: Option<String>,
+    /// Absent keeps the property's current agent; `null` unassigns it
+    #[serde(default, deserialize_with = "present")]
+    pub agent_id: Option<Option<String>>,
+    /// Status a new property starts in, one of `INITIAL_STATUSES`; active
+    /// when not given. Edits change status through `set_status` instead.
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// Tell a field sent as `null` (`Some(None)`) apart from one left out
+/// (`None`, via `#[serde(default)]`)
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetStatusInput {
+    pub status: String,
+    /// Only used for `sold`; defaults to the asking price
+    pub sold_price: Option<i64>,
+    /// Date the change took effect (YYYY-MM-DD); defaults to now
+    pub effective_date: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyStatusEvent {
+    pub id: String,
+    pub property_id: String,
+    pub status: String,
+    pub price: i64,
+    pub note: Option<String>,
+    pub changed_at: String,
 }
 
 pub async fn create(pool: &SqlitePool, input: CreatePropertyInput) -> Result<Property, AppError> {
+    let status = input.status.as_deref().unwrap_or("active");
+    if !INITIAL_STATUSES.contains(&status) {
+        return Err(AppError::Validation(format!(
+            "A new property can't start as '{}'. Choose one of: {}",
+            status,
+            INITIAL_STATUSES.join(", ")
+        )));
+    }
+    // Days on market only start counting once the property goes active
+    let listed_at =
+        (status == "active").then(|| chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string());
+
     let id = uuid::Uuid::new_v4().to_string();
     let key_features = serde_json::to_string(&input.key_features)?;
     let neighborhood_highlights = serde_json::to_string(&input.neighborhood_highlights)?;
     let nearby_amenities = serde_json::to_string(&input.nearby_amenities)?;
 
+    let mut tx = pool.begin().await?;
+
     sqlx::query(
-        "INSERT INTO properties (id, address, city, state, zip, beds, baths, sqft, price, property_type, year_built, lot_size, parking, key_features, neighborhood, neighborhood_highlights, school_district, nearby_amenities, agent_notes)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        "INSERT INTO properties (id, address, city, state, zip, beds, baths, sqft, price, property_type, year_built, lot_size, parking, key_features, neighborhood, neighborhood_highlights, school_district, nearby_amenities, agent_notes, agent_id, status, listed_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(&id)
     .bind(&input.address)
@@ -93,16 +173,23 @@ pub async fn create(pool: &SqlitePool, input: CreatePropertyInput) -> Result<Pro
     .bind(&input.school_district)
     .bind(&nearby_amenities)
     .bind(&input.agent_notes)
-    .execute(pool)
+    .bind(input.agent_id.as_ref().and_then(Option::as_ref))
+    .bind(status)
+    .bind(&listed_at)
+    .execute(&mut *tx)
     .await?;
 
+    record_status(&mut tx, &id, status, input.price, None, None).await?;
+    tx.commit().await?;
+
     get(pool, &id).await
 }
 
 pub async fn get(pool: &SqlitePool, id: &str) -> Result<Property, AppError> {
-    let property = sqlx::query_as::<_, Property>(
-        "SELECT id, address, city, state, zip, beds, baths, sqft, price, property_type, year_built, lot_size, parking, key_features, neighborhood, neighborhood_highlights, school_district, nearby_amenities, agent_notes, created_at, updated_at FROM properties WHERE id = ?"
-    )
+    let property = sqlx::query_as::<_, Property>(&format!(
+        "SELECT {} FROM properties p WHERE p.id = ? AND p.deleted_at IS NULL",
+        COLUMNS
+    ))
     .bind(id)
     .fetch_one(pool)
     .await?;
@@ -111,9 +198,10 @@ pub async fn get(pool: &SqlitePool, id: &str) -> Result<Property, AppError> {
 }
 
 pub async fn list_all(pool: &SqlitePool) -> Result<Vec<Property>, AppError> {
-    let properties = sqlx::query_as::<_, Property>(
-        "SELECT id, address, city, state, zip, beds, baths, sqft, price, property_type, year_built, lot_size, parking, key_features, neighborhood, neighborhood_highlights, school_district, nearby_amenities, agent_notes, created_at, updated_at FROM properties ORDER BY created_at DESC"
-    )
+    let properties = sqlx::query_as::<_, Property>(&format!(
+        "SELECT {} FROM properties p WHERE p.deleted_at IS NULL ORDER BY p.created_at DESC",
+        COLUMNS
+    ))
     .fetch_all(pool)
     .await?;
 
@@ -130,7 +218,7 @@ pub async fn update(
     let nearby_amenities = serde_json::to_string(&input.nearby_amenities)?;
 
     sqlx::query(
-        "UPDATE properties SET address = ?, city = ?, state = ?, zip = ?, beds = ?, baths = ?, sqft = ?, price = ?, property_type = ?, year_built = ?, lot_size = ?, parking = ?, key_features = ?, neighborhood = ?, neighborhood_highlights = ?, school_district = ?, nearby_amenities = ?, agent_notes = ?, updated_at = datetime('now') WHERE id = ?"
+        "UPDATE properties SET address = ?, city = ?, state = ?, zip = ?, beds = ?, baths = ?, sqft = ?, price = ?, property_type = ?, year_built = ?, lot_size = ?, parking = ?, key_features = ?, neighborhood = ?, neighborhood_highlights = ?, school_district = ?, nearby_amenities = ?, agent_notes = ?, agent_id = CASE WHEN ? THEN ? ELSE agent_id END, updated_at = datetime('now') WHERE id = ? AND deleted_at IS NULL"
     )
     .bind(&input.address)
     .bind(&input.city)
@@ -150,6 +238,8 @@ pub async fn update(
     .bind(&input.school_district)
     .bind(&nearby_amenities)
     .bind(&input.agent_notes)
+    .bind(input.agent_id.is_some())
+    .bind(input.agent_id.as_ref().and_then(Option::as_ref))
     .bind(id)
     .execute(pool)
     .await?;
@@ -157,14 +247,190 @@ pub async fn update(
     get(pool, id).await
 }
 
+/// Append features to a property's `key_features`, skipping any already
+/// listed (case-insensitively)
+pub async fn add_key_features(
+    pool: &SqlitePool,
+    id: &str,
+    features: &[String],
+) -> Result<Property, AppError> {
+    let property = get(pool, id).await?;
+    let mut key_features: Vec<String> =
+        serde_json::from_str(&property.key_features).unwrap_or_default();
+
+    for feature in features.iter().map(|f| f.trim()).filter(|f| !f.is_empty()) {
+        if !key_features.iter().any(|k| k.eq_ignore_ascii_case(feature)) {
+            key_features.push(feature.to_string());
+        }
+    }
+
+    sqlx::query("UPDATE properties SET key_features = ?, updated_at = datetime('now') WHERE id = ?")
+        .bind(serde_json::to_string(&key_features)?)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    get(pool, id).await
+}
+
+/// Move a property to the trash. Its listings and photos stay attached and
+/// come back with it on restore; see `db::trash`.
 pub async fn delete(pool: &SqlitePool, id: &str) -> Result<(), AppError> {
-    sqlx::query("DELETE FROM properties WHERE id = ?")
+    sqlx::query("UPDATE properties SET deleted_at = datetime('now') WHERE id = ? AND deleted_at IS NULL")
         .bind(id)
         .execute(pool)
         .await?;
     Ok(())
 }
 
+/// Move a property to a new lifecycle status, updating the listing dates and
+/// recording the change in the status history
+pub async fn set_status(
+    pool: &SqlitePool,
+    id: &str,
+    input: SetStatusInput,
+) -> Result<Property, AppError> {
+    if !STATUSES.contains(&input.status.as_str()) {
+        return Err(AppError::Validation(format!(
+            "Unknown property status '{}'",
+            input.status
+        )));
+    }
+    if matches!(input.sold_price, Some(price) if price <= 0) {
+        return Err(AppError::Validation(
+            "Sold price must be greater than zero".to_string(),
+        ));
+    }
+
+    let changed_at = match input.effective_date.as_deref() {
+        Some(date) => chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map_err(|_| {
+                AppError::Validation(format!("Invalid date '{}', expected YYYY-MM-DD", date))
+            })?
+            .format("%Y-%m-%d 00:00:00")
+            .to_string(),
+        None => chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+
+    let property = get(pool, id)
+        .await
+        .map_err(|_| AppError::PropertyNotFound(id.to_string()))?;
+
+    let mut listed_at = property.listed_at;
+    let mut pending_at = property.pending_at;
+    let mut sold_at = property.sold_at;
+    let mut sold_price = property.sold_price;
+    let mut off_market_at = property.off_market_at;
+
+    match input.status.as_str() {
+        "active" => {
+            // Back on the market: a fallen-through deal keeps the original
+            // list date so days on market keep counting, while a relist after
+            // a withdrawal, expiry or sale starts a fresh count
+            if matches!(property.status.as_str(), "withdrawn" | "expired" | "sold") {
+                listed_at = Some(changed_at.clone());
+            } else {
+                listed_at.get_or_insert_with(|| changed_at.clone());
+            }
+            pending_at = None;
+            sold_at = None;
+            sold_price = None;
+            off_market_at = None;
+        }
+        "pending" => {
+            listed_at.get_or_insert_with(|| changed_at.clone());
+            pending_at = Some(changed_at.clone());
+            off_market_at = None;
+        }
+        "sold" => {
+            listed_at.get_or_insert_with(|| changed_at.clone());
+            sold_at = Some(changed_at.clone());
+            sold_price = Some(input.sold_price.unwrap_or(property.price));
+            off_market_at = None;
+        }
+        "withdrawn" | "expired" => {
+            off_market_at = Some(changed_at.clone());
+        }
+        "coming_soon" | "draft" => {
+            // Not on the market yet, so going live later starts the count
+            listed_at = None;
+        }
+        _ => {}
+    }
+
+    let event_price = if input.status == "sold" {
+        sold_price.unwrap_or(property.price)
+    } else {
+        property.price
+    };
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "UPDATE properties SET status = ?, listed_at = ?, pending_at = ?, sold_at = ?, sold_price = ?, off_market_at = ?, updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(&input.status)
+    .bind(&listed_at)
+    .bind(&pending_at)
+    .bind(&sold_at)
+    .bind(sold_price)
+    .bind(&off_market_at)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    record_status(
+        &mut tx,
+        id,
+        &input.status,
+        event_price,
+        input.note.as_deref(),
+        Some(&changed_at),
+    )
+    .await?;
+    tx.commit().await?;
+
+    get(pool, id).await
+}
+
+pub async fn list_status_history(
+    pool: &SqlitePool,
+    property_id: &str,
+) -> Result<Vec<PropertyStatusEvent>, AppError> {
+    let events = sqlx::query_as::<_, PropertyStatusEvent>(
+        "SELECT id, property_id, status, price, note, changed_at FROM property_status_history WHERE property_id = ? ORDER BY changed_at ASC, rowid ASC",
+    )
+    .bind(property_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events)
+}
+
+pub(crate) async fn record_status(
+    conn: &mut SqliteConnection,
+    property_id: &str,
+    status: &str,
+    price: i64,
+    note: Option<&str>,
+    changed_at: Option<&str>,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO property_status_history (id, property_id, status, price, note, changed_at)
+         VALUES (?, ?, ?, ?, ?, COALESCE(?, datetime('now')))",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(property_id)
+    .bind(status)
+    .bind(price)
+    .bind(note)
+    .bind(changed_at)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,6 +456,8 @@ mod tests {
             school_district: Some("SFUSD".to_string()),
             nearby_amenities: vec!["Whole Foods 0.3mi".to_string()],
             agent_notes: Some("Motivated seller".to_string()),
+            agent_id: None,
+            status: None,
         }
     }
 
@@ -207,6 +475,26 @@ mod tests {
         assert_eq!(fetched.id, property.id);
     }
 
+    #[tokio::test]
+    async fn test_add_key_features() {
+        let pool = test_pool().await;
+        let property = create(&pool, sample_input()).await.unwrap();
+
+        let updated = add_key_features(
+            &pool,
+            &property.id,
+            &["Hardwood Floors".to_string(), " vaulted ceilings ".to_string()],
+        )
+        .await
+        .unwrap();
+
+        let features: Vec<String> = serde_json::from_str(&updated.key_features).unwrap();
+        assert_eq!(
+            features,
+            vec!["hardwood floors", "chef's kitchen", "vaulted ceilings"]
+        );
+    }
+
     #[tokio::test]
     async fn test_list_all() {
         let pool = test_pool().await;
@@ -240,4 +528,215 @@ mod tests {
         let result = get(&pool, &property.id).await;
         assert!(result.is_err());
     }
+
+    fn status_input(status: &str, date: Option<&str>) -> SetStatusInput {
+        SetStatusInput {
+            status: status.to_string(),
+            sold_price: None,
+            effective_date: date.map(str::to_string),
+            note: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_new_property_is_active_and_listed() {
+        let pool = test_pool().await;
+        let property = create(&pool, sample_input()).await.unwrap();
+
+        assert_eq!(property.status, "active");
+        assert!(property.listed_at.is_some());
+        assert_eq!(property.days_on_market, Some(0));
+
+        let history = list_status_history(&pool, &property.id).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, "active");
+        assert_eq!(history[0].price, 95000000);
+    }
+
+    #[tokio::test]
+    async fn test_status_lifecycle_and_days_on_market() {
+        let pool = test_pool().await;
+        let property = create(&pool, sample_input()).await.unwrap();
+        // Backdate the listing so the pending and sold dates come after it
+        sqlx::query("UPDATE properties SET listed_at = '2024-03-01 00:00:00' WHERE id = ?")
+            .bind(&property.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "UPDATE property_status_history SET changed_at = '2024-03-01 00:00:00' WHERE property_id = ?",
+        )
+        .bind(&property.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let pending = set_status(
+            &pool,
+            &property.id,
+            status_input("pending", Some("2024-03-15")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(pending.status, "pending");
+        assert_eq!(pending.days_on_market, Some(14));
+
+        let mut sold_input = status_input("sold", Some("2024-04-20"));
+        sold_input.sold_price = Some(97500000);
+        sold_input.note = Some("Multiple offers".to_string());
+        let sold = set_status(&pool, &property.id, sold_input).await.unwrap();
+        assert_eq!(sold.status, "sold");
+        assert_eq!(sold.sold_price, Some(97500000));
+        assert_eq!(sold.sold_at.as_deref(), Some("2024-04-20 00:00:00"));
+        // Days on market stop at the pending date, not the close
+        assert_eq!(sold.days_on_market, Some(14));
+
+        let history = list_status_history(&pool, &property.id).await.unwrap();
+        let statuses: Vec<&str> = history.iter().map(|e| e.status.as_str()).collect();
+        assert_eq!(statuses, vec!["active", "pending", "sold"]);
+        assert_eq!(history[2].price, 97500000);
+        assert_eq!(history[2].note.as_deref(), Some("Multiple offers"));
+    }
+
+    #[tokio::test]
+    async fn test_back_to_active_clears_pending() {
+        let pool = test_pool().await;
+        let property = create(&pool, sample_input()).await.unwrap();
+
+        set_status(&pool, &property.id, status_input("pending", None))
+            .await
+            .unwrap();
+        let active = set_status(&pool, &property.id, status_input("active", None))
+            .await
+            .unwrap();
+
+        assert!(active.pending_at.is_none());
+        assert_eq!(active.listed_at, property.listed_at);
+    }
+
+    #[tokio::test]
+    async fn test_relist_restarts_days_on_market() {
+        let pool = test_pool().await;
+        let property = create(&pool, sample_input()).await.unwrap();
+        sqlx::query("UPDATE properties SET listed_at = '2024-03-01 00:00:00' WHERE id = ?")
+            .bind(&property.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        set_status(
+            &pool,
+            &property.id,
+            status_input("withdrawn", Some("2024-03-11")),
+        )
+        .await
+        .unwrap();
+        let relisted = set_status(
+            &pool,
+            &property.id,
+            status_input("active", Some("2024-05-01")),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(relisted.listed_at.as_deref(), Some("2024-05-01 00:00:00"));
+        assert!(relisted.off_market_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_relist_after_sale_restarts_days_on_market() {
+        let pool = test_pool().await;
+        let property = create(&pool, sample_input()).await.unwrap();
+
+        set_status(
+            &pool,
+            &property.id,
+            status_input("sold", Some("2024-03-11")),
+        )
+        .await
+        .unwrap();
+        let relisted = set_status(
+            &pool,
+            &property.id,
+            status_input("active", Some("2024-05-01")),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(relisted.listed_at.as_deref(), Some("2024-05-01 00:00:00"));
+        assert!(relisted.sold_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_back_to_pre_market_clears_list_date() {
+        let pool = test_pool().await;
+        let property = create(&pool, sample_input()).await.unwrap();
+        assert!(property.listed_at.is_some());
+
+        for status in ["coming_soon", "draft"] {
+            let updated = set_status(&pool, &property.id, status_input(status, None))
+                .await
+                .unwrap();
+            assert!(updated.listed_at.is_none(), "{}", status);
+            assert!(updated.days_on_market.is_none(), "{}", status);
+        }
+
+        let active = set_status(
+            &pool,
+            &property.id,
+            status_input("active", Some("2024-05-01")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(active.listed_at.as_deref(), Some("2024-05-01 00:00:00"));
+    }
+
+    #[tokio::test]
+    async fn test_create_with_initial_status() {
+        let pool = test_pool().await;
+        let mut input = sample_input();
+        input.status = Some("coming_soon".to_string());
+        let property = create(&pool, input).await.unwrap();
+
+        assert_eq!(property.status, "coming_soon");
+        assert!(property.listed_at.is_none());
+        assert!(property.days_on_market.is_none());
+        let history = list_status_history(&pool, &property.id).await.unwrap();
+        assert_eq!(history[0].status, "coming_soon");
+
+        let active = set_status(&pool, &property.id, status_input("active", None))
+            .await
+            .unwrap();
+        assert!(active.listed_at.is_some());
+
+        let mut sold = sample_input();
+        sold.status = Some("sold".to_string());
+        assert!(matches!(
+            create(&pool, sold).await,
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_set_status_validation() {
+        let pool = test_pool().await;
+        let property = create(&pool, sample_input()).await.unwrap();
+
+        assert!(
+            set_status(&pool, &property.id, status_input("closed", None))
+                .await
+                .is_err()
+        );
+        assert!(set_status(
+            &pool,
+            &property.id,
+            status_input("sold", Some("04/20/2024"))
+        )
+        .await
+        .is_err());
+        assert!(matches!(
+            set_status(&pool, "missing", status_input("sold", None)).await,
+            Err(AppError::PropertyNotFound(_))
+        ));
+    }
 }
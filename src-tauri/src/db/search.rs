@@ -0,0 +1,506 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, QueryBuilder, Row, Sqlite, SqlitePool};
+
+use crate::db::properties::{self, Property};
+use crate::error::AppError;
+
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 200;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertySort {
+    #[default]
+    Newest,
+    Oldest,
+    PriceHigh,
+    PriceLow,
+    Address,
+    /// Best full-text match first; same as `Newest` without a text query
+    Relevance,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PropertySearchQuery {
+    /// Free text matched against address, city, neighborhood, features,
+    /// agent notes and generated listing content
+    pub text: Option<String>,
+    pub min_price: Option<i64>,
+    pub max_price: Option<i64>,
+    pub min_beds: Option<i64>,
+    pub max_beds: Option<i64>,
+    pub min_baths: Option<f64>,
+    pub max_baths: Option<f64>,
+    pub property_types: Vec<String>,
+    /// Lifecycle statuses to include; empty means all
+    pub statuses: Vec<String>,
+    pub city: Option<String>,
+    pub has_favorite_listing: Option<bool>,
+    pub sort: PropertySort,
+    /// Opaque cursor from the previous page's `next_cursor`
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertySearchPage {
+    pub items: Vec<Property>,
+    pub total: i64,
+    pub next_cursor: Option<String>,
+}
+
+/// Sort key of the last row on a page, plus its id as the tiebreaker
+#[derive(Debug, Serialize, Deserialize)]
+struct Cursor {
+    key: CursorKey,
+    id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum CursorKey {
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+/// Search properties with full-text matching, structured filters, sorting and
+/// keyset (cursor) pagination
+pub async fn search_properties(
+    pool: &SqlitePool,
+    query: &PropertySearchQuery,
+) -> Result<PropertySearchPage, AppError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let fts = query.text.as_deref().and_then(fts_query);
+
+    let sort = if query.sort == PropertySort::Relevance && fts.is_none() {
+        PropertySort::Newest
+    } else {
+        query.sort
+    };
+    let (sort_expr, descending) = match sort {
+        PropertySort::Newest => ("p.created_at", true),
+        PropertySort::Oldest => ("p.created_at", false),
+        PropertySort::PriceHigh => ("p.price", true),
+        PropertySort::PriceLow => ("p.price", false),
+        PropertySort::Address => ("lower(p.address)", false),
+        PropertySort::Relevance => ("bm25(property_search)", false),
+    };
+
+    let mut count = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM properties p");
+    push_filters(&mut count, query, fts.as_deref());
+    let total: i64 = count.build_query_scalar().fetch_one(pool).await?;
+
+    let mut qb = QueryBuilder::<Sqlite>::new(format!(
+        "SELECT * FROM (SELECT {}, {} AS sort_key FROM properties p",
+        properties::COLUMNS,
+        sort_expr
+    ));
+    push_filters(&mut qb, query, fts.as_deref());
+    qb.push(") WHERE 1 = 1");
+
+    if let Some(ref encoded) = query.cursor {
+        let cursor: Cursor = serde_json::from_str(encoded)
+            .map_err(|_| AppError::Validation("Invalid search cursor".to_string()))?;
+        let cmp = if descending { "<" } else { ">" };
+
+        qb.push(format!(" AND (sort_key {} ", cmp));
+        push_cursor_key(&mut qb, &cursor.key);
+        qb.push(" OR (sort_key = ");
+        push_cursor_key(&mut qb, &cursor.key);
+        qb.push(format!(" AND id {} ", cmp));
+        qb.push_bind(cursor.id);
+        qb.push("))");
+    }
+
+    let dir = if descending { "DESC" } else { "ASC" };
+    qb.push(format!(" ORDER BY sort_key {dir}, id {dir} LIMIT "));
+    qb.push_bind(limit as i64 + 1);
+
+    let rows = qb.build().fetch_all(pool).await?;
+
+    let has_more = rows.len() > limit as usize;
+    let mut items = Vec::with_capacity(limit as usize);
+    let mut next_cursor = None;
+
+    for row in rows.iter().take(limit as usize) {
+        items.push(Property::from_row(row)?);
+    }
+
+    if has_more {
+        if let Some(last) = rows.get(limit as usize - 1) {
+            let key = match sort {
+                PropertySort::Newest | PropertySort::Oldest | PropertySort::Address => {
+                    CursorKey::Text(last.try_get("sort_key")?)
+                }
+                PropertySort::PriceHigh | PropertySort::PriceLow => {
+                    CursorKey::Int(last.try_get("sort_key")?)
+                }
+                PropertySort::Relevance => CursorKey::Float(last.try_get("sort_key")?),
+            };
+            let cursor = Cursor {
+                key,
+                id: last.try_get("id")?,
+            };
+            next_cursor = Some(serde_json::to_string(&cursor)?);
+        }
+    }
+
+    Ok(PropertySearchPage {
+        items,
+        total,
+        next_cursor,
+    })
+}
+
+/// Append the FTS join and every structured filter as a WHERE clause
+fn push_filters(qb: &mut QueryBuilder<'_, Sqlite>, query: &PropertySearchQuery, fts: Option<&str>) {
+    if let Some(fts) = fts {
+        qb.push(" JOIN property_search ON property_search.property_id = p.id WHERE property_search MATCH ");
+        qb.push_bind(fts.to_string());
+        qb.push(" AND p.deleted_at IS NULL");
+    } else {
+        qb.push(" WHERE p.deleted_at IS NULL");
+    }
+
+    if let Some(min_price) = query.min_price {
+        qb.push(" AND p.price >= ").push_bind(min_price);
+    }
+    if let Some(max_price) = query.max_price {
+        qb.push(" AND p.price <= ").push_bind(max_price);
+    }
+    if let Some(min_beds) = query.min_beds {
+        qb.push(" AND p.beds >= ").push_bind(min_beds);
+    }
+    if let Some(max_beds) = query.max_beds {
+        qb.push(" AND p.beds <= ").push_bind(max_beds);
+    }
+    if let Some(min_baths) = query.min_baths {
+        qb.push(" AND p.baths >= ").push_bind(min_baths);
+    }
+    if let Some(max_baths) = query.max_baths {
+        qb.push(" AND p.baths <= ").push_bind(max_baths);
+    }
+    if !query.property_types.is_empty() {
+        qb.push(" AND p.property_type IN (");
+        let mut separated = qb.separated(", ");
+        for property_type in &query.property_types {
+            separated.push_bind(property_type.clone());
+        }
+        separated.push_unseparated(")");
+    }
+    if !query.statuses.is_empty() {
+        qb.push(" AND p.status IN (");
+        let mut separated = qb.separated(", ");
+        for status in &query.statuses {
+            separated.push_bind(status.clone());
+        }
+        separated.push_unseparated(")");
+    }
+    if let Some(city) = query
+        .city
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+    {
+        qb.push(" AND p.city = ")
+            .push_bind(city.to_string())
+            .push(" COLLATE NOCASE");
+    }
+    if let Some(has_favorite) = query.has_favorite_listing {
+        qb.push(if has_favorite {
+            " AND EXISTS"
+        } else {
+            " AND NOT EXISTS"
+        });
+        qb.push(" (SELECT 1 FROM listings l WHERE l.property_id = p.id AND l.is_favorite = 1 AND l.deleted_at IS NULL)");
+    }
+}
+
+fn push_cursor_key(qb: &mut QueryBuilder<'_, Sqlite>, key: &CursorKey) {
+    match key {
+        CursorKey::Int(v) => qb.push_bind(*v),
+        CursorKey::Float(v) => qb.push_bind(*v),
+        CursorKey::Text(v) => qb.push_bind(v.clone()),
+    };
+}
+
+/// Turn free text into an FTS5 query where every word must match as a prefix.
+/// Quoting each term keeps user input from being parsed as FTS5 syntax.
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("\"{}\"*", t))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::listings::{self, CreateListingInput};
+    use crate::db::properties::{self, CreatePropertyInput};
+    use crate::db::test_pool;
+    use crate::db::trash::{self, TrashKind};
+
+    fn input(
+        address: &str,
+        city: &str,
+        price: i64,
+        beds: i64,
+        features: &[&str],
+    ) -> CreatePropertyInput {
+        CreatePropertyInput {
+            address: address.to_string(),
+            city: city.to_string(),
+            beds,
+            baths: 2.0,
+            price,
+            key_features: features.iter().map(|f| f.to_string()).collect(),
+            ..crate::db::test_property_input()
+        }
+    }
+
+    async fn save_listing(
+        pool: &SqlitePool,
+        property_id: &str,
+        content: &str,
+    ) -> listings::Listing {
+        listings::save(
+            pool,
+            CreateListingInput {
+                content: content.to_string(),
+                tokens_used: 100,
+                generation_cost_cents: 1,
+                ..crate::db::test_listing_input(property_id)
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fts_query_quotes_terms() {
+        assert_eq!(
+            fts_query("quartz counters").unwrap(),
+            r#""quartz"* "counters"*"#
+        );
+        assert_eq!(fts_query(r#"pool" OR "#).unwrap(), r#""pool"* "OR"*"#);
+        assert!(fts_query("  -- ").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_text_search_covers_features_and_listings() {
+        let pool = test_pool().await;
+        let a = properties::create(
+            &pool,
+            input("1 Oak St", "Austin", 50000000, 3, &["quartz counters"]),
+        )
+        .await
+        .unwrap();
+        let b = properties::create(&pool, input("2 Elm St", "Austin", 60000000, 4, &["pool"]))
+            .await
+            .unwrap();
+        save_listing(&pool, &b.id, "Soaring vaulted ceilings throughout.").await;
+
+        let query = PropertySearchQuery {
+            text: Some("quartz".to_string()),
+            ..Default::default()
+        };
+        let page = search_properties(&pool, &query).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].id, a.id);
+
+        let query = PropertySearchQuery {
+            text: Some("vault".to_string()),
+            sort: PropertySort::Relevance,
+            ..Default::default()
+        };
+        let page = search_properties(&pool, &query).await.unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].id, b.id);
+    }
+
+    #[tokio::test]
+    async fn test_index_follows_updates_and_deletes() {
+        let pool = test_pool().await;
+        let property = properties::create(&pool, input("1 Oak St", "Austin", 50000000, 3, &[]))
+            .await
+            .unwrap();
+        let listing = save_listing(&pool, &property.id, "Gourmet kitchen").await;
+
+        let mut updated = input("9 Birch Ln", "Austin", 50000000, 3, &[]);
+        updated.agent_notes = Some("Seller relocating".to_string());
+        properties::update(&pool, &property.id, updated)
+            .await
+            .unwrap();
+
+        for (text, expected) in [("birch", 1), ("oak", 0), ("relocating", 1), ("gourmet", 1)] {
+            let query = PropertySearchQuery {
+                text: Some(text.to_string()),
+                ..Default::default()
+            };
+            let page = search_properties(&pool, &query).await.unwrap();
+            assert_eq!(page.total, expected, "query {}", text);
+        }
+
+        listings::delete(&pool, &listing.id).await.unwrap();
+        let query = PropertySearchQuery {
+            text: Some("gourmet".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(search_properties(&pool, &query).await.unwrap().total, 0);
+
+        properties::delete(&pool, &property.id).await.unwrap();
+        let query = PropertySearchQuery {
+            text: Some("birch".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(search_properties(&pool, &query).await.unwrap().total, 0);
+
+        trash::purge(&pool, TrashKind::Property, &property.id)
+            .await
+            .unwrap();
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM property_search")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_structured_filters() {
+        let pool = test_pool().await;
+        let cheap = properties::create(&pool, input("1 Oak St", "Austin", 30000000, 2, &[]))
+            .await
+            .unwrap();
+        let mid = properties::create(&pool, input("2 Oak St", "austin", 60000000, 3, &[]))
+            .await
+            .unwrap();
+        properties::create(&pool, input("3 Oak St", "Dallas", 90000000, 5, &[]))
+            .await
+            .unwrap();
+
+        let query = PropertySearchQuery {
+            min_price: Some(25000000),
+            max_price: Some(70000000),
+            city: Some("AUSTIN".to_string()),
+            sort: PropertySort::PriceLow,
+            ..Default::default()
+        };
+        let page = search_properties(&pool, &query).await.unwrap();
+        let ids: Vec<&str> = page.items.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec![cheap.id.as_str(), mid.id.as_str()]);
+
+        properties::set_status(
+            &pool,
+            &cheap.id,
+            properties::SetStatusInput {
+                status: "sold".to_string(),
+                sold_price: None,
+                effective_date: None,
+                note: None,
+            },
+        )
+        .await
+        .unwrap();
+        let query = PropertySearchQuery {
+            statuses: vec!["active".to_string(), "pending".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(search_properties(&pool, &query).await.unwrap().total, 2);
+
+        let query = PropertySearchQuery {
+            min_beds: Some(3),
+            property_types: vec!["condo".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(search_properties(&pool, &query).await.unwrap().total, 0);
+
+        let listing = save_listing(&pool, &mid.id, "Lovely").await;
+        listings::toggle_favorite(&pool, &listing.id).await.unwrap();
+        let query = PropertySearchQuery {
+            has_favorite_listing: Some(true),
+            ..Default::default()
+        };
+        let page = search_properties(&pool, &query).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].id, mid.id);
+
+        let query = PropertySearchQuery {
+            has_favorite_listing: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(search_properties(&pool, &query).await.unwrap().total, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_pagination_visits_every_row_once() {
+        let pool = test_pool().await;
+        for i in 0..7 {
+            // Equal prices exercise the id tiebreaker
+            properties::create(
+                &pool,
+                input(
+                    &format!("{} Oak St", i),
+                    "Austin",
+                    50000000 + (i / 2) * 100,
+                    3,
+                    &[],
+                ),
+            )
+            .await
+            .unwrap();
+        }
+
+        for sort in [
+            PropertySort::Newest,
+            PropertySort::PriceHigh,
+            PropertySort::Address,
+        ] {
+            let mut seen = Vec::new();
+            let mut cursor = None;
+            loop {
+                let query = PropertySearchQuery {
+                    sort,
+                    cursor: cursor.clone(),
+                    limit: Some(3),
+                    ..Default::default()
+                };
+                let page = search_properties(&pool, &query).await.unwrap();
+                assert_eq!(page.total, 7);
+                seen.extend(page.items.into_iter().map(|p| p.id));
+                cursor = page.next_cursor;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+            let mut unique = seen.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(seen.len(), 7, "sort {:?}", sort);
+            assert_eq!(unique.len(), 7, "sort {:?}", sort);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_cursor_rejected() {
+        let pool = test_pool().await;
+        let query = PropertySearchQuery {
+            cursor: Some("not-a-cursor".to_string()),
+            ..Default::default()
+        };
+        assert!(search_properties(&pool, &query).await.is_err());
+    }
+}
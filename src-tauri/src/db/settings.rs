@@ -2,6 +2,12 @@ use sqlx::{Row, SqlitePool};
 
 use crate::error::AppError;
 
+/// Photos per property when the setting is missing or unreadable
+pub const DEFAULT_PHOTO_LIMIT: usize = 20;
+
+/// Highest photo limit that can be set; MLS systems cap well below this
+pub const MAX_PHOTO_LIMIT: usize = 100;
+
 pub async fn get(pool: &SqlitePool, key: &str) -> Result<String, AppError> {
     let row = sqlx::query("SELECT value FROM settings WHERE key = ?")
         .bind(key)
@@ -11,6 +17,7 @@ pub async fn get(pool: &SqlitePool, key: &str) -> Result<String, AppError> {
 }
 
 pub async fn set(pool: &SqlitePool, key: &str, value: &str) -> Result<(), AppError> {
+    validate(key, value)?;
     sqlx::query(
         "INSERT INTO settings (key, value, updated_at) VALUES (?, ?, datetime('now'))
          ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
@@ -22,6 +29,43 @@ pub async fn set(pool: &SqlitePool, key: &str, value: &str) -> Result<(), AppErr
     Ok(())
 }
 
+/// Reject values the app can't use for settings it reads as numbers
+fn validate(key: &str, value: &str) -> Result<(), AppError> {
+    if key == "max_photos_per_property" {
+        match value.trim().parse::<usize>() {
+            Ok(limit) if (1..=MAX_PHOTO_LIMIT).contains(&limit) => {}
+            _ => {
+                return Err(AppError::Validation(format!(
+                    "Photo limit must be a whole number from 1 to {}",
+                    MAX_PHOTO_LIMIT
+                )))
+            }
+        }
+    }
+    // Zero or fewer days would have the next purge delete everything in the
+    // trash for good
+    if key == "trash_retention_days" {
+        match value.trim().parse::<i64>() {
+            Ok(days) if days >= 1 => {}
+            _ => {
+                return Err(AppError::Validation(
+                    "Trash retention must be a whole number of days, at least 1".to_string(),
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Most photos a property can hold
+pub async fn photo_limit(pool: &SqlitePool) -> usize {
+    get(pool, "max_photos_per_property")
+        .await
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_PHOTO_LIMIT)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,4 +100,29 @@ mod tests {
         let value = get(&pool, "custom_setting").await.unwrap();
         assert_eq!(value, "custom_value");
     }
+
+    #[tokio::test]
+    async fn test_photo_limit_is_validated() {
+        let pool = test_pool().await;
+        assert_eq!(photo_limit(&pool).await, DEFAULT_PHOTO_LIMIT);
+
+        set(&pool, "max_photos_per_property", "60").await.unwrap();
+        assert_eq!(photo_limit(&pool).await, 60);
+
+        for bad in ["0", "-5", "abc", "101"] {
+            assert!(set(&pool, "max_photos_per_property", bad).await.is_err());
+        }
+        assert_eq!(photo_limit(&pool).await, 60);
+    }
+
+    #[tokio::test]
+    async fn test_trash_retention_is_validated() {
+        let pool = test_pool().await;
+        set(&pool, "trash_retention_days", "7").await.unwrap();
+
+        for bad in ["0", "-1", "abc", "1.5", ""] {
+            assert!(set(&pool, "trash_retention_days", bad).await.is_err());
+        }
+        assert_eq!(get(&pool, "trash_retention_days").await.unwrap(), "7");
+    }
 }
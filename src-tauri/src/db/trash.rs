@@ -0,0 +1,346 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::db::photos::{Photo, COLUMNS as PHOTO_COLUMNS};
+use crate::error::AppError;
+
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashKind {
+    Property,
+    Listing,
+    Photo,
+}
+
+impl TrashKind {
+    fn table(self) -> &'static str {
+        match self {
+            TrashKind::Property => "properties",
+            TrashKind::Listing => "listings",
+            TrashKind::Photo => "photos",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            TrashKind::Property => "property",
+            TrashKind::Listing => "listing",
+            TrashKind::Photo => "photo",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItem {
+    /// `property`, `listing` or `photo`
+    pub kind: String,
+    pub id: String,
+    pub property_id: String,
+    pub property_address: String,
+    /// Generation type for listings, original filename for photos
+    pub detail: Option<String>,
+    pub deleted_at: String,
+    pub purge_at: String,
+}
+
+/// Everything in the trash, most recently deleted first
+pub async fn list(pool: &SqlitePool, retention_days: i64) -> Result<Vec<TrashItem>, AppError> {
+    let offset = format!("+{} days", retention_days);
+
+    let items = sqlx::query_as::<_, TrashItem>(
+        "SELECT 'property' AS kind, id, id AS property_id, address AS property_address, NULL AS detail, deleted_at, datetime(deleted_at, ?1) AS purge_at
+         FROM properties WHERE deleted_at IS NOT NULL
+         UNION ALL
+         SELECT 'listing', l.id, l.property_id, p.address, l.generation_type, l.deleted_at, datetime(l.deleted_at, ?1)
+         FROM listings l JOIN properties p ON p.id = l.property_id WHERE l.deleted_at IS NOT NULL
+         UNION ALL
+         SELECT 'photo', ph.id, ph.property_id, p.address, ph.filename, ph.deleted_at, datetime(ph.deleted_at, ?1)
+         FROM photos ph JOIN properties p ON p.id = ph.property_id WHERE ph.deleted_at IS NOT NULL
+         ORDER BY deleted_at DESC",
+    )
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(items)
+}
+
+/// Take an item back out of the trash. Listings and photos can only be
+/// restored while their property is not itself in the trash.
+pub async fn restore(pool: &SqlitePool, kind: TrashKind, id: &str) -> Result<(), AppError> {
+    if kind != TrashKind::Property {
+        let parent_deleted: Option<bool> = sqlx::query_scalar(&format!(
+            "SELECT p.deleted_at IS NOT NULL FROM {} t JOIN properties p ON p.id = t.property_id WHERE t.id = ?",
+            kind.table()
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        if parent_deleted == Some(true) {
+            return Err(AppError::Validation(
+                "Restore the property first; it is also in the trash".to_string(),
+            ));
+        }
+    }
+
+    let result = sqlx::query(&format!(
+        "UPDATE {} SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL",
+        kind.table()
+    ))
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::Validation(format!(
+            "No {} with ID {} in the trash",
+            kind.label(),
+            id
+        )));
+    }
+
+    Ok(())
+}
+
+/// Permanently delete one trashed item. Returns the photos whose files should
+/// now be removed from disk.
+pub async fn purge(pool: &SqlitePool, kind: TrashKind, id: &str) -> Result<Vec<Photo>, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let photos = match kind {
+        TrashKind::Property => {
+            sqlx::query_as::<_, Photo>(&format!(
+                "SELECT {} FROM photos WHERE property_id = (SELECT id FROM properties WHERE id = ? AND deleted_at IS NOT NULL)",
+                PHOTO_COLUMNS
+            ))
+            .bind(id)
+            .fetch_all(&mut *tx)
+            .await?
+        }
+        TrashKind::Photo => {
+            sqlx::query_as::<_, Photo>(&format!(
+                "SELECT {} FROM photos WHERE id = ? AND deleted_at IS NOT NULL",
+                PHOTO_COLUMNS
+            ))
+            .bind(id)
+            .fetch_all(&mut *tx)
+            .await?
+        }
+        TrashKind::Listing => Vec::new(),
+    };
+
+    // Deleting a property cascades to its listings and photos
+    sqlx::query(&format!(
+        "DELETE FROM {} WHERE id = ? AND deleted_at IS NOT NULL",
+        kind.table()
+    ))
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(photos)
+}
+
+/// Permanently delete everything that has been in the trash for at least
+/// `retention_days`. Returns the photos whose files should now be removed
+/// from disk.
+pub async fn purge_expired(pool: &SqlitePool, retention_days: i64) -> Result<Vec<Photo>, AppError> {
+    let offset = format!("-{} days", retention_days.max(0));
+    let mut tx = pool.begin().await?;
+
+    let photos = sqlx::query_as::<_, Photo>(&format!(
+        "SELECT {} FROM photos
+         WHERE deleted_at <= datetime('now', ?1)
+            OR property_id IN (SELECT id FROM properties WHERE deleted_at <= datetime('now', ?1))",
+        PHOTO_COLUMNS
+    ))
+    .bind(&offset)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for table in ["photos", "listings", "properties"] {
+        sqlx::query(&format!(
+            "DELETE FROM {} WHERE deleted_at <= datetime('now', ?)",
+            table
+        ))
+        .bind(&offset)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(photos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::listings::{self, CreateListingInput};
+    use crate::db::{photos, properties, test_pool};
+
+    async fn setup(pool: &SqlitePool) -> (String, String) {
+        let property = properties::create(pool, crate::db::test_property_input())
+            .await
+            .unwrap();
+
+        let listing = listings::save(
+            pool,
+            CreateListingInput {
+                content: "Lovely home".to_string(),
+                tokens_used: 100,
+                generation_cost_cents: 1,
+                ..crate::db::test_listing_input(&property.id)
+            },
+        )
+        .await
+        .unwrap();
+
+        photos::insert(
+            pool,
+            "ph1",
+            &property.id,
+            "front.jpg",
+            "/a.jpg",
+            "/ta.jpg",
+            0,
+            None,
+        )
+        .await
+        .unwrap();
+
+        (property.id, listing.id)
+    }
+
+    async fn backdate(pool: &SqlitePool, table: &str, days: i64) {
+        sqlx::query(&format!(
+            "UPDATE {} SET deleted_at = datetime('now', '-{} days') WHERE deleted_at IS NOT NULL",
+            table, days
+        ))
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_property_delete_is_soft_and_restorable() {
+        let pool = test_pool().await;
+        let (property_id, listing_id) = setup(&pool).await;
+
+        properties::delete(&pool, &property_id).await.unwrap();
+        assert!(properties::get(&pool, &property_id).await.is_err());
+        assert!(properties::list_all(&pool).await.unwrap().is_empty());
+
+        let items = list(&pool, 30).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].kind, "property");
+        assert_eq!(items[0].property_address, "123 Oak Street");
+
+        restore(&pool, TrashKind::Property, &property_id)
+            .await
+            .unwrap();
+        assert!(properties::get(&pool, &property_id).await.is_ok());
+        // Children were never touched, so they come back with the property
+        assert!(listings::get(&pool, &listing_id).await.is_ok());
+        assert_eq!(
+            photos::list_by_property(&pool, &property_id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(list(&pool, 30).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_child_restore_requires_live_property() {
+        let pool = test_pool().await;
+        let (property_id, listing_id) = setup(&pool).await;
+
+        listings::delete(&pool, &listing_id).await.unwrap();
+        photos::delete(&pool, "ph1").await.unwrap();
+        assert!(listings::list_by_property(&pool, &property_id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(photos::list_by_property(&pool, &property_id)
+            .await
+            .unwrap()
+            .is_empty());
+        properties::delete(&pool, &property_id).await.unwrap();
+
+        assert_eq!(list(&pool, 30).await.unwrap().len(), 3);
+        assert!(restore(&pool, TrashKind::Listing, &listing_id)
+            .await
+            .is_err());
+
+        restore(&pool, TrashKind::Property, &property_id)
+            .await
+            .unwrap();
+        restore(&pool, TrashKind::Listing, &listing_id)
+            .await
+            .unwrap();
+        restore(&pool, TrashKind::Photo, "ph1").await.unwrap();
+        assert_eq!(
+            listings::list_by_property(&pool, &property_id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Restoring something that isn't in the trash is an error
+        assert!(restore(&pool, TrashKind::Photo, "ph1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_respects_retention() {
+        let pool = test_pool().await;
+        let (property_id, _) = setup(&pool).await;
+        properties::delete(&pool, &property_id).await.unwrap();
+
+        backdate(&pool, "properties", 10).await;
+        assert!(purge_expired(&pool, 30).await.unwrap().is_empty());
+        assert_eq!(list(&pool, 30).await.unwrap().len(), 1);
+
+        backdate(&pool, "properties", 31).await;
+        let removed = purge_expired(&pool, 30).await.unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].original_path, "/a.jpg");
+
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM listings")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 0);
+        assert!(list(&pool, 30).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_purge_single_item() {
+        let pool = test_pool().await;
+        let (property_id, _) = setup(&pool).await;
+
+        // Only trashed items can be purged
+        assert!(purge(&pool, TrashKind::Photo, "ph1")
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            photos::list_by_property(&pool, &property_id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        photos::delete(&pool, "ph1").await.unwrap();
+        let removed = purge(&pool, TrashKind::Photo, "ph1").await.unwrap();
+        assert_eq!(removed.len(), 1);
+        assert!(list(&pool, 30).await.unwrap().is_empty());
+    }
+}
@@ -32,6 +32,12 @@ pub enum AppError {
     #[error("Brand voice not found (ID: {0}). It may have been deleted.")]
     BrandVoiceNotFound(String),
 
+    #[error("Agent not found (ID: {0}). It may have been deleted.")]
+    AgentNotFound(String),
+
+    #[error("Prompt preset not found (ID: {0}). It may have been deleted.")]
+    PromptPresetNotFound(String),
+
     #[error("License key is invalid or expired. Please purchase or renew at https://lemonsqueezy.com")]
     InvalidLicense,
 
@@ -53,12 +59,15 @@ pub enum AppError {
     #[error("Failed to import photos: {0}. Please ensure files are valid images (JPEG, PNG).")]
     PhotoImportFailed(String),
 
-    #[error("Maximum of 20 photos per property. Please delete some photos before adding more.")]
-    PhotoLimitExceeded,
+    #[error("Maximum of {0} photos per property. Please delete some photos or raise the limit in Settings.")]
+    PhotoLimitExceeded(usize),
 
     #[error("Photo error: {0}")]
     Photo(String),
 
+    #[error("Backup error: {0}")]
+    Backup(String),
+
     #[error("Configuration error: {0}. Please check your settings.")]
     Config(String),
 
@@ -0,0 +1,49 @@
+use crate::ai::prompts::language_name;
+use crate::db::listings::Listing;
+
+/// The translation to print after `listing` in a bilingual export: one that
+/// shares its original, or the original itself when `listing` is a
+/// translation
+pub fn translation_of<'a>(listing: &Listing, translations: &'a [Listing]) -> Option<&'a Listing> {
+    let original_id = listing.source_listing_id.as_deref().unwrap_or(&listing.id);
+    translations.iter().find(|t| {
+        t.id != listing.id
+            && (t.id == original_id || t.source_listing_id.as_deref() == Some(original_id))
+    })
+}
+
+/// Language name for a bilingual section title
+pub fn language_label(listing: &Listing) -> &str {
+    language_name(&listing.language).unwrap_or(&listing.language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing(id: &str, language: &str, source: Option<&str>) -> Listing {
+        Listing {
+            id: id.to_string(),
+            language: language.to_string(),
+            source_listing_id: source.map(str::to_string),
+            ..crate::db::test_listing()
+        }
+    }
+
+    #[test]
+    fn test_translation_of() {
+        let original = listing("a", "en", None);
+        let spanish = listing("b", "es", Some("a"));
+        let chinese = listing("c", "zh", Some("a"));
+        let other = listing("d", "es", Some("x"));
+
+        let translations = [other.clone(), spanish.clone()];
+        assert_eq!(translation_of(&original, &translations).unwrap().id, "b");
+        assert!(translation_of(&listing("x2", "en", None), &translations).is_none());
+
+        // A translation pairs with its original or a sibling translation
+        assert_eq!(translation_of(&chinese, &[original]).unwrap().id, "a");
+        assert_eq!(translation_of(&chinese, &[spanish]).unwrap().id, "b");
+        assert_eq!(language_label(&chinese), "Simplified Chinese");
+    }
+}
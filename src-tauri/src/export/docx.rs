@@ -1,16 +1,103 @@
 use docx_rs::*;
 use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
 
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::ai::prompts::AgentInfo;
 use crate::db::listings::Listing;
 use crate::db::photos::Photo;
 use crate::db::properties::Property;
 use crate::error::AppError;
+use crate::photos::derivatives::{self, DerivativePreset};
+
+use super::bilingual::{language_label, translation_of};
+use super::long_form::{blocks, section_title, Block};
+
+/// Largest a photo is drawn, in EMUs (914,400 to the inch)
+const MAX_IMAGE_WIDTH_EMU: u64 = 4_000_000; // ~4.4 inches
+const MAX_IMAGE_HEIGHT_EMU: u64 = 4_000_000;
+
+/// The drawing properties docx-rs writes for every picture
+const PICTURE_PROPERTIES: &str = r#"<wp:docPr id="1" name="Figure" />"#;
+
+/// Size in EMUs that fits a `width` x `height` image in the largest photo
+/// box without stretching it
+fn picture_size(width: u32, height: u32) -> (u32, u32) {
+    let (width, height) = (width.max(1) as u64, height.max(1) as u64);
+    (
+        MAX_IMAGE_WIDTH_EMU.min(MAX_IMAGE_HEIGHT_EMU * width / height) as u32,
+        MAX_IMAGE_HEIGHT_EMU.min(MAX_IMAGE_WIDTH_EMU * height / width) as u32,
+    )
+}
+
+fn escape_attribute(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// docx-rs can't describe a picture, so give each drawing in the packed
+/// document its own id and, where the photo has one, its alt text.
+/// `descriptions` are in the order the pictures were added.
+fn describe_pictures(docx: &[u8], descriptions: &[Option<&str>]) -> Result<Vec<u8>, AppError> {
+    let error =
+        |e: zip::result::ZipError| AppError::Export(format!("Failed to generate DOCX: {}", e));
+    let mut archive = ZipArchive::new(Cursor::new(docx)).map_err(error)?;
+    let mut buf = Vec::new();
+    let mut zip = ZipWriter::new(Cursor::new(&mut buf));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(error)?;
+        let name = entry.name().to_string();
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+
+        if name == "word/document.xml" {
+            let xml = String::from_utf8_lossy(&contents);
+            let mut parts = xml.split(PICTURE_PROPERTIES);
+            let mut described = parts.next().unwrap_or_default().to_string();
+            for (n, part) in parts.enumerate() {
+                let descr = descriptions
+                    .get(n)
+                    .copied()
+                    .flatten()
+                    .map(|text| format!(r#" descr="{}""#, escape_attribute(text)))
+                    .unwrap_or_default();
+                described.push_str(&format!(
+                    r#"<wp:docPr id="{}" name="Photo {}"{} />"#,
+                    n + 1,
+                    n + 1,
+                    descr
+                ));
+                described.push_str(part);
+            }
+            contents = described.into_bytes();
+        }
+
+        zip.start_file(name, options).map_err(error)?;
+        zip.write_all(&contents)?;
+    }
+    zip.finish().map_err(error)?;
+    drop(zip);
+
+    Ok(buf)
+}
 
-/// Generate a DOCX document for a property with its listings and photos
+/// Generate a DOCX document for a property with its listings, photos and the
+/// assigned agent's contact details. Photos are drawn from their cached
+/// featured-size derivatives under `app_data_dir`, with their alt text.
 pub fn generate_docx(
+    app_data_dir: &Path,
     property: &Property,
     listings: &[Listing],
+    translations: &[Listing],
     photos: &[Photo],
+    agent: &AgentInfo,
 ) -> Result<Vec<u8>, AppError> {
     let mut docx = Docx::new();
 
@@ -59,6 +146,7 @@ pub fn generate_docx(
     }
 
     // Photos section
+    let mut descriptions = Vec::new();
     if !photos.is_empty() {
         docx = docx.add_paragraph(Paragraph::new());
         docx = docx.add_paragraph(
@@ -69,15 +157,30 @@ pub fn generate_docx(
 
         // Add up to 6 photos
         for photo in photos.iter().take(6) {
-            if let Ok(image_bytes) = fs::read(&photo.original_path) {
-                // Create a Pic element with the image data
-                const IMAGE_WIDTH_EMU: u32 = 4_000_000; // ~4.4 inches
-                const IMAGE_HEIGHT_EMU: u32 = 3_000_000; // ~3.3 inches
-                let pic = Pic::new(&image_bytes).size(IMAGE_WIDTH_EMU, IMAGE_HEIGHT_EMU);
+            // The featured derivative is already upright and far smaller
+            // than the original; fall back to the original if it can't be
+            // made
+            let (source, width, height) = match derivatives::get_or_create(
+                app_data_dir,
+                photo,
+                &DerivativePreset::Featured.spec(),
+            ) {
+                Ok(derivative) => (derivative.path, derivative.width, derivative.height),
+                Err(e) => {
+                    eprintln!("Failed to prepare image {}: {}", photo.original_path, e);
+                    let (width, height) =
+                        image::image_dimensions(&photo.original_path).unwrap_or((4, 3));
+                    (photo.original_path.clone(), width, height)
+                }
+            };
+            if let Ok(image_bytes) = fs::read(&source) {
+                let (width_emu, height_emu) = picture_size(width, height);
+                let pic = Pic::new(&image_bytes).size(width_emu, height_emu);
 
                 docx = docx.add_paragraph(
                     Paragraph::new().add_run(Run::new().add_image(pic)),
                 );
+                descriptions.push(photo.alt_text.as_deref().filter(|t| !t.trim().is_empty()));
 
                 // Add caption if available
                 if let Some(ref caption) = photo.caption {
@@ -91,50 +194,102 @@ pub fn generate_docx(
 
                 docx = docx.add_paragraph(Paragraph::new()); // Spacer
             } else {
-                eprintln!("Failed to read image file: {}", photo.original_path);
+                eprintln!("Failed to read image file: {}", source);
                 // Continue with other photos even if one fails
             }
         }
     }
 
-    // Listings
+    // Listings, each followed by its translation in bilingual exports
     for (i, listing) in listings.iter().enumerate() {
-        docx = docx.add_paragraph(Paragraph::new()); // spacer
+        let title = section_title(listing, i);
+        let sections = match translation_of(listing, translations) {
+            Some(translation) => vec![
+                (listing, format!("{} ({})", title, language_label(listing))),
+                (translation, format!("{} ({})", title, language_label(translation))),
+            ],
+            None => vec![(listing, title)],
+        };
+
+        for (listing, section_title) in sections {
+            docx = docx.add_paragraph(Paragraph::new()); // spacer
+            docx = docx.add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_text(&section_title).bold())
+                    .style("Heading2"),
+            );
 
-        let section_title = match listing.generation_type.as_str() {
-            "listing" => format!("Listing Description {}", i + 1),
-            t if t.starts_with("social_") => {
-                format!("Social Media - {}", t.strip_prefix("social_").unwrap_or(t))
+            // Long-form copy is laid out by its structure
+            if let Some(blocks) = blocks(listing) {
+                for block in blocks {
+                    let paragraph = match block {
+                        Block::Subheading(text) => Paragraph::new()
+                            .add_run(Run::new().add_text(&text).bold())
+                            .style("Heading3"),
+                        Block::Paragraph(text) => Paragraph::new().add_run(Run::new().add_text(&text)),
+                        Block::Bullet(text) => {
+                            Paragraph::new().add_run(Run::new().add_text(format!("\u{2022} {}", text)))
+                        }
+                        Block::Note(text) => {
+                            Paragraph::new().add_run(Run::new().add_text(&text).italic())
+                        }
+                    };
+                    docx = docx.add_paragraph(paragraph);
+                }
+                continue;
             }
-            t if t.starts_with("email_") => {
-                format!("Email - {}", t.strip_prefix("email_").unwrap_or(t))
+
+            // Split content by paragraphs
+            for paragraph in listing.content.split("\n\n") {
+                let trimmed = paragraph.trim();
+                if !trimmed.is_empty() {
+                    docx = docx
+                        .add_paragraph(Paragraph::new().add_run(Run::new().add_text(trimmed)));
+                }
             }
-            t => t.to_string(),
-        };
+        }
+    }
 
+    // Agent contact block
+    if !agent.name.is_empty() {
+        docx = docx.add_paragraph(Paragraph::new()); // spacer
         docx = docx.add_paragraph(
             Paragraph::new()
-                .add_run(Run::new().add_text(&section_title).bold())
+                .add_run(Run::new().add_text("Presented By").bold())
                 .style("Heading2"),
         );
+        docx = docx.add_paragraph(
+            Paragraph::new().add_run(Run::new().add_text(&agent.name).bold()),
+        );
 
-        // Split content by paragraphs
-        for paragraph in listing.content.split("\n\n") {
-            let trimmed = paragraph.trim();
-            if !trimmed.is_empty() {
-                docx = docx
-                    .add_paragraph(Paragraph::new().add_run(Run::new().add_text(trimmed)));
-            }
+        let contact: Vec<&str> = [
+            agent.brokerage.as_str(),
+            agent.phone.as_str(),
+            agent.email.as_str(),
+        ]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect();
+        if !contact.is_empty() {
+            docx = docx.add_paragraph(
+                Paragraph::new().add_run(Run::new().add_text(&contact.join(" | "))),
+            );
+        }
+
+        for line in agent.signature.lines().filter(|l| !l.trim().is_empty()) {
+            docx = docx.add_paragraph(
+                Paragraph::new().add_run(Run::new().add_text(line.trim()).italic()),
+            );
         }
     }
 
     // Render to bytes
     let mut buf = Vec::new();
     docx.build()
-        .pack(&mut std::io::Cursor::new(&mut buf))
+        .pack(&mut Cursor::new(&mut buf))
         .map_err(|e| AppError::Export(format!("Failed to generate DOCX: {}", e)))?;
 
-    Ok(buf)
+    describe_pictures(&buf, &descriptions)
 }
 
 fn format_price_dollars(cents: i64) -> String {
@@ -159,25 +314,13 @@ mod tests {
         Property {
             id: "test".to_string(),
             address: "123 Oak St".to_string(),
-            city: "San Francisco".to_string(),
-            state: "CA".to_string(),
-            zip: "94105".to_string(),
-            beds: 3,
-            baths: 2.5,
-            sqft: 1800,
-            price: 95000000,
-            property_type: "single_family".to_string(),
             year_built: Some(2015),
-            lot_size: None,
-            parking: None,
             key_features: r#"["pool","hardwood floors"]"#.to_string(),
-            neighborhood: None,
-            neighborhood_highlights: "[]".to_string(),
-            school_district: None,
-            nearby_amenities: "[]".to_string(),
-            agent_notes: None,
+            listed_at: Some("2024-01-01 00:00:00".to_string()),
+            days_on_market: Some(12),
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
+            ..crate::db::test_property()
         }
     }
 
@@ -186,16 +329,23 @@ mod tests {
             id: "listing-1".to_string(),
             property_id: "test".to_string(),
             content: "A beautiful home in San Francisco.\n\nThis stunning property features hardwood floors and a pool.".to_string(),
-            generation_type: "listing".to_string(),
             style: Some("luxury".to_string()),
             tone: Some("warm".to_string()),
             length: Some("medium".to_string()),
-            seo_keywords: "[]".to_string(),
-            brand_voice_id: None,
             tokens_used: 500,
             generation_cost_cents: 1,
-            is_favorite: false,
             created_at: "2024-01-01".to_string(),
+            ..crate::db::test_listing()
+        }
+    }
+
+    fn sample_agent() -> AgentInfo {
+        AgentInfo {
+            name: "Jane Smith".to_string(),
+            phone: "555-1234".to_string(),
+            email: "jane@example.com".to_string(),
+            brokerage: "RE/MAX".to_string(),
+            signature: "Jane Smith\nDRE #01234567".to_string(),
         }
     }
 
@@ -203,11 +353,58 @@ mod tests {
     fn test_generate_docx_produces_valid_zip() {
         let property = sample_property();
         let listings = vec![sample_listing()];
-        let result = generate_docx(&property, &listings);
+        let result = generate_docx(
+            &std::env::temp_dir(),
+            &property,
+            &listings,
+            &[],
+            &[],
+            &sample_agent(),
+        );
         assert!(result.is_ok());
         let bytes = result.unwrap();
         // DOCX is a ZIP file — check magic bytes
         assert!(bytes.len() > 4);
         assert_eq!(&bytes[0..2], b"PK");
     }
+
+    #[test]
+    fn test_photos_keep_their_shape_and_alt_text() {
+        let dir = std::env::temp_dir().join(format!("realestate-docx-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let original = dir.join("porch.png");
+        image::RgbImage::new(600, 900).save(&original).unwrap();
+        let photo = Photo {
+            original_path: original.to_string_lossy().to_string(),
+            alt_text: Some("Front porch with \"Welcome\" sign & swing".to_string()),
+            ..crate::db::test_photo()
+        };
+
+        let bytes = generate_docx(
+            &dir,
+            &sample_property(),
+            &[sample_listing()],
+            &[],
+            &[photo],
+            &sample_agent(),
+        )
+        .unwrap();
+        let mut document = String::new();
+        ZipArchive::new(Cursor::new(bytes))
+            .unwrap()
+            .by_name("word/document.xml")
+            .unwrap()
+            .read_to_string(&mut document)
+            .unwrap();
+
+        // Portrait, drawn taller than wide
+        let (width, height) = picture_size(600, 900);
+        assert_eq!((width, height), (2_666_666, 4_000_000));
+        assert!(document.contains(&format!(r#"cx="{}" cy="{}""#, width, height)));
+        assert!(document.contains(
+            r#"<wp:docPr id="1" name="Photo 1" descr="Front porch with &quot;Welcome&quot; sign &amp; swing" />"#
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
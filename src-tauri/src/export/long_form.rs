@@ -0,0 +1,171 @@
+use crate::ai::long_form::{self, LongFormDocument, LongFormKind, Section};
+use crate::db::listings::Listing;
+
+/// A line of long-form copy as laid out in DOCX and PDF exports
+#[derive(Debug, PartialEq)]
+pub enum Block {
+    Subheading(String),
+    Paragraph(String),
+    Bullet(String),
+    /// Secondary detail: taglines, running times, character counts
+    Note(String),
+}
+
+/// Export section title for a listing of any generation type
+pub fn section_title(listing: &Listing, index: usize) -> String {
+    match listing.generation_type.as_str() {
+        "listing" => format!("Listing Description {}", index + 1),
+        t if t.starts_with("social_") => {
+            format!("Social Media - {}", t.strip_prefix("social_").unwrap_or(t))
+        }
+        t if t.starts_with("email_") => {
+            format!("Email - {}", t.strip_prefix("email_").unwrap_or(t))
+        }
+        t => match LongFormKind::from_generation_type(t) {
+            Some(kind) => kind.title().to_string(),
+            None => t.to_string(),
+        },
+    }
+}
+
+/// Structured layout for long-form listings. `None` for other generation
+/// types and for long-form copy edited out of its format, which export as
+/// plain paragraphs.
+pub fn blocks(listing: &Listing) -> Option<Vec<Block>> {
+    let kind = LongFormKind::from_generation_type(&listing.generation_type)?;
+    let document = long_form::parse(kind, &listing.content).ok()?;
+
+    let mut blocks = Vec::new();
+    match document {
+        LongFormDocument::VideoScript {
+            title,
+            scenes,
+            total_seconds,
+        } => {
+            if !title.is_empty() {
+                blocks.push(Block::Subheading(title));
+            }
+            if total_seconds > 0 {
+                blocks.push(Block::Note(format!(
+                    "Running time {}:{:02}",
+                    total_seconds / 60,
+                    total_seconds % 60
+                )));
+            }
+            for (i, scene) in scenes.into_iter().enumerate() {
+                let mut heading = format!("Scene {}", i + 1);
+                if !scene.location.is_empty() {
+                    heading.push_str(&format!(" - {}", scene.location));
+                }
+                if let Some(seconds) = scene.duration_seconds {
+                    heading.push_str(&format!(" ({}s)", seconds));
+                }
+                blocks.push(Block::Subheading(heading));
+                blocks.extend(scene.shots.into_iter().map(Block::Bullet));
+                if !scene.voiceover.is_empty() {
+                    blocks.push(Block::Paragraph(format!(
+                        "Voiceover: {}",
+                        scene.voiceover.replace('\n', " ")
+                    )));
+                }
+            }
+        }
+        LongFormDocument::WebsiteCopy {
+            headline,
+            tagline,
+            sections,
+        } => {
+            blocks.push(Block::Subheading(headline));
+            if !tagline.is_empty() {
+                blocks.push(Block::Note(tagline));
+            }
+            push_sections(&mut blocks, sections);
+        }
+        LongFormDocument::FeatureSheet { title, rooms } => {
+            if !title.is_empty() {
+                blocks.push(Block::Note(title));
+            }
+            push_sections(&mut blocks, rooms);
+        }
+        LongFormDocument::NeighborhoodGuide { title, sections } => {
+            if !title.is_empty() {
+                blocks.push(Block::Note(title));
+            }
+            push_sections(&mut blocks, sections);
+        }
+        LongFormDocument::PrintAd {
+            headline,
+            body,
+            characters,
+        } => {
+            blocks.push(Block::Subheading(headline));
+            blocks.extend(
+                body.split("\n\n")
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(|p| Block::Paragraph(p.to_string())),
+            );
+            blocks.push(Block::Note(format!("{} characters", characters)));
+        }
+    }
+    Some(blocks)
+}
+
+fn push_sections(blocks: &mut Vec<Block>, sections: Vec<Section>) {
+    for section in sections {
+        blocks.push(Block::Subheading(section.heading));
+        blocks.extend(section.paragraphs.into_iter().map(Block::Paragraph));
+        blocks.extend(section.bullets.into_iter().map(Block::Bullet));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing(generation_type: &str, content: &str) -> Listing {
+        Listing {
+            content: content.to_string(),
+            generation_type: generation_type.to_string(),
+            ..crate::db::test_listing()
+        }
+    }
+
+    #[test]
+    fn test_section_titles() {
+        assert_eq!(
+            section_title(&listing("listing", ""), 1),
+            "Listing Description 2"
+        );
+        assert_eq!(
+            section_title(&listing("social_instagram", ""), 0),
+            "Social Media - instagram"
+        );
+        assert_eq!(
+            section_title(&listing("video_script", ""), 0),
+            "Video Script"
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        let script = listing(
+            "video_script",
+            "TITLE: Tour\n---SCENE 1---\nLOCATION: Kitchen\nDURATION: 75s\nSHOTS:\n- Pan across the island\nVOICEOVER: Cook here.\n",
+        );
+        assert_eq!(
+            blocks(&script).unwrap(),
+            vec![
+                Block::Subheading("Tour".to_string()),
+                Block::Note("Running time 1:15".to_string()),
+                Block::Subheading("Scene 1 - Kitchen (75s)".to_string()),
+                Block::Bullet("Pan across the island".to_string()),
+                Block::Paragraph("Voiceover: Cook here.".to_string()),
+            ]
+        );
+
+        // Edited out of format, or not long-form at all
+        assert!(blocks(&listing("feature_sheet", "Kitchen has quartz.")).is_none());
+        assert!(blocks(&listing("listing", "HEADLINE: x\nBODY: y")).is_none());
+    }
+}
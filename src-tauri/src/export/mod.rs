@@ -1,3 +1,7 @@
+pub mod bilingual;
 pub mod docx;
+pub mod long_form;
 pub mod pdf;
+pub mod social_card;
+pub mod system_font;
 pub mod templates;
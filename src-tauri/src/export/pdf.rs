@@ -1,45 +1,121 @@
 use genpdf::elements::{Break, Image, Paragraph};
-use genpdf::fonts;
+use genpdf::fonts::{self, FontData, FontFamily};
 use genpdf::style::{Color, Style};
 use genpdf::{Document, Element, Mm, SimplePageDecorator};
+use std::path::Path;
+
 use image::DynamicImage;
 
+use crate::ai::prompts::AgentInfo;
 use crate::db::listings::Listing;
 use crate::db::photos::Photo;
 use crate::db::properties::Property;
 use crate::error::AppError;
+use crate::photos::derivatives::{self, DerivativePreset};
+
+use super::bilingual::{language_label, translation_of};
+use super::long_form::{blocks, section_title, Block};
+use super::system_font;
+
+/// Whether the PDF's Latin fonts have a glyph for `c`: Latin with its
+/// extensions and combining accents (which covers Vietnamese), plus common
+/// punctuation and symbols. Chinese and other scripts would print as blanks.
+fn pdf_can_draw(c: char) -> bool {
+    c.is_whitespace()
+        || matches!(
+            c,
+            '\u{0000}'..='\u{024F}'
+                | '\u{0300}'..='\u{036F}'
+                | '\u{1E00}'..='\u{1EFF}'
+                | '\u{2000}'..='\u{206F}'
+                | '\u{20A0}'..='\u{20CF}'
+                | '\u{2100}'..='\u{214F}'
+        )
+}
+
+/// An installed font covering every listing, when one is in a script the
+/// built-in fonts can't draw. Fails rather than exporting blank text when no
+/// installed font covers them all.
+fn fallback_font_family<'a>(
+    listings: impl IntoIterator<Item = &'a Listing>,
+) -> Result<Option<FontFamily<FontData>>, AppError> {
+    let listings: Vec<&Listing> = listings.into_iter().collect();
+    let Some((listing, c)) = listings.iter().find_map(|listing| {
+        listing
+            .content
+            .chars()
+            .find(|&c| !pdf_can_draw(c))
+            .map(|c| (listing, c))
+    }) else {
+        return Ok(None);
+    };
 
-/// Generate a PDF marketing package for a property with its listings and photos
+    let text = listings
+        .iter()
+        .map(|listing| listing.content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    // genpdf can't read font collections, so skip to the next covering font
+    let regular = system_font::covering_files(&text)
+        .find_map(|bytes| FontData::new(bytes.to_vec(), None).ok())
+        .ok_or_else(|| {
+            AppError::Export(format!(
+                "No installed font can display {} text (such as \"{}\"). Export this listing as a Word document instead.",
+                language_label(listing),
+                c
+            ))
+        })?;
+    Ok(Some(FontFamily {
+        regular: regular.clone(),
+        bold: regular.clone(),
+        italic: regular.clone(),
+        bold_italic: regular,
+    }))
+}
+
+/// Generate a PDF marketing package for a property with its listings, photos
+/// and the assigned agent's contact details. Photos are drawn from their
+/// cached featured-size derivatives under `app_data_dir`. Listings in a
+/// script the built-in fonts don't cover, such as Chinese, are drawn with an
+/// installed font that does; fails when there is none.
 pub fn generate_pdf(
+    app_data_dir: &Path,
     property: &Property,
     listings: &[Listing],
+    translations: &[Listing],
     photos: &[Photo],
+    agent: &AgentInfo,
 ) -> Result<Vec<u8>, AppError> {
+    let fallback = fallback_font_family(listings.iter().chain(translations))?;
+
     // Use built-in Helvetica font (always available)
-    let font_family =
-        fonts::from_files("", "Helvetica", None).unwrap_or_else(|_| {
+    let font_family = match fallback {
+        Some(family) => family,
+        None => fonts::from_files("", "Helvetica", None).unwrap_or_else(|_| {
             // Fallback: use Liberation Sans if Helvetica not found
             // genpdf provides built-in fonts as fallback
-            fonts::from_files("", "LiberationSans", None)
-                .unwrap_or_else(|_| {
-                    // Last resort: create a minimal font family
-                    let font_data = genpdf::fonts::FontData::new(
-                        Vec::new(), // Will fail gracefully
-                        None,
-                    );
-                    let regular = match font_data {
-                        Ok(f) => f,
-                        Err(_) => return fonts::from_files("/System/Library/Fonts", "Helvetica", None)
-                            .expect("Could not load any font"),
-                    };
-                    genpdf::fonts::FontFamily {
-                        regular: regular.clone(),
-                        bold: regular.clone(),
-                        italic: regular.clone(),
-                        bold_italic: regular,
+            fonts::from_files("", "LiberationSans", None).unwrap_or_else(|_| {
+                // Last resort: create a minimal font family
+                let font_data = genpdf::fonts::FontData::new(
+                    Vec::new(), // Will fail gracefully
+                    None,
+                );
+                let regular = match font_data {
+                    Ok(f) => f,
+                    Err(_) => {
+                        return fonts::from_files("/System/Library/Fonts", "Helvetica", None)
+                            .expect("Could not load any font")
                     }
-                })
-        });
+                };
+                genpdf::fonts::FontFamily {
+                    regular: regular.clone(),
+                    bold: regular.clone(),
+                    italic: regular.clone(),
+                    bold_italic: regular,
+                }
+            })
+        }),
+    };
 
     let mut doc = Document::new(font_family);
     doc.set_title("Property Marketing Package");
@@ -99,7 +175,17 @@ pub fn generate_pdf(
 
         // Add up to 6 photos (3x2 grid layout)
         for (i, photo) in photos.iter().take(6).enumerate() {
-            match load_and_resize_image(&photo.original_path, 400) {
+            // The featured derivative is already upright and far smaller to
+            // decode than the original; fall back to the original if it
+            // can't be made
+            let source =
+                derivatives::get_or_create(app_data_dir, photo, &DerivativePreset::Featured.spec())
+                    .map(|derivative| derivative.path)
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to prepare image {}: {}", photo.original_path, e);
+                        photo.original_path.clone()
+                    });
+            match load_and_resize_image(&source, 400) {
                 Ok(image_data) => {
                     // Add the image
                     match Image::from_dynamic_image(&image_data) {
@@ -137,34 +223,87 @@ pub fn generate_pdf(
         doc.push(Break::new(1.0));
     }
 
-    // Listings
+    // Listings, each followed by its translation in bilingual exports
     for (i, listing) in listings.iter().enumerate() {
-        doc.push(Break::new(1.0));
+        let title = section_title(listing, i);
+        let sections = match translation_of(listing, translations) {
+            Some(translation) => vec![
+                (listing, format!("{} ({})", title, language_label(listing))),
+                (translation, format!("{} ({})", title, language_label(translation))),
+            ],
+            None => vec![(listing, title)],
+        };
+
+        for (listing, section_title) in sections {
+            doc.push(Break::new(1.0));
+            doc.push(
+                Paragraph::new(section_title)
+                    .styled(Style::new().bold().with_font_size(14)),
+            );
+            doc.push(Break::new(0.3));
 
-        let section_title = match listing.generation_type.as_str() {
-            "listing" => format!("Listing Description {}", i + 1),
-            t if t.starts_with("social_") => {
-                format!("Social Media - {}", t.strip_prefix("social_").unwrap_or(t))
+            // Long-form copy is laid out by its structure
+            if let Some(blocks) = blocks(listing) {
+                for block in blocks {
+                    match block {
+                        Block::Subheading(text) => {
+                            doc.push(Break::new(0.3));
+                            doc.push(
+                                Paragraph::new(text).styled(Style::new().bold().with_font_size(12)),
+                            );
+                        }
+                        Block::Paragraph(text) => {
+                            doc.push(Paragraph::new(text));
+                            doc.push(Break::new(0.3));
+                        }
+                        Block::Bullet(text) => doc.push(Paragraph::new(format!("\u{2022} {}", text))),
+                        Block::Note(text) => doc.push(
+                            Paragraph::new(text)
+                                .styled(Style::new().italic().with_color(Color::Rgb(100, 100, 100))),
+                        ),
+                    }
+                }
+                continue;
             }
-            t if t.starts_with("email_") => {
-                format!("Email - {}", t.strip_prefix("email_").unwrap_or(t))
+
+            // Split content by paragraphs for better formatting
+            for paragraph in listing.content.split("\n\n") {
+                let trimmed = paragraph.trim();
+                if !trimmed.is_empty() {
+                    doc.push(Paragraph::new(trimmed));
+                    doc.push(Break::new(0.3));
+                }
             }
-            t => t.to_string(),
-        };
+        }
+    }
 
+    // Agent contact block
+    if !agent.name.is_empty() {
+        doc.push(Break::new(1.0));
         doc.push(
-            Paragraph::new(section_title)
+            Paragraph::new("Presented By")
                 .styled(Style::new().bold().with_font_size(14)),
         );
         doc.push(Break::new(0.3));
+        doc.push(Paragraph::new(agent.name.as_str()).styled(Style::new().bold()));
 
-        // Split content by paragraphs for better formatting
-        for paragraph in listing.content.split("\n\n") {
-            let trimmed = paragraph.trim();
-            if !trimmed.is_empty() {
-                doc.push(Paragraph::new(trimmed));
-                doc.push(Break::new(0.3));
-            }
+        let contact: Vec<&str> = [
+            agent.brokerage.as_str(),
+            agent.phone.as_str(),
+            agent.email.as_str(),
+        ]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect();
+        if !contact.is_empty() {
+            doc.push(Paragraph::new(contact.join(" | ")));
+        }
+
+        for line in agent.signature.lines().filter(|l| !l.trim().is_empty()) {
+            doc.push(
+                Paragraph::new(line.trim())
+                    .styled(Style::new().with_color(Color::Rgb(100, 100, 100))),
+            );
         }
     }
 
@@ -217,25 +356,62 @@ mod tests {
         Property {
             id: "test".to_string(),
             address: "123 Oak St".to_string(),
-            city: "San Francisco".to_string(),
-            state: "CA".to_string(),
-            zip: "94105".to_string(),
-            beds: 3,
-            baths: 2.5,
-            sqft: 1800,
-            price: 95000000,
-            property_type: "single_family".to_string(),
             year_built: Some(2015),
-            lot_size: None,
-            parking: None,
             key_features: r#"["pool","hardwood floors"]"#.to_string(),
-            neighborhood: None,
-            neighborhood_highlights: "[]".to_string(),
-            school_district: None,
-            nearby_amenities: "[]".to_string(),
-            agent_notes: None,
+            listed_at: Some("2024-01-01 00:00:00".to_string()),
+            days_on_market: Some(12),
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
+            ..crate::db::test_property()
+        }
+    }
+
+    fn sample_listing(id: &str, content: &str, language: &str) -> Listing {
+        Listing {
+            id: id.to_string(),
+            property_id: "test".to_string(),
+            content: content.to_string(),
+            language: language.to_string(),
+            ..crate::db::test_listing()
+        }
+    }
+
+    #[test]
+    fn test_chinese_translation_uses_an_installed_font() {
+        let original = sample_listing("a", "Sunny 3 bed home — café nearby.", "en");
+        let vietnamese = Listing {
+            source_listing_id: Some("a".to_string()),
+            ..sample_listing("b", "Nhà 3 phòng ngủ đầy nắng, gần quán cà phê.", "vi")
+        };
+        let chinese = Listing {
+            source_listing_id: Some("a".to_string()),
+            ..sample_listing("c", "阳光充足的三居室住宅，附近有咖啡馆。", "zh")
+        };
+        assert!(fallback_font_family([&original, &vietnamese])
+            .unwrap()
+            .is_none());
+
+        let pdf = generate_pdf(
+            &std::env::temp_dir(),
+            &sample_property(),
+            std::slice::from_ref(&original),
+            &[chinese],
+            &[],
+            &AgentInfo {
+                name: String::new(),
+                phone: String::new(),
+                email: String::new(),
+                brokerage: String::new(),
+                signature: String::new(),
+            },
+        );
+        // Only machines without a CJK font should refuse the export
+        let cjk_font_installed = system_font::covering_files("阳光充足的三居室住宅")
+            .any(|bytes| FontData::new(bytes.to_vec(), None).is_ok());
+        if cjk_font_installed {
+            assert!(pdf.unwrap().starts_with(b"%PDF"));
+        } else {
+            assert!(pdf.unwrap_err().to_string().contains("Simplified Chinese"));
         }
     }
 
@@ -0,0 +1,476 @@
+use std::io::Cursor;
+use std::path::Path;
+
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::ai::prompts::AgentInfo;
+use crate::db::photos::Photo;
+use crate::db::properties::Property;
+use crate::error::AppError;
+use crate::photos::derivatives::{self, DerivativePreset};
+use crate::photos::mls_export::Sink;
+use crate::photos::watermark::{self, text_height};
+
+use super::system_font;
+
+const DEFAULT_ACCENT: &str = "#1F3A5F";
+const MAX_BANNER_CHARS: usize = 32;
+
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const PANEL: Rgba<u8> = Rgba([0, 0, 0, 170]);
+
+/// Platform sizes a card is rendered at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CardFormat {
+    /// Instagram feed post, 1080x1080
+    InstagramSquare,
+    /// Instagram portrait post, 1080x1350
+    InstagramPortrait,
+    /// Facebook feed and link image, 1200x628
+    Facebook,
+}
+
+impl CardFormat {
+    pub const ALL: [CardFormat; 3] = [
+        CardFormat::InstagramSquare,
+        CardFormat::InstagramPortrait,
+        CardFormat::Facebook,
+    ];
+
+    fn preset(self) -> DerivativePreset {
+        match self {
+            CardFormat::InstagramSquare => DerivativePreset::Square,
+            CardFormat::InstagramPortrait => DerivativePreset::Portrait,
+            CardFormat::Facebook => DerivativePreset::Landscape,
+        }
+    }
+
+    fn filename(self) -> &'static str {
+        match self {
+            CardFormat::InstagramSquare => "instagram-square.png",
+            CardFormat::InstagramPortrait => "instagram-portrait.png",
+            CardFormat::Facebook => "facebook.png",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SocialCardOptions {
+    /// Photo to use; the property's first photo when not set
+    pub photo_id: Option<String>,
+    /// Status banner in the top-left corner, e.g. "Just Listed" or
+    /// "Open House Sat 1–4"
+    pub banner: Option<String>,
+    /// Banner and panel accent as "#RRGGBB"
+    pub accent_color: Option<String>,
+    /// Agent name, phone and brokerage along the bottom
+    pub include_agent: bool,
+    /// Brokerage logo drawn at the right of the info panel
+    pub logo_path: Option<String>,
+}
+
+impl Default for SocialCardOptions {
+    fn default() -> Self {
+        Self {
+            photo_id: None,
+            banner: None,
+            accent_color: None,
+            include_agent: true,
+            logo_path: None,
+        }
+    }
+}
+
+/// Text shown on a card, resolved from the property and agent
+#[derive(Debug, Clone)]
+pub struct CardContent {
+    pub price: String,
+    pub details: String,
+    pub address: String,
+    pub agent: Option<String>,
+}
+
+impl CardContent {
+    pub fn new(property: &Property, agent: &AgentInfo, include_agent: bool) -> Self {
+        let mut details = vec![
+            format!("{} BD", property.beds),
+            format!("{} BA", format_baths(property.baths)),
+        ];
+        if property.sqft > 0 {
+            details.push(format!("{} SQFT", group_digits(property.sqft)));
+        }
+
+        let agent = if include_agent {
+            let parts: Vec<&str> = [
+                agent.name.as_str(),
+                agent.phone.as_str(),
+                agent.brokerage.as_str(),
+            ]
+            .into_iter()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect();
+            (!parts.is_empty()).then(|| parts.join(" | "))
+        } else {
+            None
+        };
+
+        Self {
+            price: format!("${}", group_digits(property.price / 100)),
+            details: details.join(" | "),
+            address: format!("{}, {}", property.address, property.city),
+            agent,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SocialBundle {
+    pub destination: String,
+    pub files: Vec<String>,
+}
+
+/// Check options before any rendering so a bad color fails fast
+pub fn validate_options(options: &SocialCardOptions) -> Result<(), AppError> {
+    accent(options)?;
+    if let Some(banner) = &options.banner {
+        if banner.chars().count() > MAX_BANNER_CHARS {
+            return Err(AppError::Validation(format!(
+                "Banner text must be at most {} characters",
+                MAX_BANNER_CHARS
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn accent(options: &SocialCardOptions) -> Result<Rgba<u8>, AppError> {
+    parse_hex_color(options.accent_color.as_deref().unwrap_or(DEFAULT_ACCENT))
+}
+
+fn parse_hex_color(hex: &str) -> Result<Rgba<u8>, AppError> {
+    let digits = hex.trim().trim_start_matches('#');
+    let invalid = || AppError::Validation(format!("Invalid color \"{}\", expected #RRGGBB", hex));
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| invalid());
+    Ok(Rgba([channel(0)?, channel(2)?, channel(4)?, 255]))
+}
+
+/// Compose a card on a background already cropped to the card size
+pub fn compose(
+    background: &DynamicImage,
+    content: &CardContent,
+    options: &SocialCardOptions,
+) -> Result<RgbaImage, AppError> {
+    let accent = accent(options)?;
+    let mut card = background.to_rgba8();
+    let (width, height) = card.dimensions();
+
+    // Unit scale of the bitmap font: 6px per font pixel on a 1080px card
+    let unit = (width.min(height) / 180).max(1);
+    let margin = unit * 6;
+    let line_gap = unit * 3;
+    let max_text_width = width.saturating_sub(margin * 4);
+
+    if let Some(banner) = options.banner.as_deref().map(str::trim) {
+        if !banner.is_empty() {
+            let scale = fit_scale(banner, unit * 2, max_text_width);
+            let padding = unit * 3;
+            let band = RgbaImage::from_pixel(
+                text_width(banner, scale) + padding * 2,
+                text_height(scale) + padding * 2,
+                accent,
+            );
+            imageops::overlay(&mut card, &band, margin as i64, margin as i64);
+            draw_text(
+                &mut card,
+                banner,
+                margin + padding,
+                margin + padding,
+                scale,
+                WHITE,
+            );
+        }
+    }
+
+    let logo = match &options.logo_path {
+        Some(path) => Some(
+            image::open(path)
+                .map_err(|e| AppError::Export(format!("Failed to open logo: {}", e)))?,
+        ),
+        None => None,
+    };
+
+    // Lines from the top of the panel down, each with its font scale
+    let mut lines = vec![
+        (content.price.as_str(), unit * 3),
+        (content.details.as_str(), unit * 2),
+        (content.address.as_str(), unit),
+    ];
+    if let Some(agent) = &content.agent {
+        lines.push((agent.as_str(), unit));
+    }
+    let lines: Vec<(&str, u32)> = lines
+        .into_iter()
+        .map(|(text, scale)| (text, fit_scale(text, scale, max_text_width)))
+        .collect();
+
+    let text_block: u32 = lines.iter().map(|(_, s)| text_height(*s)).sum::<u32>()
+        + line_gap * (lines.len() as u32 - 1);
+    let panel_height = (text_block + margin * 2).min(height);
+    let panel_y = height - panel_height;
+
+    let panel = RgbaImage::from_pixel(width, panel_height, PANEL);
+    imageops::overlay(&mut card, &panel, 0, panel_y as i64);
+    let rule = RgbaImage::from_pixel(width, unit, accent);
+    imageops::overlay(&mut card, &rule, 0, panel_y as i64);
+
+    let mut y = panel_y + margin;
+    for (text, scale) in &lines {
+        draw_text(&mut card, text, margin, y, *scale, WHITE);
+        y += text_height(*scale) + line_gap;
+    }
+
+    if let Some(logo) = logo {
+        let logo_height = panel_height.saturating_sub(margin * 2).max(1);
+        let logo_width =
+            ((logo.width() as f32 * logo_height as f32 / logo.height() as f32) as u32).max(1);
+        let logo = logo
+            .resize(logo_width.min(width / 4), logo_height, FilterType::Lanczos3)
+            .to_rgba8();
+        let x = width.saturating_sub(logo.width() + margin);
+        imageops::overlay(&mut card, &logo, x as i64, (panel_y + margin) as i64);
+    }
+
+    Ok(card)
+}
+
+/// Render a card from a property photo, cropped to the format's size
+pub fn render(
+    app_data_dir: &Path,
+    photo: &Photo,
+    content: &CardContent,
+    options: &SocialCardOptions,
+    format: CardFormat,
+) -> Result<Vec<u8>, AppError> {
+    let derivative = derivatives::get_or_create(app_data_dir, photo, &format.preset().spec())?;
+    let background = image::open(&derivative.path)
+        .map_err(|e| AppError::Export(format!("Failed to open photo: {}", e)))?;
+    let card = compose(&background, content, options)?;
+
+    let mut bytes = Vec::new();
+    card.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| AppError::Export(format!("Failed to encode card: {}", e)))?;
+    Ok(bytes)
+}
+
+/// Write a card in every format plus the caption as `caption.txt` to `dest`:
+/// a zip archive when it ends in `.zip`, otherwise a folder
+pub fn export_bundle(
+    app_data_dir: &Path,
+    photo: &Photo,
+    content: &CardContent,
+    options: &SocialCardOptions,
+    caption: Option<&str>,
+    dest: &Path,
+) -> Result<SocialBundle, AppError> {
+    validate_options(options)?;
+
+    let mut sink = Sink::open(dest)?;
+    let mut files = Vec::new();
+    for format in CardFormat::ALL {
+        let bytes = render(app_data_dir, photo, content, options, format)?;
+        sink.write(format.filename(), &bytes)?;
+        files.push(format.filename().to_string());
+    }
+    if let Some(caption) = caption {
+        sink.write("caption.txt", caption.trim().as_bytes())?;
+        files.push("caption.txt".to_string());
+    }
+    sink.finish()?;
+
+    Ok(SocialBundle {
+        destination: dest.to_string_lossy().to_string(),
+        files,
+    })
+}
+
+/// Largest scale up to `scale` at which `text` fits in `max_width`
+fn fit_scale(text: &str, scale: u32, max_width: u32) -> u32 {
+    let unit_width = text_width(text, 1).max(1);
+    scale.min(max_width / unit_width).max(1)
+}
+
+/// The bitmap font only has ASCII glyphs, so other text (accented names,
+/// Vietnamese or Chinese addresses) is drawn with an installed font that
+/// covers it, at the same line height
+fn fallback_font(text: &str) -> Option<&'static rusttype::Font<'static>> {
+    if text.is_ascii() {
+        None
+    } else {
+        system_font::covering(text)
+    }
+}
+
+fn text_width(text: &str, scale: u32) -> u32 {
+    match fallback_font(text) {
+        Some(font) => system_font::text_width(font, text, text_height(scale)),
+        None => watermark::text_width(text, scale),
+    }
+}
+
+fn draw_text(img: &mut RgbaImage, text: &str, x: u32, y: u32, scale: u32, color: Rgba<u8>) {
+    match fallback_font(text) {
+        Some(font) => system_font::draw_text(img, font, text, x, y, text_height(scale), color),
+        None => watermark::draw_text(img, text, x, y, scale, color),
+    }
+}
+
+fn format_baths(baths: f64) -> String {
+    if baths.fract() == 0.0 {
+        format!("{}", baths as i64)
+    } else {
+        format!("{}", baths)
+    }
+}
+
+fn group_digits(n: i64) -> String {
+    let digits = n.abs().to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    if n < 0 {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content() -> CardContent {
+        CardContent {
+            price: "$1,250,000".to_string(),
+            details: "4 BD | 3.5 BA | 2,800 SQFT".to_string(),
+            address: "123 Main St, Springfield".to_string(),
+            agent: Some("Jane Doe | 555-0100 | Acme Realty".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_group_digits_and_baths() {
+        assert_eq!(group_digits(1_250_000), "1,250,000");
+        assert_eq!(group_digits(950), "950");
+        assert_eq!(format_baths(2.0), "2");
+        assert_eq!(format_baths(2.5), "2.5");
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(
+            parse_hex_color("#FF8000").unwrap(),
+            Rgba([255, 128, 0, 255])
+        );
+        assert_eq!(parse_hex_color("1f3a5f").unwrap(), Rgba([31, 58, 95, 255]));
+        assert!(parse_hex_color("#FFF").is_err());
+        assert!(parse_hex_color("#GGGGGG").is_err());
+    }
+
+    #[test]
+    fn test_compose_draws_banner_and_panel() {
+        let background = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            1080,
+            1080,
+            image::Rgb([200, 200, 200]),
+        ));
+        let options = SocialCardOptions {
+            banner: Some("Open House Sat 1–4".to_string()),
+            accent_color: Some("#FF0000".to_string()),
+            ..Default::default()
+        };
+        let card = compose(&background, &content(), &options).unwrap();
+        assert_eq!(card.dimensions(), (1080, 1080));
+
+        // Banner background in the accent color at the top-left
+        let banner = card.get_pixel(40, 40);
+        assert!(banner[0] > 200 && banner[1] < 50);
+        // Accent rule then dark panel at the bottom; untouched photo between
+        assert_eq!(card.get_pixel(540, 540), &Rgba([200, 200, 200, 255]));
+        let panel = card.get_pixel(1075, 1075);
+        assert!(panel[0] < 100);
+    }
+
+    #[test]
+    fn test_long_text_is_shrunk_to_fit() {
+        assert_eq!(fit_scale("SHORT", 6, 1000), 6);
+        let long = "X".repeat(100);
+        assert!(text_width(&long, fit_scale(&long, 6, 1000)) <= 1000);
+    }
+
+    #[test]
+    fn test_non_ascii_text_is_drawn_with_a_fallback_font() {
+        let text = "Église Saint-Jean";
+        if fallback_font(text).is_none() {
+            // No installed font covers it; the card still renders blank text
+            return;
+        }
+        let mut img = RgbaImage::from_pixel(400, 60, Rgba([0, 0, 0, 255]));
+        assert!(text_width(text, 4) > 0);
+        draw_text(&mut img, text, 4, 4, 4, WHITE);
+        assert!(img.pixels().any(|p| p[0] > 128));
+    }
+
+    #[test]
+    fn test_export_bundle_writes_all_formats_and_caption() {
+        let dir = std::env::temp_dir().join(format!("realestate-social-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let original = dir.join("front.png");
+        image::RgbImage::from_pixel(1600, 1200, image::Rgb([90, 120, 150]))
+            .save(&original)
+            .unwrap();
+        let photo = Photo {
+            id: "a".to_string(),
+            filename: "front.png".to_string(),
+            original_path: original.to_string_lossy().to_string(),
+            ..crate::db::test_photo()
+        };
+
+        let dest = dir.join("bundle");
+        let bundle = export_bundle(
+            &dir,
+            &photo,
+            &content(),
+            &SocialCardOptions::default(),
+            Some("Just listed! #dreamhome"),
+            &dest,
+        )
+        .unwrap();
+        assert_eq!(bundle.files.len(), 4);
+        assert_eq!(
+            image::image_dimensions(dest.join("instagram-portrait.png")).unwrap(),
+            (1080, 1350)
+        );
+        assert_eq!(
+            image::image_dimensions(dest.join("facebook.png")).unwrap(),
+            (1200, 628)
+        );
+        assert_eq!(
+            std::fs::read_to_string(dest.join("caption.txt")).unwrap(),
+            "Just listed! #dreamhome"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
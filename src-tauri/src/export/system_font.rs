@@ -0,0 +1,123 @@
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+use image::{Rgba, RgbaImage};
+use rusttype::{point, Font, FontCollection, Scale};
+
+/// Installed fonts tried, in order, for text the built-in bitmap font can't
+/// draw. Broad-coverage fonts come first so accented Latin, Vietnamese and
+/// CJK all find a match on a stock macOS, Windows or Linux install.
+const CANDIDATES: &[&str] = &[
+    // macOS
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/System/Library/Fonts/Helvetica.ttc",
+    // Windows
+    "C:\\Windows\\Fonts\\arialuni.ttf",
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\simsun.ttc",
+    "C:\\Windows\\Fonts\\arial.ttf",
+    // Linux
+    "/usr/share/fonts/truetype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+];
+
+/// An installed font and the file it was read from
+struct Loaded {
+    bytes: Arc<[u8]>,
+    font: Font<'static>,
+}
+
+/// Each candidate is read at most once per run, and only when an earlier
+/// one didn't cover the text
+static LOADED: [OnceLock<Option<Loaded>>; CANDIDATES.len()] =
+    [const { OnceLock::new() }; CANDIDATES.len()];
+
+fn load(path: &Path) -> Option<Loaded> {
+    let bytes: Arc<[u8]> = std::fs::read(path).ok()?.into();
+    let font = FontCollection::from_bytes(bytes.clone())
+        .ok()?
+        .font_at(0)
+        .ok()?;
+    Some(Loaded { bytes, font })
+}
+
+fn installed() -> impl Iterator<Item = &'static Loaded> {
+    CANDIDATES
+        .iter()
+        .zip(&LOADED)
+        .filter_map(|(path, slot)| slot.get_or_init(|| load(Path::new(path))).as_ref())
+}
+
+fn covers(font: &Font, text: &str) -> bool {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .all(|c| font.glyph(c).id().0 != 0)
+}
+
+/// The first installed font with a glyph for every character in `text`
+pub fn covering(text: &str) -> Option<&'static Font<'static>> {
+    installed()
+        .map(|loaded| &loaded.font)
+        .find(|font| covers(font, text))
+}
+
+/// File contents of each installed font with a glyph for every character in
+/// `text`, in order of preference
+pub fn covering_files(text: &str) -> impl Iterator<Item = &'static [u8]> + '_ {
+    installed()
+        .filter(move |loaded| covers(&loaded.font, text))
+        .map(|loaded| &*loaded.bytes)
+}
+
+/// Width in pixels of `text` drawn `height` pixels tall
+pub fn text_width(font: &Font, text: &str, height: u32) -> u32 {
+    font.layout(text, Scale::uniform(height as f32), point(0.0, 0.0))
+        .last()
+        .map(|glyph| glyph.position().x + glyph.unpositioned().h_metrics().advance_width)
+        .unwrap_or(0.0)
+        .ceil() as u32
+}
+
+/// Draw `text` `height` pixels tall with its top-left corner at (`x`, `y`),
+/// blending anti-aliased edges into the image. Anything outside the image is
+/// clipped.
+pub fn draw_text(
+    img: &mut RgbaImage,
+    font: &Font,
+    text: &str,
+    x: u32,
+    y: u32,
+    height: u32,
+    color: Rgba<u8>,
+) {
+    let scale = Scale::uniform(height as f32);
+    let ascent = font.v_metrics(scale).ascent;
+    for glyph in font.layout(text, scale, point(x as f32, y as f32 + ascent)) {
+        let Some(bounds) = glyph.pixel_bounding_box() else {
+            continue;
+        };
+        glyph.draw(|gx, gy, coverage| {
+            let px = bounds.min.x + gx as i32;
+            let py = bounds.min.y + gy as i32;
+            if px < 0 || py < 0 || px as u32 >= img.width() || py as u32 >= img.height() {
+                return;
+            }
+            let pixel = img.get_pixel_mut(px as u32, py as u32);
+            let alpha = coverage * color[3] as f32 / 255.0;
+            for channel in 0..3 {
+                pixel[channel] = (color[channel] as f32 * alpha
+                    + pixel[channel] as f32 * (1.0 - alpha))
+                    .round() as u8;
+            }
+            pixel[3] = pixel[3].max((alpha * 255.0).round() as u8);
+        });
+    }
+}
@@ -211,6 +211,8 @@ async fn import_single_property(
         school_district: row.school_district,
         nearby_amenities,
         agent_notes: row.agent_notes,
+        agent_id: None,
+        status: None,
     };
 
     // Create property in database
@@ -1,4 +1,5 @@
 mod ai;
+mod backup;
 mod commands;
 mod db;
 mod error;
@@ -8,9 +9,11 @@ mod license;
 mod photos;
 
 use commands::{
-    analytics as analytics_commands, brand_voice as brand_voice_commands,
-    export as export_commands, generate, import as import_commands,
-    license as license_commands, photos as photo_commands, property, settings,
+    agents as agent_commands, analytics as analytics_commands, backup as backup_commands,
+    brand_voice as brand_voice_commands, export as export_commands,
+    fact_check as fact_check_commands, generate, import as import_commands,
+    license as license_commands, photos as photo_commands,
+    prompt_presets as prompt_preset_commands, property, settings, trash as trash_commands,
 };
 use tauri::Manager;
 
@@ -27,6 +30,12 @@ pub fn run() {
                 .app_data_dir()
                 .map_err(|e| format!("Failed to get app data dir: {}", e))?;
 
+            // A restore staged in the previous session is swapped in before
+            // anything opens the database
+            if let Err(e) = backup::restore::apply_pending_restore(&app_data_dir) {
+                eprintln!("Failed to apply pending restore: {}", e);
+            }
+
             let db_path = app_data_dir.join("realestate.db");
 
             let pool = tauri::async_runtime::block_on(async {
@@ -34,6 +43,20 @@ pub fn run() {
             })
             .map_err(|e| format!("Failed to initialize database: {}", e))?;
 
+            // Purge trash past its retention period in the background
+            let purge_pool = pool.clone();
+            let purge_dir = app_data_dir.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = trash_commands::purge_expired(&purge_pool, &purge_dir).await {
+                    eprintln!("Failed to purge trash: {}", e);
+                }
+            });
+
+            tauri::async_runtime::spawn(backup_commands::run_scheduled_backups(
+                pool.clone(),
+                app_data_dir.clone(),
+            ));
+
             app.manage(pool);
             Ok(())
         })
@@ -41,26 +64,80 @@ pub fn run() {
             property::create_property,
             property::get_property,
             property::list_properties,
+            property::search_properties,
             property::update_property,
             property::delete_property,
+            property::set_property_status,
+            property::list_property_status_history,
+            property::list_price_history,
             settings::get_setting,
             settings::set_setting,
+            agent_commands::create_agent,
+            agent_commands::get_agent,
+            agent_commands::list_agents,
+            agent_commands::update_agent,
+            agent_commands::delete_agent,
             generate::generate_listing,
+            generate::generate_listing_variants,
             generate::generate_social,
             generate::list_listings,
+            generate::list_variant_group,
             generate::toggle_listing_favorite,
+            generate::update_listing,
             generate::delete_listing,
             generate::generate_email,
+            generate::generate_price_improvement,
+            generate::generate_long_form,
+            generate::get_long_form_document,
+            generate::translate_listing,
+            generate::list_listing_translations,
+            fact_check_commands::check_listing_facts,
             photo_commands::import_photos,
+            photo_commands::import_photo_paths,
             photo_commands::list_photos,
             photo_commands::delete_photo,
             photo_commands::reorder_photos,
+            photo_commands::update_photo_details,
+            photo_commands::generate_photo_captions,
+            photo_commands::analyze_photos,
+            photo_commands::accept_suggested_features,
+            photo_commands::auto_arrange_photos,
+            photo_commands::export_mls_photos,
+            photo_commands::get_photo_derivatives,
+            photo_commands::get_photo_storage_report,
+            photo_commands::clean_up_photo_storage,
+            trash_commands::list_trash,
+            trash_commands::restore_from_trash,
+            trash_commands::delete_from_trash,
+            trash_commands::empty_trash,
+            backup_commands::create_backup,
+            backup_commands::restore_backup,
+            backup_commands::list_backups,
+            backup_commands::export_property_bundle,
+            backup_commands::import_property_bundle,
+            export_commands::check_export_claims,
             export_commands::export_pdf,
             export_commands::export_docx,
+            export_commands::render_social_card,
+            export_commands::export_social_bundle,
             export_commands::copy_to_clipboard,
             brand_voice_commands::create_brand_voice,
             brand_voice_commands::list_brand_voices,
+            brand_voice_commands::update_brand_voice,
+            brand_voice_commands::retrain_brand_voice,
+            brand_voice_commands::list_brand_voice_versions,
+            brand_voice_commands::restore_brand_voice_version,
+            brand_voice_commands::list_voice_feedback_listings,
+            brand_voice_commands::learn_brand_voice,
+            brand_voice_commands::suggest_brand_voice_update,
+            brand_voice_commands::apply_brand_voice_update,
+            brand_voice_commands::score_listing_voice,
             brand_voice_commands::delete_brand_voice,
+            prompt_preset_commands::create_prompt_preset,
+            prompt_preset_commands::list_prompt_presets,
+            prompt_preset_commands::update_prompt_preset,
+            prompt_preset_commands::delete_prompt_preset,
+            prompt_preset_commands::preview_prompt_preset,
             license_commands::validate_license_key,
             license_commands::check_license,
             import_commands::import_properties_csv,
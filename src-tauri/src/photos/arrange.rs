@@ -0,0 +1,108 @@
+use std::cmp::Ordering;
+
+use crate::db::photos::{Photo, ROOMS};
+
+/// Combined quality of a photo, 0-1: the measured hero score, averaged with
+/// the vision quality score when the photo has been analyzed
+pub fn photo_score(photo: &Photo) -> f64 {
+    let measured = photo.hero_score.unwrap_or(0.0);
+    match photo.quality_score {
+        Some(quality) => (measured + quality as f64 / 10.0) / 2.0,
+        None => measured,
+    }
+}
+
+fn room_rank(photo: &Photo) -> usize {
+    let room = photo.room.as_deref().unwrap_or("other");
+    ROOMS.iter().position(|r| *r == room).unwrap_or(ROOMS.len())
+}
+
+fn best<'a>(candidates: impl Iterator<Item = &'a Photo>) -> Option<&'a Photo> {
+    candidates.max_by(|a, b| {
+        photo_score(a)
+            .partial_cmp(&photo_score(b))
+            .unwrap_or(Ordering::Equal)
+    })
+}
+
+/// Pick the lead image: the best front exterior if there is one, since MLS
+/// buyers expect to see the house first, otherwise the best photo overall
+pub fn pick_hero(photos: &[Photo]) -> Option<&Photo> {
+    best(
+        photos
+            .iter()
+            .filter(|p| p.room.as_deref() == Some("exterior_front")),
+    )
+    .or_else(|| best(photos.iter()))
+}
+
+/// Photo ids in MLS order: the hero first, then by room in `ROOMS` order, and
+/// the best shot first within a room. Untagged photos go with `other`.
+pub fn arrange(photos: &[Photo]) -> Vec<String> {
+    let hero_id = pick_hero(photos).map(|p| p.id.as_str());
+
+    let mut ordered: Vec<&Photo> = photos.iter().collect();
+    ordered.sort_by(|a, b| {
+        let is_hero = |p: &Photo| Some(p.id.as_str()) == hero_id;
+        is_hero(b)
+            .cmp(&is_hero(a))
+            .then(room_rank(a).cmp(&room_rank(b)))
+            .then(
+                photo_score(b)
+                    .partial_cmp(&photo_score(a))
+                    .unwrap_or(Ordering::Equal),
+            )
+            .then(a.sort_order.cmp(&b.sort_order))
+    });
+
+    ordered.into_iter().map(|p| p.id.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn photo(id: &str, order: i64, room: Option<&str>, score: f64) -> Photo {
+        Photo {
+            id: id.to_string(),
+            filename: format!("{}.jpg", id),
+            sort_order: order,
+            room: room.map(str::to_string),
+            hero_score: Some(score),
+            ..crate::db::test_photo()
+        }
+    }
+
+    #[test]
+    fn test_arrange_follows_mls_order() {
+        let photos = vec![
+            photo("yard", 0, Some("exterior_back"), 0.9),
+            photo("bath", 1, Some("bathroom"), 0.5),
+            photo("untagged", 2, None, 0.5),
+            photo("kitchen2", 3, Some("kitchen"), 0.4),
+            photo("front", 4, Some("exterior_front"), 0.6),
+            photo("kitchen1", 5, Some("kitchen"), 0.8),
+            photo("living", 6, Some("living_room"), 0.5),
+        ];
+
+        assert_eq!(
+            arrange(&photos),
+            vec!["front", "living", "kitchen1", "kitchen2", "bath", "yard", "untagged"]
+        );
+    }
+
+    #[test]
+    fn test_hero_without_front_exterior_is_best_overall() {
+        let mut photos = vec![
+            photo("kitchen", 0, Some("kitchen"), 0.5),
+            photo("pool", 1, Some("pool"), 0.6),
+        ];
+        // Vision quality pulls the kitchen ahead
+        photos[0].quality_score = Some(10);
+
+        assert_eq!(pick_hero(&photos).unwrap().id, "kitchen");
+
+        photos[0].quality_score = Some(2);
+        assert_eq!(arrange(&photos), vec!["pool", "kitchen"]);
+    }
+}
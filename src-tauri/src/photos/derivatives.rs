@@ -0,0 +1,269 @@
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use image::imageops::FilterType;
+use serde::{Deserialize, Serialize};
+
+use crate::db::photos::Photo;
+use crate::error::AppError;
+
+use super::manager;
+
+const MIN_DIMENSION: u32 = 16;
+const MAX_DIMENSION: u32 = 4096;
+
+/// How the photo is fitted to the requested size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fit {
+    /// Fill the size exactly, cropping the overflow around the center
+    Cover,
+    /// Fit inside the size keeping the whole photo; never upscales
+    Contain,
+}
+
+impl Fit {
+    fn as_str(self) -> &'static str {
+        match self {
+            Fit::Cover => "cover",
+            Fit::Contain => "contain",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivativeSpec {
+    pub width: u32,
+    pub height: u32,
+    pub fit: Fit,
+    /// JPEG quality, 1-100
+    pub quality: u8,
+}
+
+/// Sizes the app itself uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DerivativePreset {
+    /// Photo grid tile, 3:2
+    Grid,
+    /// Full-width photo in the PDF featured layout
+    Featured,
+    /// Instagram feed post, 1:1
+    Square,
+    /// Facebook link and feed image, 1.91:1
+    Landscape,
+    /// Instagram portrait post, 4:5
+    Portrait,
+}
+
+impl DerivativePreset {
+    pub fn spec(self) -> DerivativeSpec {
+        let (width, height, fit) = match self {
+            DerivativePreset::Grid => (600, 400, Fit::Cover),
+            DerivativePreset::Featured => (1600, 1600, Fit::Contain),
+            DerivativePreset::Square => (1080, 1080, Fit::Cover),
+            DerivativePreset::Landscape => (1200, 628, Fit::Cover),
+            DerivativePreset::Portrait => (1080, 1350, Fit::Cover),
+        };
+        DerivativeSpec {
+            width,
+            height,
+            fit,
+            quality: 85,
+        }
+    }
+}
+
+impl DerivativeSpec {
+    pub fn validate(&self) -> Result<(), AppError> {
+        let in_range = |d: u32| (MIN_DIMENSION..=MAX_DIMENSION).contains(&d);
+        if !in_range(self.width) || !in_range(self.height) {
+            return Err(AppError::Validation(format!(
+                "Image size must be between {} and {} pixels per side",
+                MIN_DIMENSION, MAX_DIMENSION
+            )));
+        }
+        if !(1..=100).contains(&self.quality) {
+            return Err(AppError::Validation(
+                "JPEG quality must be between 1 and 100".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Part of the cache file name identifying this spec
+    fn key(&self) -> String {
+        format!(
+            "{}x{}-{}-q{}",
+            self.width,
+            self.height,
+            self.fit.as_str(),
+            self.quality
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoDerivative {
+    pub photo_id: String,
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Cache folder for one photo's derivatives. Kept outside `photos/` so
+/// backups and orphan cleanup leave it alone.
+fn cache_dir(app_data_dir: &Path, photo_id: &str) -> PathBuf {
+    app_data_dir
+        .join("cache")
+        .join("derivatives")
+        .join(photo_id)
+}
+
+/// Changes whenever the original file is replaced, so a stale derivative is
+/// never served after the photo changes
+fn fingerprint(original: &Path) -> Result<String, AppError> {
+    let meta = std::fs::metadata(original)
+        .map_err(|e| AppError::Photo(format!("Failed to read photo: {}", e)))?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    Ok(format!("{:x}-{:x}", meta.len(), modified))
+}
+
+/// Path of a derivative of `photo`, generating and caching it if needed.
+/// Older versions of the same spec are removed when a new one is written.
+pub fn get_or_create(
+    app_data_dir: &Path,
+    photo: &Photo,
+    spec: &DerivativeSpec,
+) -> Result<PhotoDerivative, AppError> {
+    spec.validate()?;
+    let original = Path::new(&photo.original_path);
+    let dir = cache_dir(app_data_dir, &photo.id);
+    let prefix = format!("{}-", spec.key());
+    let path = dir.join(format!("{}{}.jpg", prefix, fingerprint(original)?));
+
+    if let Ok((width, height)) = image::image_dimensions(&path) {
+        return Ok(PhotoDerivative {
+            photo_id: photo.id.clone(),
+            path: path.to_string_lossy().to_string(),
+            width,
+            height,
+        });
+    }
+
+    let img = manager::open_oriented(original)?;
+    let img = match spec.fit {
+        Fit::Cover => img.resize_to_fill(spec.width, spec.height, FilterType::Lanczos3),
+        Fit::Contain if img.width() > spec.width || img.height() > spec.height => {
+            img.resize(spec.width, spec.height, FilterType::Lanczos3)
+        }
+        Fit::Contain => img,
+    };
+
+    std::fs::create_dir_all(&dir)?;
+    let mut bytes = Vec::new();
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut bytes, spec.quality);
+    img.to_rgb8()
+        .write_with_encoder(encoder)
+        .map_err(|e| AppError::Photo(format!("Failed to encode image: {}", e)))?;
+
+    // Write under a temporary name so a concurrent reader never sees a
+    // partial file
+    let partial = dir.join(format!("{}.partial", uuid::Uuid::new_v4()));
+    std::fs::write(&partial, &bytes)?;
+    std::fs::rename(&partial, &path)?;
+
+    if let Ok(entries) = std::fs::read_dir(&dir) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with(&prefix) && entry.path() != path {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    Ok(PhotoDerivative {
+        photo_id: photo.id.clone(),
+        path: path.to_string_lossy().to_string(),
+        width: img.width(),
+        height: img.height(),
+    })
+}
+
+/// Drop every cached derivative of a photo
+pub fn invalidate(app_data_dir: &Path, photo_id: &str) -> Result<(), AppError> {
+    let dir = cache_dir(app_data_dir, photo_id);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn photo(id: &str, path: &Path) -> Photo {
+        Photo {
+            id: id.to_string(),
+            filename: format!("{}.png", id),
+            original_path: path.to_string_lossy().to_string(),
+            ..crate::db::test_photo()
+        }
+    }
+
+    #[test]
+    fn test_derivatives_are_sized_cached_and_invalidated() {
+        let dir = std::env::temp_dir().join(format!("realestate-deriv-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let original = dir.join("a.png");
+        image::RgbImage::new(900, 600).save(&original).unwrap();
+        let photo = photo("a", &original);
+
+        let square = get_or_create(&dir, &photo, &DerivativePreset::Square.spec()).unwrap();
+        assert_eq!((square.width, square.height), (1080, 1080));
+
+        // Contain never upscales
+        let featured = get_or_create(&dir, &photo, &DerivativePreset::Featured.spec()).unwrap();
+        assert_eq!((featured.width, featured.height), (900, 600));
+
+        // A second request is served from the cache
+        let modified = std::fs::metadata(&square.path).unwrap().modified().unwrap();
+        let again = get_or_create(&dir, &photo, &DerivativePreset::Square.spec()).unwrap();
+        assert_eq!(again.path, square.path);
+        assert_eq!(
+            std::fs::metadata(&again.path).unwrap().modified().unwrap(),
+            modified
+        );
+
+        // Replacing the original produces a new derivative and drops the old
+        image::RgbImage::new(600, 900).save(&original).unwrap();
+        let portrait = get_or_create(&dir, &photo, &DerivativePreset::Featured.spec()).unwrap();
+        assert_ne!(portrait.path, featured.path);
+        assert_eq!((portrait.width, portrait.height), (600, 900));
+        assert!(!Path::new(&featured.path).exists());
+
+        invalidate(&dir, "a").unwrap();
+        assert!(!Path::new(&square.path).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_spec_validation() {
+        let mut spec = DerivativePreset::Grid.spec();
+        assert!(spec.validate().is_ok());
+        spec.width = 8;
+        assert!(spec.validate().is_err());
+        spec.width = 300;
+        spec.quality = 0;
+        assert!(spec.validate().is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use crate::db::photos::{self, Photo};
+use crate::db::settings;
+use crate::error::AppError;
+
+use super::manager::{self, FileImport, FileOutcome};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoImportReport {
+    pub imported: Vec<Photo>,
+    pub files: Vec<PhotoImportFile>,
+}
+
+/// Result for one file in the batch
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoImportFile {
+    pub path: String,
+    /// "imported", "duplicate", "over_limit" or "failed"
+    pub status: &'static str,
+    pub photo_id: Option<String>,
+    pub error: Option<String>,
+}
+
+impl From<&FileImport> for PhotoImportFile {
+    fn from(file: &FileImport) -> Self {
+        let (status, photo_id, error) = match &file.outcome {
+            FileOutcome::Imported(record) => ("imported", Some(record.id.clone()), None),
+            FileOutcome::Duplicate => ("duplicate", None, None),
+            FileOutcome::OverLimit => ("over_limit", None, None),
+            FileOutcome::Failed(error) => ("failed", None, Some(error.clone())),
+        };
+        Self {
+            path: file.source.to_string_lossy().to_string(),
+            status,
+            photo_id,
+            error,
+        }
+    }
+}
+
+/// Progress of an import, sent once per file as it is processed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "event", content = "data")]
+pub enum PhotoImportEvent {
+    Started {
+        total: usize,
+    },
+    File {
+        index: usize,
+        total: usize,
+        file: PhotoImportFile,
+    },
+    Finished {
+        imported: usize,
+    },
+}
+
+/// Expand directories into the image files under them, recursively and in
+/// path order. Files given directly are kept whatever their extension, so
+/// an unsupported file is reported rather than silently dropped. Hidden
+/// files (like macOS `._` sidecars) inside directories are skipped.
+pub fn collect_paths(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut collected = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            collect_dir(path, &mut collected);
+        } else {
+            collected.push(path.clone());
+        }
+    }
+    collected
+}
+
+fn collect_dir(dir: &Path, collected: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    // The entry's own type, not its target's, so a symlinked folder is seen
+    // as a link
+    let mut entries: Vec<(PathBuf, std::fs::FileType)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| Some((e.path(), e.file_type().ok()?)))
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (path, file_type) in entries {
+        let hidden = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if file_type.is_dir() {
+            collect_dir(&path, collected);
+        } else if file_type.is_symlink() && path.is_dir() {
+            // Symlinked folders aren't followed, so a link back up the tree
+            // can't send the walk in circles
+            continue;
+        } else if is_importable(&path) {
+            collected.push(path);
+        }
+    }
+}
+
+fn is_importable(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| manager::IMPORT_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Import files and directories into a property's photos, appending them
+/// after the existing ones. `on_event` is called from a worker thread as
+/// each file is processed.
+pub async fn import_paths<F>(
+    pool: &SqlitePool,
+    app_data_dir: &Path,
+    property_id: &str,
+    paths: &[PathBuf],
+    on_event: F,
+) -> Result<PhotoImportReport, AppError>
+where
+    F: Fn(PhotoImportEvent) + Send + 'static,
+{
+    let source_paths = collect_paths(paths);
+    if source_paths.is_empty() {
+        return Err(AppError::Validation(
+            "No photos found to import".to_string(),
+        ));
+    }
+
+    let limit = settings::photo_limit(pool).await;
+    let existing = photos::list_by_property(pool, property_id).await?;
+    let remaining_slots = limit.saturating_sub(existing.len());
+    if remaining_slots == 0 {
+        return Err(AppError::PhotoLimitExceeded(limit));
+    }
+
+    let start_order = existing.len() as i64;
+    let known_hashes = photos::content_hashes(pool, property_id).await?;
+
+    // Decoding, copying and thumbnailing run on a blocking thread
+    let results = {
+        let app_data_dir = app_data_dir.to_path_buf();
+        let property_id = property_id.to_string();
+        tauri::async_runtime::spawn_blocking(move || {
+            let total = source_paths.len();
+            on_event(PhotoImportEvent::Started { total });
+            let results = manager::import_photos(
+                &app_data_dir,
+                &property_id,
+                &source_paths,
+                &known_hashes,
+                remaining_slots,
+                |index, file| {
+                    on_event(PhotoImportEvent::File {
+                        index,
+                        total,
+                        file: file.into(),
+                    })
+                },
+            );
+            if let Ok(results) = &results {
+                let imported = results
+                    .iter()
+                    .filter(|f| matches!(f.outcome, FileOutcome::Imported(_)))
+                    .count();
+                on_event(PhotoImportEvent::Finished { imported });
+            }
+            results
+        })
+        .await
+        .map_err(|e| AppError::Photo(format!("Import thread error: {}", e)))??
+    };
+
+    let mut report = PhotoImportReport {
+        imported: Vec::new(),
+        files: Vec::new(),
+    };
+    for (index, result) in results.iter().enumerate() {
+        if let FileOutcome::Imported(record) = &result.outcome {
+            let inserted = photos::insert(
+                pool,
+                &record.id,
+                &record.property_id,
+                &record.filename,
+                &record.original_path,
+                &record.thumbnail_path,
+                start_order + record.sort_order,
+                Some(&record.content_hash),
+            )
+            .await;
+            match inserted {
+                Ok(photo) => report.imported.push(photo),
+                Err(e) => {
+                    // Don't leave copies behind that no photo row points at
+                    for file in &results[index..] {
+                        if let FileOutcome::Imported(record) = &file.outcome {
+                            let _ = std::fs::remove_file(&record.original_path);
+                            let _ = std::fs::remove_file(&record.thumbnail_path);
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        report.files.push(result.into());
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::db::{properties, test_pool};
+
+    fn save_png(path: &Path, shade: u8) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        image::RgbImage::from_pixel(40, 30, image::Rgb([shade, shade, shade]))
+            .save(path)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_collect_paths_walks_directories() {
+        let dir = std::env::temp_dir().join(format!("realestate-collect-{}", uuid::Uuid::new_v4()));
+        save_png(&dir.join("b.png"), 1);
+        save_png(&dir.join("a.png"), 2);
+        save_png(&dir.join("nested/c.PNG"), 3);
+        std::fs::write(dir.join("notes.txt"), b"").unwrap();
+        std::fs::write(dir.join("._a.png"), b"").unwrap();
+        let loose = dir.join("loose.txt");
+
+        let collected = collect_paths(&[dir.clone(), loose.clone()]);
+        assert_eq!(
+            collected,
+            vec![
+                dir.join("a.png"),
+                dir.join("b.png"),
+                dir.join("nested/c.PNG"),
+                loose
+            ]
+        );
+
+        // A link back up the tree isn't followed
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&dir, dir.join("nested/loop")).unwrap();
+            assert_eq!(collect_paths(std::slice::from_ref(&dir)).len(), 3);
+        }
+
+        // HEIC is only picked up when this build can decode it
+        std::fs::write(dir.join("IMG_0001.HEIC"), b"").unwrap();
+        assert_eq!(
+            collect_paths(std::slice::from_ref(&dir)).contains(&dir.join("IMG_0001.HEIC")),
+            cfg!(feature = "heic")
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_paths_reports_progress_and_appends() {
+        let pool = test_pool().await;
+        let property = properties::create(&pool, crate::db::test_property_input())
+            .await
+            .unwrap();
+        let dir = std::env::temp_dir().join(format!("realestate-paths-{}", uuid::Uuid::new_v4()));
+        let source = dir.join("source");
+        save_png(&source.join("front.png"), 10);
+        save_png(&source.join("rooms/kitchen.png"), 20);
+        save_png(&source.join("rooms/kitchen-copy.png"), 20);
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let report = import_paths(
+            &pool,
+            &dir,
+            &property.id,
+            std::slice::from_ref(&source),
+            move |event| sink.lock().unwrap().push(event),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.imported.len(), 2);
+        let statuses: Vec<&str> = report.files.iter().map(|f| f.status).collect();
+        assert_eq!(statuses, vec!["imported", "imported", "duplicate"]);
+
+        let events = std::mem::take(&mut *events.lock().unwrap());
+        assert!(matches!(events[0], PhotoImportEvent::Started { total: 3 }));
+        assert!(matches!(
+            events[3],
+            PhotoImportEvent::File {
+                index: 2,
+                total: 3,
+                ..
+            }
+        ));
+        assert!(matches!(
+            events[4],
+            PhotoImportEvent::Finished { imported: 2 }
+        ));
+
+        // A second import goes after the existing photos
+        save_png(&source.join("yard.png"), 30);
+        let report = import_paths(
+            &pool,
+            &dir,
+            &property.id,
+            &[source.join("yard.png")],
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.imported[0].sort_order, 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
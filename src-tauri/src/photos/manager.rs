@@ -1,7 +1,11 @@
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
 use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageReader};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::error::AppError;
 
@@ -14,17 +18,61 @@ pub struct PhotoRecord {
     pub original_path: String,
     pub thumbnail_path: String,
     pub sort_order: i64,
+    /// SHA-256 of the source file, used to skip re-imports
+    pub content_hash: String,
+}
+
+/// What happened to one file in an import batch
+#[derive(Debug, Clone)]
+pub enum FileOutcome {
+    Imported(PhotoRecord),
+    /// Same content as a photo already on the property or earlier in the batch
+    Duplicate,
+    /// The property has no photo slots left
+    OverLimit,
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct FileImport {
+    pub source: PathBuf,
+    pub outcome: FileOutcome,
 }
 
 const THUMB_WIDTH: u32 = 300;
 const THUMB_HEIGHT: u32 = 200;
 const THUMB_QUALITY: u8 = 85;
 
+/// Quality for originals that have to be re-encoded (HEIC, or rotated by
+/// EXIF orientation)
+const NORMALIZED_QUALITY: u8 = 92;
+
+/// Longest edge of the copies sent for vision analysis
+const ANALYSIS_MAX_DIMENSION: u32 = 1024;
+const ANALYSIS_QUALITY: u8 = 80;
+
+/// Extensions offered in the import dialog. HEIC/HEIF only when this build
+/// can decode them (the `heic` feature).
+#[cfg(feature = "heic")]
+pub const IMPORT_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "webp", "bmp", "tiff", "heic", "heif",
+];
+#[cfg(not(feature = "heic"))]
+pub const IMPORT_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "bmp", "tiff"];
+
+/// Copy photos into the property's folder and make thumbnails. Each file is
+/// handled on its own, so one unreadable file doesn't stop the batch; only a
+/// failure to create the destination folders is an error. Files whose
+/// content hash is in `known_hashes` are skipped, and at most `max_new`
+/// files are imported. `on_file` is called with each result as it's ready.
 pub fn import_photos(
     app_data_dir: &Path,
     property_id: &str,
     source_paths: &[PathBuf],
-) -> Result<Vec<PhotoRecord>, AppError> {
+    known_hashes: &HashSet<String>,
+    max_new: usize,
+    mut on_file: impl FnMut(usize, &FileImport),
+) -> Result<Vec<FileImport>, AppError> {
     let photos_dir = app_data_dir.join("photos").join(property_id);
     let thumbs_dir = photos_dir.join("thumbs");
 
@@ -35,76 +83,228 @@ pub fn import_photos(
         AppError::Photo(format!("Failed to create thumbnails directory: {}", e))
     })?;
 
-    let mut records = Vec::new();
+    let mut seen = known_hashes.clone();
+    let mut results = Vec::new();
+    let mut imported = 0;
 
     for (index, source) in source_paths.iter().enumerate() {
-        let ext = source
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("jpg")
-            .to_lowercase();
-
-        let filename = source
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("photo")
-            .to_string();
-
-        let id = uuid::Uuid::new_v4().to_string();
-        let dest_filename = format!("{}.{}", id, ext);
-        let dest_path = photos_dir.join(&dest_filename);
-        let thumb_path = thumbs_dir.join(format!("{}.jpg", id));
-
-        // Copy original file
+        // Once the limit is reached the rest aren't even read
+        let hash = if imported >= max_new {
+            None
+        } else {
+            Some(hash_file(source))
+        };
+        let outcome = match hash {
+            None => FileOutcome::OverLimit,
+            Some(Err(e)) => FileOutcome::Failed(e.to_string()),
+            Some(Ok(hash)) if seen.contains(&hash) => FileOutcome::Duplicate,
+            Some(Ok(hash)) => {
+                match import_one(source, &photos_dir, &thumbs_dir, property_id, &hash) {
+                    Ok(mut record) => {
+                        record.sort_order = imported as i64;
+                        imported += 1;
+                        seen.insert(hash);
+                        FileOutcome::Imported(record)
+                    }
+                    Err(e) => FileOutcome::Failed(e.to_string()),
+                }
+            }
+        };
+        let file = FileImport {
+            source: source.clone(),
+            outcome,
+        };
+        on_file(index, &file);
+        results.push(file);
+    }
+
+    Ok(results)
+}
+
+fn hash_file(path: &Path) -> Result<String, AppError> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| AppError::Photo(format!("Failed to read photo: {}", e)))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| AppError::Photo(format!("Failed to read photo: {}", e)))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn import_one(
+    source: &Path,
+    photos_dir: &Path,
+    thumbs_dir: &Path,
+    property_id: &str,
+    content_hash: &str,
+) -> Result<PhotoRecord, AppError> {
+    let ext = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("jpg")
+        .to_lowercase();
+
+    let filename = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("photo")
+        .to_string();
+
+    // Decode before copying anything so a corrupt file leaves nothing behind
+    let is_heic = ext == "heic" || ext == "heif";
+    let (img, rotated) = if is_heic {
+        (decode_heic(source)?, false)
+    } else {
+        decode_oriented(source)?
+    };
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let thumb_path = thumbs_dir.join(format!("{}.jpg", id));
+
+    // Keep the original byte-for-byte unless it has to be re-encoded to
+    // display upright or to be readable by the rest of the app
+    let dest_path = if is_heic || rotated {
+        let dest_path = photos_dir.join(format!("{}.jpg", id));
+        write_jpeg(&img, &dest_path, NORMALIZED_QUALITY)?;
+        dest_path
+    } else {
+        let dest_path = photos_dir.join(format!("{}.{}", id, ext));
         std::fs::copy(source, &dest_path).map_err(|e| {
             AppError::Photo(format!("Failed to copy photo {}: {}", filename, e))
         })?;
+        dest_path
+    };
 
-        // Generate thumbnail
-        create_thumbnail(&dest_path, &thumb_path).map_err(|e| {
-            // Clean up the copied original if thumbnail fails
-            let _ = std::fs::remove_file(&dest_path);
-            AppError::Photo(format!(
-                "Failed to create thumbnail for {}: {}",
-                filename, e
-            ))
-        })?;
-
-        records.push(PhotoRecord {
-            id,
-            property_id: property_id.to_string(),
-            filename,
-            original_path: dest_path.to_string_lossy().to_string(),
-            thumbnail_path: thumb_path.to_string_lossy().to_string(),
-            sort_order: index as i64,
-        });
-    }
+    create_thumbnail(&img, &thumb_path).map_err(|e| {
+        // Clean up the copied original if thumbnail fails
+        let _ = std::fs::remove_file(&dest_path);
+        AppError::Photo(format!(
+            "Failed to create thumbnail for {}: {}",
+            filename, e
+        ))
+    })?;
 
-    Ok(records)
+    Ok(PhotoRecord {
+        id,
+        property_id: property_id.to_string(),
+        filename,
+        original_path: dest_path.to_string_lossy().to_string(),
+        thumbnail_path: thumb_path.to_string_lossy().to_string(),
+        sort_order: 0,
+        content_hash: content_hash.to_string(),
+    })
 }
 
-fn create_thumbnail(source: &Path, dest: &Path) -> Result<(), AppError> {
-    let img = image::open(source).map_err(|e| {
-        AppError::Photo(format!("Failed to open image: {}", e))
-    })?;
+/// Rebuild a missing thumbnail from the stored original
+pub fn regenerate_thumbnail(original_path: &Path, thumbnail_path: &Path) -> Result<(), AppError> {
+    let img = open_oriented(original_path)?;
+    if let Some(parent) = thumbnail_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            AppError::Photo(format!("Failed to create thumbnails directory: {}", e))
+        })?;
+    }
+    create_thumbnail(&img, thumbnail_path)
+}
 
+fn create_thumbnail(img: &DynamicImage, dest: &Path) -> Result<(), AppError> {
     let thumb = img.resize_to_fill(THUMB_WIDTH, THUMB_HEIGHT, FilterType::Lanczos3);
+    write_jpeg(&thumb, dest, THUMB_QUALITY)
+}
 
-    // Encode as JPEG with specified quality
+fn write_jpeg(img: &DynamicImage, dest: &Path, quality: u8) -> Result<(), AppError> {
     let mut output = std::io::BufWriter::new(
         std::fs::File::create(dest).map_err(|e| {
-            AppError::Photo(format!("Failed to create thumbnail file: {}", e))
+            AppError::Photo(format!("Failed to create image file: {}", e))
         })?,
     );
 
-    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut output, THUMB_QUALITY);
-    thumb.write_with_encoder(encoder).map_err(|e| {
-        AppError::Photo(format!("Failed to encode thumbnail: {}", e))
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut output, quality);
+    img.to_rgb8().write_with_encoder(encoder).map_err(|e| {
+        AppError::Photo(format!("Failed to encode image: {}", e))
     })?;
 
     Ok(())
 }
 
+/// Open an image with its EXIF orientation applied to the pixels, so it
+/// still displays upright once the metadata is gone
+pub fn open_oriented(path: &Path) -> Result<DynamicImage, AppError> {
+    decode_oriented(path).map(|(img, _)| img)
+}
+
+/// Decode an image and apply its EXIF orientation. The flag is set when the
+/// pixels had to be rotated or flipped.
+fn decode_oriented(path: &Path) -> Result<(DynamicImage, bool), AppError> {
+    let mut decoder = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| AppError::Photo(format!("Failed to open image: {}", e)))?
+        .into_decoder()
+        .map_err(|e| AppError::Photo(format!("Failed to open image: {}", e)))?;
+    let orientation = decoder
+        .orientation()
+        .unwrap_or(Orientation::NoTransforms);
+
+    let mut img = DynamicImage::from_decoder(decoder)
+        .map_err(|e| AppError::Photo(format!("Failed to decode image: {}", e)))?;
+    img.apply_orientation(orientation);
+    Ok((img, orientation != Orientation::NoTransforms))
+}
+
+/// Decode the primary image of a HEIC/HEIF file. libheif applies the file's
+/// rotation and mirroring while decoding.
+#[cfg(feature = "heic")]
+fn decode_heic(path: &Path) -> Result<DynamicImage, AppError> {
+    use libheif_rs::{ColorSpace, HeifContext, HeifError, LibHeif, RgbChroma};
+
+    let heic_error =
+        |e: HeifError| AppError::Photo(format!("Failed to decode HEIC image: {}", e));
+    let context = HeifContext::read_from_file(&path.to_string_lossy()).map_err(heic_error)?;
+    let handle = context.primary_image_handle().map_err(heic_error)?;
+    let image = LibHeif::new()
+        .decode(&handle, ColorSpace::Rgb(RgbChroma::Rgb), None)
+        .map_err(heic_error)?;
+
+    let plane = image
+        .planes()
+        .interleaved
+        .ok_or_else(|| AppError::Photo("HEIC image has no RGB data".to_string()))?;
+    let row_bytes = plane.width as usize * 3;
+    let mut pixels = Vec::with_capacity(row_bytes * plane.height as usize);
+    for row in plane.data.chunks(plane.stride).take(plane.height as usize) {
+        pixels.extend_from_slice(&row[..row_bytes]);
+    }
+
+    image::RgbImage::from_raw(plane.width, plane.height, pixels)
+        .map(DynamicImage::ImageRgb8)
+        .ok_or_else(|| AppError::Photo("HEIC image data is truncated".to_string()))
+}
+
+#[cfg(not(feature = "heic"))]
+fn decode_heic(_path: &Path) -> Result<DynamicImage, AppError> {
+    Err(AppError::Photo(
+        "HEIC photos are not supported by this build".to_string(),
+    ))
+}
+
+/// Downscale an imported photo to a JPEG small enough to send for vision
+/// analysis
+pub fn encode_for_analysis(original_path: &Path) -> Result<Vec<u8>, AppError> {
+    let img = open_oriented(original_path)?;
+
+    let img = if img.width() > ANALYSIS_MAX_DIMENSION || img.height() > ANALYSIS_MAX_DIMENSION {
+        img.resize(ANALYSIS_MAX_DIMENSION, ANALYSIS_MAX_DIMENSION, FilterType::Triangle)
+    } else {
+        img
+    };
+
+    let mut output = Vec::new();
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut output, ANALYSIS_QUALITY);
+    img.to_rgb8().write_with_encoder(encoder).map_err(|e| {
+        AppError::Photo(format!("Failed to encode image for analysis: {}", e))
+    })?;
+
+    Ok(output)
+}
+
 pub fn delete_photo_files(original_path: &str, thumbnail_path: &str) -> Result<(), AppError> {
     if Path::new(original_path).exists() {
         std::fs::remove_file(original_path).map_err(|e| {
@@ -120,3 +320,81 @@ pub fn delete_photo_files(original_path: &str, thumbnail_path: &str) -> Result<(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::jpeg::JpegEncoder;
+    use image::{ImageEncoder, RgbImage};
+
+    /// A JPEG tagged with EXIF orientation 6 (rotate 90° clockwise to view)
+    fn write_rotated_jpeg(path: &Path, width: u32, height: u32) {
+        let img = RgbImage::from_fn(width, height, |x, _| image::Rgb([(x * 4) as u8, 90, 160]));
+        let mut bytes = Vec::new();
+        let mut encoder = JpegEncoder::new_with_quality(&mut bytes, 90);
+        // Big-endian TIFF header, one IFD entry: Orientation (0x0112), SHORT, 6
+        let exif = vec![
+            b'M', b'M', 0, 42, 0, 0, 0, 8, 0, 1, 0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 6, 0, 0, 0, 0,
+            0, 0,
+        ];
+        encoder.set_exif_metadata(exif).unwrap();
+        encoder
+            .write_image(img.as_raw(), width, height, image::ExtendedColorType::Rgb8)
+            .unwrap();
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_import_reports_each_file() {
+        let dir = std::env::temp_dir().join(format!("realestate-import-{}", uuid::Uuid::new_v4()));
+        let source = dir.join("source");
+        std::fs::create_dir_all(&source).unwrap();
+
+        let rotated = source.join("IMG_0001.jpg");
+        write_rotated_jpeg(&rotated, 60, 40);
+        let copy = source.join("IMG_0001 copy.jpg");
+        std::fs::copy(&rotated, &copy).unwrap();
+        let broken = source.join("broken.jpg");
+        std::fs::write(&broken, b"not an image").unwrap();
+        let plain = source.join("plain.png");
+        RgbImage::new(30, 20).save(&plain).unwrap();
+        let extra = source.join("extra.png");
+        RgbImage::new(10, 10).save(&extra).unwrap();
+
+        let results = import_photos(
+            &dir,
+            "prop1",
+            &[rotated, copy, broken, plain.clone(), extra],
+            &HashSet::new(),
+            2,
+            |_, _| {},
+        )
+        .unwrap();
+
+        let FileOutcome::Imported(first) = &results[0].outcome else {
+            panic!("expected first photo to import");
+        };
+        // Stored upright, as a JPEG
+        assert!(first.original_path.ends_with(".jpg"));
+        let stored = image::open(&first.original_path).unwrap();
+        assert_eq!((stored.width(), stored.height()), (40, 60));
+
+        assert!(matches!(results[1].outcome, FileOutcome::Duplicate));
+        assert!(matches!(results[2].outcome, FileOutcome::Failed(_)));
+        let FileOutcome::Imported(second) = &results[3].outcome else {
+            panic!("expected png to import after a failure");
+        };
+        assert_eq!(second.sort_order, 1);
+        assert!(second.original_path.ends_with(".png"));
+        assert!(matches!(results[4].outcome, FileOutcome::OverLimit));
+
+        // A later batch skips content that is already on the property
+        let known: HashSet<String> = [first.content_hash.clone(), second.content_hash.clone()]
+            .into_iter()
+            .collect();
+        let again = import_photos(&dir, "prop1", &[plain], &known, 10, |_, _| {}).unwrap();
+        assert!(matches!(again[0].outcome, FileOutcome::Duplicate));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,430 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use image::imageops::FilterType;
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::db::photos::Photo;
+use crate::error::AppError;
+
+use super::{manager, watermark};
+
+/// Lowest JPEG quality tried before giving up on quality and shrinking the
+/// image to meet the file size limit
+const MIN_QUALITY: u8 = 60;
+const QUALITY_STEP: u8 = 10;
+
+/// Each shrink pass scales the longest edge by this much
+const SHRINK_FACTOR: f32 = 0.85;
+
+/// Never shrink below this longest edge; the file is written over the limit
+/// instead and reported as such
+const MIN_DIMENSION: u32 = 640;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MlsExportOptions {
+    /// Longest edge in pixels; smaller photos are never upscaled
+    pub max_dimension: u32,
+    /// Upper bound on each file's size, if the MLS has one
+    pub max_file_bytes: Option<u64>,
+    /// Starting JPEG quality, 1-100
+    pub quality: u8,
+    /// Brokerage logo drawn in the bottom-right corner
+    pub watermark_path: Option<String>,
+    /// Text drawn in the bottom-left corner, e.g. "MLS# 12345678"
+    pub overlay_text: Option<String>,
+}
+
+impl Default for MlsExportOptions {
+    fn default() -> Self {
+        Self {
+            max_dimension: 2048,
+            max_file_bytes: None,
+            quality: 85,
+            watermark_path: None,
+            overlay_text: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedPhoto {
+    pub photo_id: String,
+    pub filename: String,
+    pub width: u32,
+    pub height: u32,
+    pub bytes: u64,
+    /// Set when the photo couldn't be brought under `max_file_bytes`
+    pub over_limit: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MlsExportReport {
+    pub destination: String,
+    pub photos: Vec<ExportedPhoto>,
+    /// Photos left out because they couldn't be read or encoded
+    pub skipped: Vec<SkippedPhoto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedPhoto {
+    pub photo_id: String,
+    pub error: String,
+}
+
+/// Write MLS-ready copies of photos to `dest`: a zip archive when it ends in
+/// `.zip`, otherwise a folder. Files are named by position and room
+/// (`01-front-exterior.jpg`), resized, re-encoded as JPEG and optionally
+/// watermarked. Re-encoding drops all EXIF data, including GPS coordinates.
+pub fn export_photos(
+    photos: &[Photo],
+    dest: &Path,
+    options: &MlsExportOptions,
+) -> Result<MlsExportReport, AppError> {
+    validate_options(options)?;
+
+    let mut sink = Sink::open(dest)?;
+
+    let mut exported = Vec::new();
+    let mut skipped = Vec::new();
+    let written = (|| -> Result<(), AppError> {
+        for photo in photos {
+            // A photo that can't be read or encoded is reported and left
+            // out; numbering stays contiguous across the ones written
+            let encoded = prepare_image(Path::new(&photo.original_path), options)
+                .and_then(|img| encode_within_limit(img, options));
+            let (bytes, width, height) = match encoded {
+                Ok(encoded) => encoded,
+                Err(e) => {
+                    skipped.push(SkippedPhoto {
+                        photo_id: photo.id.clone(),
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let filename = export_filename(exported.len(), photo.room.as_deref());
+            sink.write(&filename, &bytes)?;
+            exported.push(ExportedPhoto {
+                photo_id: photo.id.clone(),
+                filename,
+                width,
+                height,
+                bytes: bytes.len() as u64,
+                over_limit: options
+                    .max_file_bytes
+                    .is_some_and(|max| bytes.len() as u64 > max),
+            });
+        }
+        if exported.is_empty() {
+            return Err(AppError::Photo(
+                "None of the selected photos could be exported".to_string(),
+            ));
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = written.and_then(|()| sink.finish()) {
+        Sink::discard(dest);
+        return Err(e);
+    }
+
+    Ok(MlsExportReport {
+        destination: dest.to_string_lossy().to_string(),
+        photos: exported,
+        skipped,
+    })
+}
+
+fn validate_options(options: &MlsExportOptions) -> Result<(), AppError> {
+    if options.max_dimension < MIN_DIMENSION {
+        return Err(AppError::Validation(format!(
+            "Maximum dimension must be at least {} pixels",
+            MIN_DIMENSION
+        )));
+    }
+    if !(1..=100).contains(&options.quality) {
+        return Err(AppError::Validation(
+            "JPEG quality must be between 1 and 100".to_string(),
+        ));
+    }
+    if let Some(c) = options
+        .overlay_text
+        .as_deref()
+        .and_then(watermark::unsupported_char)
+    {
+        return Err(AppError::Validation(format!(
+            "Overlay text can't include \"{}\". Use letters A-Z, digits and common punctuation.",
+            c
+        )));
+    }
+    Ok(())
+}
+
+/// `01-front-exterior.jpg`; untagged photos are just `01-photo.jpg`
+fn export_filename(index: usize, room: Option<&str>) -> String {
+    let label = match room {
+        Some("exterior_front") => "front-exterior".to_string(),
+        Some("exterior_back") => "back-exterior".to_string(),
+        Some(room) => room.replace('_', "-"),
+        None => "photo".to_string(),
+    };
+    format!("{:02}-{}.jpg", index + 1, label)
+}
+
+fn prepare_image(path: &Path, options: &MlsExportOptions) -> Result<DynamicImage, AppError> {
+    let img = manager::open_oriented(path)?;
+    let img = if img.width() > options.max_dimension || img.height() > options.max_dimension {
+        img.resize(
+            options.max_dimension,
+            options.max_dimension,
+            FilterType::Lanczos3,
+        )
+    } else {
+        img
+    };
+
+    if options.watermark_path.is_none() && options.overlay_text.is_none() {
+        return Ok(img);
+    }
+
+    let mut rgba = img.to_rgba8();
+    if let Some(logo) = &options.watermark_path {
+        watermark::overlay_logo(&mut rgba, Path::new(logo))?;
+    }
+    if let Some(text) = &options.overlay_text {
+        watermark::overlay_text(&mut rgba, text);
+    }
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+/// Encode as JPEG, stepping quality down and then shrinking the image until
+/// it fits `max_file_bytes`. Returns the bytes and final dimensions.
+fn encode_within_limit(
+    img: DynamicImage,
+    options: &MlsExportOptions,
+) -> Result<(Vec<u8>, u32, u32), AppError> {
+    let mut img = img;
+    let mut quality = options.quality;
+    loop {
+        let bytes = encode_jpeg(&img, quality)?;
+        let fits = options
+            .max_file_bytes
+            .is_none_or(|max| bytes.len() as u64 <= max);
+        if fits {
+            return Ok((bytes, img.width(), img.height()));
+        }
+
+        if quality > MIN_QUALITY {
+            quality = quality.saturating_sub(QUALITY_STEP).max(MIN_QUALITY);
+            continue;
+        }
+
+        let longest = img.width().max(img.height());
+        if longest <= MIN_DIMENSION {
+            return Ok((bytes, img.width(), img.height()));
+        }
+        let target = ((longest as f32 * SHRINK_FACTOR) as u32).max(MIN_DIMENSION);
+        img = img.resize(target, target, FilterType::Lanczos3);
+    }
+}
+
+fn encode_jpeg(img: &DynamicImage, quality: u8) -> Result<Vec<u8>, AppError> {
+    let mut output = Vec::new();
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut output, quality);
+    img.to_rgb8()
+        .write_with_encoder(encoder)
+        .map_err(|e| AppError::Photo(format!("Failed to encode photo: {}", e)))?;
+    Ok(output)
+}
+
+/// Destination for a set of exported files: a folder, or a zip archive when
+/// the path ends in `.zip`
+pub(crate) enum Sink {
+    Folder(PathBuf),
+    Zip(ZipWriter<File>),
+}
+
+impl Sink {
+    fn is_zip(dest: &Path) -> bool {
+        dest.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+    }
+
+    pub(crate) fn open(dest: &Path) -> Result<Self, AppError> {
+        if Self::is_zip(dest) {
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            Ok(Sink::Zip(ZipWriter::new(File::create(dest)?)))
+        } else {
+            std::fs::create_dir_all(dest)?;
+            Ok(Sink::Folder(dest.to_path_buf()))
+        }
+    }
+
+    pub(crate) fn write(&mut self, filename: &str, bytes: &[u8]) -> Result<(), AppError> {
+        match self {
+            Sink::Folder(dir) => std::fs::write(dir.join(filename), bytes)?,
+            Sink::Zip(zip) => {
+                // JPEGs and PNGs don't compress further
+                let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+                zip.start_file(filename, stored).map_err(zip_error)?;
+                zip.write_all(bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(self) -> Result<(), AppError> {
+        if let Sink::Zip(mut zip) = self {
+            zip.finish().map_err(zip_error)?;
+        }
+        Ok(())
+    }
+
+    /// Clean up after a failed export. A partial archive is removed; files
+    /// already written to a folder are complete and kept.
+    pub(crate) fn discard(dest: &Path) {
+        if Self::is_zip(dest) {
+            let _ = std::fs::remove_file(dest);
+        }
+    }
+}
+
+fn zip_error(e: zip::result::ZipError) -> AppError {
+    AppError::Export(format!("Zip error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::jpeg::JpegEncoder;
+    use image::{ImageEncoder, RgbImage};
+
+    fn photo(id: &str, path: &Path, room: Option<&str>) -> Photo {
+        Photo {
+            id: id.to_string(),
+            filename: format!("{}.jpg", id),
+            original_path: path.to_string_lossy().to_string(),
+            room: room.map(str::to_string),
+            ..crate::db::test_photo()
+        }
+    }
+
+    /// A JPEG carrying an EXIF block with a GPS marker
+    fn write_jpeg_with_exif(path: &Path, width: u32, height: u32) {
+        let img = RgbImage::from_fn(width, height, |x, y| {
+            image::Rgb([(x * 255 / width) as u8, (y * 255 / height) as u8, 128])
+        });
+        let mut bytes = Vec::new();
+        let mut encoder = JpegEncoder::new_with_quality(&mut bytes, 95);
+        let mut exif = b"MM\0\x2a\0\0\0\x08\0\0".to_vec();
+        exif.extend_from_slice(b"GPSLatitude");
+        encoder.set_exif_metadata(exif).unwrap();
+        encoder
+            .write_image(img.as_raw(), width, height, image::ExtendedColorType::Rgb8)
+            .unwrap();
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_export_filename() {
+        assert_eq!(
+            export_filename(0, Some("exterior_front")),
+            "01-front-exterior.jpg"
+        );
+        assert_eq!(
+            export_filename(9, Some("primary_bedroom")),
+            "10-primary-bedroom.jpg"
+        );
+        assert_eq!(export_filename(2, None), "03-photo.jpg");
+    }
+
+    #[test]
+    fn test_overlay_text_must_be_drawable() {
+        let options = |text: &str| MlsExportOptions {
+            overlay_text: Some(text.to_string()),
+            ..Default::default()
+        };
+        assert!(validate_options(&options("MLS# 12345 - Courtesy of Bay & Co.")).is_ok());
+        for text in ["Café Realty", "50% off", "房屋出售"] {
+            let err = validate_options(&options(text)).unwrap_err();
+            assert!(matches!(err, AppError::Validation(_)), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_export_resizes_strips_exif_and_meets_size_limit() {
+        let dir = std::env::temp_dir().join(format!("realestate-mls-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let front = dir.join("front.jpg");
+        let kitchen = dir.join("kitchen.jpg");
+        write_jpeg_with_exif(&front, 3000, 2000);
+        write_jpeg_with_exif(&kitchen, 1200, 800);
+        assert!(std::fs::read(&front)
+            .unwrap()
+            .windows(11)
+            .any(|w| w == b"GPSLatitude"));
+
+        let options = MlsExportOptions {
+            max_dimension: 1600,
+            max_file_bytes: Some(400_000),
+            overlay_text: Some("MLS# 12345".to_string()),
+            ..Default::default()
+        };
+        let out = dir.join("mls");
+        let report = export_photos(
+            &[
+                photo("a", &front, Some("exterior_front")),
+                photo("b", &kitchen, Some("kitchen")),
+            ],
+            &out,
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(report.photos[0].filename, "01-front-exterior.jpg");
+        assert_eq!(report.photos[1].filename, "02-kitchen.jpg");
+        assert!(report.photos[0].width <= 1600 && report.photos[0].height <= 1600);
+        // Smaller photos are not upscaled
+        assert_eq!(report.photos[1].width, 1200);
+
+        for exported in &report.photos {
+            let bytes = std::fs::read(out.join(&exported.filename)).unwrap();
+            assert!(!exported.over_limit);
+            assert!(bytes.len() as u64 <= 400_000);
+            assert!(!bytes.windows(11).any(|w| w == b"GPSLatitude"));
+        }
+
+        // An unreadable photo is skipped without leaving a gap in the numbering
+        let missing = dir.join("missing.jpg");
+        let zip_path = dir.join("mls.zip");
+        let report = export_photos(
+            &[photo("gone", &missing, None), photo("a", &front, None)],
+            &zip_path,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].photo_id, "gone");
+        let mut archive = zip::ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        assert!(archive.by_name("01-photo.jpg").is_ok());
+
+        // A failed export leaves no partial archive behind
+        let failed_zip = dir.join("failed.zip");
+        assert!(export_photos(&[photo("gone", &missing, None)], &failed_zip, &options).is_err());
+        assert!(!failed_zip.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
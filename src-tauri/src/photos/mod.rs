@@ -1 +1,8 @@
+pub mod arrange;
+pub mod derivatives;
+pub mod import;
 pub mod manager;
+pub mod mls_export;
+pub mod quality;
+pub mod storage;
+pub mod watermark;
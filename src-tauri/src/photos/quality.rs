@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use image::imageops::FilterType;
+use image::GrayImage;
+use serde::Serialize;
+
+use crate::error::AppError;
+
+/// Images are measured at this size so scores are comparable across
+/// cameras and cheap to compute
+const MEASURE_DIMENSION: u32 = 512;
+
+/// Laplacian variance at which sharpness scores 0.5
+const SHARPNESS_MIDPOINT: f64 = 150.0;
+
+/// Megapixels at which resolution scores 1.0; MLS systems rarely keep more
+const FULL_RESOLUTION_MP: f64 = 6.0;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageMetrics {
+    pub width: u32,
+    pub height: u32,
+    /// Variance of the Laplacian; higher means more fine detail in focus
+    pub laplacian_variance: f64,
+    /// Mean luminance, 0-1
+    pub mean_luminance: f64,
+    /// Share of pixels that are blown out or crushed to black
+    pub clipped_fraction: f64,
+}
+
+impl ImageMetrics {
+    /// 0-1, saturating as focus improves
+    pub fn sharpness_score(&self) -> f64 {
+        self.laplacian_variance / (self.laplacian_variance + SHARPNESS_MIDPOINT)
+    }
+
+    /// 0-1, best for a mid-tone average with little clipping
+    pub fn exposure_score(&self) -> f64 {
+        let balance = 1.0 - (self.mean_luminance - 0.5).abs() * 2.0;
+        (balance - self.clipped_fraction * 2.0).clamp(0.0, 1.0)
+    }
+
+    /// 0-1
+    pub fn resolution_score(&self) -> f64 {
+        let megapixels = (self.width as f64 * self.height as f64) / 1_000_000.0;
+        (megapixels / FULL_RESOLUTION_MP).min(1.0)
+    }
+
+    /// How well the photo would work as the lead image, 0-1
+    pub fn hero_score(&self) -> f64 {
+        self.sharpness_score() * 0.45 + self.exposure_score() * 0.35 + self.resolution_score() * 0.2
+    }
+}
+
+/// Measure sharpness, exposure and resolution of an image file
+pub fn measure(path: &Path) -> Result<ImageMetrics, AppError> {
+    let img =
+        image::open(path).map_err(|e| AppError::Photo(format!("Failed to open image: {}", e)))?;
+    let (width, height) = (img.width(), img.height());
+
+    let gray = img
+        .resize(MEASURE_DIMENSION, MEASURE_DIMENSION, FilterType::Triangle)
+        .to_luma8();
+
+    Ok(ImageMetrics {
+        width,
+        height,
+        laplacian_variance: laplacian_variance(&gray),
+        mean_luminance: mean_luminance(&gray),
+        clipped_fraction: clipped_fraction(&gray),
+    })
+}
+
+fn laplacian_variance(gray: &GrayImage) -> f64 {
+    let (w, h) = gray.dimensions();
+    if w < 3 || h < 3 {
+        return 0.0;
+    }
+
+    let px = |x: u32, y: u32| gray.get_pixel(x, y)[0] as f64;
+    let mut values = Vec::with_capacity(((w - 2) * (h - 2)) as usize);
+    for y in 1..h - 1 {
+        for x in 1..w - 1 {
+            values.push(px(x - 1, y) + px(x + 1, y) + px(x, y - 1) + px(x, y + 1) - 4.0 * px(x, y));
+        }
+    }
+
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+}
+
+fn mean_luminance(gray: &GrayImage) -> f64 {
+    let total: u64 = gray.pixels().map(|p| p[0] as u64).sum();
+    total as f64 / (gray.pixels().len() as f64 * 255.0)
+}
+
+fn clipped_fraction(gray: &GrayImage) -> f64 {
+    let clipped = gray.pixels().filter(|p| p[0] <= 5 || p[0] >= 250).count();
+    clipped as f64 / gray.pixels().len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Luma, RgbImage};
+
+    fn save(name: &str, img: RgbImage) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("realestate-{}-{}.png", name, uuid::Uuid::new_v4()));
+        img.save(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_detail_scores_sharper_than_flat() {
+        let detailed = save(
+            "detailed",
+            RgbImage::from_fn(800, 600, |x, y| {
+                let v = if (x / 4 + y / 4) % 2 == 0 { 60 } else { 190 };
+                image::Rgb([v, v, v])
+            }),
+        );
+        let flat = save(
+            "flat",
+            RgbImage::from_pixel(800, 600, image::Rgb([128, 128, 128])),
+        );
+
+        let detailed_metrics = measure(&detailed).unwrap();
+        let flat_metrics = measure(&flat).unwrap();
+        assert!(detailed_metrics.sharpness_score() > 0.5);
+        assert!(flat_metrics.sharpness_score() < 0.01);
+        assert!(detailed_metrics.hero_score() > flat_metrics.hero_score());
+
+        std::fs::remove_file(detailed).unwrap();
+        std::fs::remove_file(flat).unwrap();
+    }
+
+    #[test]
+    fn test_exposure_penalizes_dark_and_clipped() {
+        let gray = |v: u8| GrayImage::from_pixel(10, 10, Luma([v]));
+        let metrics = |img: &GrayImage| ImageMetrics {
+            width: 10,
+            height: 10,
+            laplacian_variance: 0.0,
+            mean_luminance: mean_luminance(img),
+            clipped_fraction: clipped_fraction(img),
+        };
+
+        let mid = metrics(&gray(128));
+        let dark = metrics(&gray(30));
+        let black = metrics(&gray(0));
+        assert!(mid.exposure_score() > 0.95);
+        assert!(dark.exposure_score() < mid.exposure_score());
+        assert_eq!(black.exposure_score(), 0.0);
+    }
+}
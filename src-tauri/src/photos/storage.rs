@@ -0,0 +1,279 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use crate::db::photos::{self, Photo};
+use crate::error::AppError;
+
+use super::manager;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyStorage {
+    pub property_id: String,
+    pub address: Option<String>,
+    /// Includes photos in the trash, whose files are still on disk
+    pub photo_count: usize,
+    pub original_bytes: u64,
+    pub thumbnail_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedFile {
+    pub path: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingFile {
+    pub photo_id: String,
+    pub property_id: String,
+    pub path: String,
+    /// The original is gone, so the photo can't be recovered; otherwise only
+    /// the thumbnail is missing and can be rebuilt
+    pub original: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageReport {
+    pub properties: Vec<PropertyStorage>,
+    pub total_original_bytes: u64,
+    pub total_thumbnail_bytes: u64,
+    /// Files under `photos/` that no photo row points at
+    pub orphaned_files: Vec<OrphanedFile>,
+    pub orphaned_bytes: u64,
+    /// Photo rows pointing at files that don't exist
+    pub missing_files: Vec<MissingFile>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCleanup {
+    pub files_removed: usize,
+    pub bytes_freed: u64,
+    pub thumbnails_rebuilt: usize,
+    /// Photo rows deleted because their original file was gone
+    pub photos_removed: usize,
+}
+
+/// Measure disk usage of the photo library per property and find files
+/// and rows that have lost their counterpart
+pub async fn storage_report(
+    pool: &SqlitePool,
+    app_data_dir: &Path,
+) -> Result<StorageReport, AppError> {
+    let all_photos = photos::list_all(pool).await?;
+    let addresses: Vec<(String, String)> = sqlx::query_as("SELECT id, address FROM properties")
+        .fetch_all(pool)
+        .await?;
+    let photos_dir = app_data_dir.join("photos");
+
+    tokio::task::spawn_blocking(move || build_report(&all_photos, &addresses, &photos_dir))
+        .await
+        .map_err(|e| AppError::Photo(format!("Storage scan thread error: {}", e)))
+}
+
+fn build_report(
+    all_photos: &[Photo],
+    addresses: &[(String, String)],
+    photos_dir: &Path,
+) -> StorageReport {
+    let mut properties: BTreeMap<&str, PropertyStorage> = BTreeMap::new();
+    let mut referenced: HashSet<PathBuf> = HashSet::new();
+    let mut missing_files = Vec::new();
+
+    for photo in all_photos {
+        let entry = properties
+            .entry(photo.property_id.as_str())
+            .or_insert_with(|| PropertyStorage {
+                property_id: photo.property_id.clone(),
+                address: addresses
+                    .iter()
+                    .find(|(id, _)| *id == photo.property_id)
+                    .map(|(_, address)| address.clone()),
+                photo_count: 0,
+                original_bytes: 0,
+                thumbnail_bytes: 0,
+            });
+        entry.photo_count += 1;
+
+        for (path, original) in [(&photo.original_path, true), (&photo.thumbnail_path, false)] {
+            let path = PathBuf::from(path);
+            match std::fs::metadata(&path) {
+                Ok(meta) if original => entry.original_bytes += meta.len(),
+                Ok(meta) => entry.thumbnail_bytes += meta.len(),
+                Err(_) => missing_files.push(MissingFile {
+                    photo_id: photo.id.clone(),
+                    property_id: photo.property_id.clone(),
+                    path: path.to_string_lossy().to_string(),
+                    original,
+                }),
+            }
+            referenced.insert(path);
+        }
+    }
+
+    let mut orphaned_files = Vec::new();
+    walk_files(photos_dir, &mut |path, bytes| {
+        if !referenced.contains(path) {
+            orphaned_files.push(OrphanedFile {
+                path: path.to_string_lossy().to_string(),
+                bytes,
+            });
+        }
+    });
+
+    let properties: Vec<PropertyStorage> = properties.into_values().collect();
+    StorageReport {
+        total_original_bytes: properties.iter().map(|p| p.original_bytes).sum(),
+        total_thumbnail_bytes: properties.iter().map(|p| p.thumbnail_bytes).sum(),
+        orphaned_bytes: orphaned_files.iter().map(|f| f.bytes).sum(),
+        properties,
+        orphaned_files,
+        missing_files,
+    }
+}
+
+fn walk_files(dir: &Path, visit: &mut dyn FnMut(&Path, u64)) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        match entry.metadata() {
+            Ok(meta) if meta.is_dir() => walk_files(&path, visit),
+            Ok(meta) => visit(&path, meta.len()),
+            Err(_) => {}
+        }
+    }
+}
+
+/// Delete orphaned files, rebuild missing thumbnails and drop photo rows
+/// whose original is gone. Works from a fresh scan rather than a report the
+/// caller may have held on to.
+pub async fn clean_up(pool: &SqlitePool, app_data_dir: &Path) -> Result<StorageCleanup, AppError> {
+    let report = storage_report(pool, app_data_dir).await?;
+    let mut cleanup = StorageCleanup {
+        files_removed: 0,
+        bytes_freed: 0,
+        thumbnails_rebuilt: 0,
+        photos_removed: 0,
+    };
+
+    for file in &report.orphaned_files {
+        if std::fs::remove_file(&file.path).is_ok() {
+            cleanup.files_removed += 1;
+            cleanup.bytes_freed += file.bytes;
+        }
+    }
+
+    let lost: HashSet<&str> = report
+        .missing_files
+        .iter()
+        .filter(|m| m.original)
+        .map(|m| m.photo_id.as_str())
+        .collect();
+    for photo_id in &lost {
+        photos::delete_permanently(pool, photo_id).await?;
+        cleanup.photos_removed += 1;
+    }
+
+    let all_photos = photos::list_all(pool).await?;
+    let rebuild: Vec<(PathBuf, PathBuf)> = report
+        .missing_files
+        .iter()
+        .filter(|m| !m.original && !lost.contains(m.photo_id.as_str()))
+        .filter_map(|m| {
+            let photo = all_photos.iter().find(|p| p.id == m.photo_id)?;
+            Some((PathBuf::from(&photo.original_path), PathBuf::from(&m.path)))
+        })
+        .collect();
+    cleanup.thumbnails_rebuilt = tokio::task::spawn_blocking(move || {
+        rebuild
+            .iter()
+            .filter(|(original, thumb)| manager::regenerate_thumbnail(original, thumb).is_ok())
+            .count()
+    })
+    .await
+    .map_err(|e| AppError::Photo(format!("Thumbnail thread error: {}", e)))?;
+
+    Ok(cleanup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{properties, test_pool};
+
+    #[tokio::test]
+    async fn test_report_and_clean_up() {
+        let pool = test_pool().await;
+        let property = properties::create(&pool, crate::db::test_property_input())
+            .await
+            .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("realestate-storage-{}", uuid::Uuid::new_v4()));
+        let property_dir = dir.join("photos").join(&property.id);
+        std::fs::create_dir_all(property_dir.join("thumbs")).unwrap();
+
+        // Complete photo
+        let a = property_dir.join("a.png");
+        image::RgbImage::new(40, 30).save(&a).unwrap();
+        std::fs::write(property_dir.join("thumbs/a.jpg"), vec![0u8; 100]).unwrap();
+        // Photo whose thumbnail was lost
+        let b = property_dir.join("b.png");
+        image::RgbImage::new(40, 30).save(&b).unwrap();
+        // Photo whose original was lost
+        let c = property_dir.join("c.png");
+        // Stray file from an interrupted import
+        std::fs::write(property_dir.join("stray.jpg"), vec![0u8; 50]).unwrap();
+
+        for (id, original, order) in [("a", &a, 0), ("b", &b, 1), ("c", &c, 2)] {
+            photos::insert(
+                &pool,
+                id,
+                &property.id,
+                &format!("{}.png", id),
+                &original.to_string_lossy(),
+                &property_dir
+                    .join(format!("thumbs/{}.jpg", id))
+                    .to_string_lossy(),
+                order,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        let report = storage_report(&pool, &dir).await.unwrap();
+        assert_eq!(report.properties.len(), 1);
+        assert_eq!(
+            report.properties[0].address.as_deref(),
+            Some("123 Oak Street")
+        );
+        assert_eq!(report.properties[0].photo_count, 3);
+        assert_eq!(report.total_thumbnail_bytes, 100);
+        assert_eq!(report.orphaned_files.len(), 1);
+        assert_eq!(report.orphaned_bytes, 50);
+        // b's thumbnail, c's original and c's thumbnail
+        assert_eq!(report.missing_files.len(), 3);
+
+        let cleanup = clean_up(&pool, &dir).await.unwrap();
+        assert_eq!(cleanup.files_removed, 1);
+        assert_eq!(cleanup.bytes_freed, 50);
+        assert_eq!(cleanup.thumbnails_rebuilt, 1);
+        assert_eq!(cleanup.photos_removed, 1);
+
+        let report = storage_report(&pool, &dir).await.unwrap();
+        assert!(report.orphaned_files.is_empty());
+        assert!(report.missing_files.is_empty());
+        assert_eq!(report.properties[0].photo_count, 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
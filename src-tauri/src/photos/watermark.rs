@@ -0,0 +1,176 @@
+use std::path::Path;
+
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+
+use crate::error::AppError;
+
+/// Logo width as a share of the photo width
+const LOGO_WIDTH_FRACTION: f32 = 0.2;
+
+/// Logo opacity, 0-1, so it marks the photo without hiding the room
+const LOGO_OPACITY: f32 = 0.6;
+
+/// Text height as a share of the photo height
+const TEXT_HEIGHT_FRACTION: f32 = 0.035;
+
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+
+/// Rows of a 5x7 bitmap glyph, high bit on the left, or `None` for a
+/// character the font doesn't have. Lowercase letters are drawn as capitals.
+/// Built in so the MLS number overlay doesn't depend on fonts installed on
+/// the machine.
+fn glyph(c: char) -> Option<[u8; 7]> {
+    let rows = match c.to_ascii_uppercase() {
+        ' ' => [0x00; 7],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '&' => [0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D],
+        '\'' => [0x0C, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00],
+        '$' => [0x04, 0x0F, 0x14, 0x0E, 0x05, 0x1E, 0x04],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '@' => [0x0E, 0x11, 0x17, 0x15, 0x17, 0x10, 0x0E],
+        '|' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        '\u{2013}' | '\u{2014}' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        _ => return None,
+    };
+    Some(rows)
+}
+
+/// The first character of `text` the bitmap font can't draw, if any
+pub fn unsupported_char(text: &str) -> Option<char> {
+    text.chars().find(|c| glyph(*c).is_none())
+}
+
+/// Draw a brokerage logo in the bottom-right corner at reduced opacity
+pub fn overlay_logo(img: &mut RgbaImage, logo_path: &Path) -> Result<(), AppError> {
+    let logo = image::open(logo_path)
+        .map_err(|e| AppError::Photo(format!("Failed to open watermark image: {}", e)))?;
+
+    let target_width = ((img.width() as f32 * LOGO_WIDTH_FRACTION) as u32).max(1);
+    let target_height =
+        ((logo.height() as f32 * target_width as f32 / logo.width() as f32) as u32).max(1);
+    let mut logo = logo
+        .resize_exact(target_width, target_height, FilterType::Lanczos3)
+        .to_rgba8();
+    for pixel in logo.pixels_mut() {
+        pixel[3] = (pixel[3] as f32 * LOGO_OPACITY) as u8;
+    }
+
+    let margin = img.width().min(img.height()) / 40;
+    let x = img.width().saturating_sub(logo.width() + margin);
+    let y = img.height().saturating_sub(logo.height() + margin);
+    imageops::overlay(img, &logo, x as i64, y as i64);
+    Ok(())
+}
+
+/// Width in pixels of `text` drawn at `scale`
+pub fn text_width(text: &str, scale: u32) -> u32 {
+    let chars = text.chars().count() as u32;
+    (chars * (GLYPH_WIDTH + 1)).saturating_sub(1) * scale
+}
+
+/// Height in pixels of a line drawn at `scale`
+pub fn text_height(scale: u32) -> u32 {
+    GLYPH_HEIGHT * scale
+}
+
+/// Draw `text` with its top-left corner at (`x`, `y`), each font pixel
+/// `scale` pixels square. Anything outside the image is clipped, and
+/// characters the font doesn't have are left blank.
+pub fn draw_text(img: &mut RgbaImage, text: &str, x: u32, y: u32, scale: u32, color: Rgba<u8>) {
+    let advance = (GLYPH_WIDTH + 1) * scale;
+    for (i, c) in text.chars().enumerate() {
+        let origin_x = x + i as u32 * advance;
+        for (row, bits) in glyph(c).unwrap_or([0x00; 7]).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (0x10 >> col) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let px = origin_x + col * scale + dx;
+                        let py = y + row as u32 * scale + dy;
+                        if px < img.width() && py < img.height() {
+                            img.put_pixel(px, py, color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Draw a line of text, e.g. "MLS# 12345678", in white on a translucent
+/// dark band in the bottom-left corner
+pub fn overlay_text(img: &mut RgbaImage, text: &str) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+
+    let scale = ((img.height() as f32 * TEXT_HEIGHT_FRACTION) as u32 / GLYPH_HEIGHT).max(1);
+    let padding = 2 * scale;
+    let margin = img.width().min(img.height()) / 40;
+
+    let band_width = text_width(text, scale) + padding * 2;
+    let band_height = text_height(scale) + padding * 2;
+    let band_x = margin;
+    let band_y = img.height().saturating_sub(band_height + margin);
+
+    let band = RgbaImage::from_pixel(band_width, band_height, Rgba([0, 0, 0, 140]));
+    imageops::overlay(img, &band, band_x as i64, band_y as i64);
+
+    draw_text(
+        img,
+        text,
+        band_x + padding,
+        band_y + padding,
+        scale,
+        Rgba([255, 255, 255, 255]),
+    );
+}
@@ -30,6 +30,7 @@ mod tests {
             school_district: Some("Portland Public Schools".to_string()),
             nearby_amenities: vec!["Trader Joe's 0.2mi".to_string()],
             agent_notes: Some("Great investment".to_string()),
+            agent_id: None,
         }
     }
 
@@ -151,6 +152,7 @@ mod tests {
             school_district: None,
             nearby_amenities: vec![],
             agent_notes: None,
+            agent_id: None,
         };
 
         let property = realestate_lib::db::properties::create(&pool, minimal_input)
@@ -31,6 +31,7 @@ mod tests {
             school_district: Some("Seattle Public Schools".to_string()),
             nearby_amenities: vec!["Light rail 0.1mi".to_string()],
             agent_notes: Some("Hot market".to_string()),
+            agent_id: None,
         }
     }
 
@@ -259,6 +260,7 @@ mod tests {
             school_district: None,
             nearby_amenities: vec![],
             agent_notes: None,
+            agent_id: None,
         };
 
         let property = properties::create(&pool, minimal_input)
@@ -3,7 +3,7 @@ import { renderHook, act } from "@testing-library/react";
 import { useExport } from "@/hooks/useExport";
 import { save } from "@tauri-apps/plugin-dialog";
 import { writeFile } from "@tauri-apps/plugin-fs";
-import { exportPdf, exportDocx } from "@/lib/tauri";
+import { checkExportClaims, exportPdf, exportDocx } from "@/lib/tauri";
 import toast from "react-hot-toast";
 
 vi.mock("@tauri-apps/plugin-dialog");
@@ -14,6 +14,7 @@ vi.mock("react-hot-toast");
 describe("useExport", () => {
   beforeEach(() => {
     vi.clearAllMocks();
+    vi.mocked(checkExportClaims).mockResolvedValue([]);
   });
 
   it("should initialize with isExporting false", () => {
@@ -34,7 +35,13 @@ describe("useExport", () => {
         await result.current.handleExportPdf("prop-1", ["listing-1"]);
       });
 
-      expect(exportPdf).toHaveBeenCalledWith("prop-1", ["listing-1"]);
+      expect(checkExportClaims).toHaveBeenCalledWith("prop-1", ["listing-1"]);
+      expect(exportPdf).toHaveBeenCalledWith(
+        "prop-1",
+        ["listing-1"],
+        undefined,
+        false
+      );
       expect(save).toHaveBeenCalledWith({
         defaultPath: "property-listing.pdf",
         filters: [{ name: "PDF", extensions: ["pdf"] }],
@@ -78,6 +85,53 @@ describe("useExport", () => {
       expect(result.current.isExporting).toBe(false);
     });
 
+    it("should export unsupported claims once the agent confirms them", async () => {
+      vi.mocked(checkExportClaims).mockResolvedValue([
+        '"4 bed" (property record: 3 bedrooms)',
+      ]);
+      const confirm = vi.spyOn(window, "confirm").mockReturnValue(true);
+      vi.mocked(exportPdf).mockResolvedValue([0x25, 0x50, 0x44, 0x46]);
+      vi.mocked(save).mockResolvedValue("/path/to/file.pdf");
+      vi.mocked(writeFile).mockResolvedValue();
+
+      const { result } = renderHook(() => useExport());
+
+      await act(async () => {
+        await result.current.handleExportPdf("prop-1", ["listing-1"]);
+      });
+
+      expect(confirm).toHaveBeenCalledWith(
+        expect.stringContaining('"4 bed" (property record: 3 bedrooms)')
+      );
+      expect(exportPdf).toHaveBeenCalledWith(
+        "prop-1",
+        ["listing-1"],
+        undefined,
+        true
+      );
+      expect(toast.success).toHaveBeenCalledWith("PDF saved successfully");
+      confirm.mockRestore();
+    });
+
+    it("should not export when the agent declines unsupported claims", async () => {
+      vi.mocked(checkExportClaims).mockResolvedValue([
+        '"fireplace" (not in the property record)',
+      ]);
+      const confirm = vi.spyOn(window, "confirm").mockReturnValue(false);
+
+      const { result } = renderHook(() => useExport());
+
+      await act(async () => {
+        await result.current.handleExportPdf("prop-1", ["listing-1"]);
+      });
+
+      expect(exportPdf).not.toHaveBeenCalled();
+      expect(save).not.toHaveBeenCalled();
+      expect(toast.error).not.toHaveBeenCalled();
+      expect(result.current.isExporting).toBe(false);
+      confirm.mockRestore();
+    });
+
     it("should set isExporting during export", async () => {
       let exportingDuringCall = false;
 
@@ -112,7 +166,12 @@ describe("useExport", () => {
         await result.current.handleExportDocx("prop-1", ["listing-1"]);
       });
 
-      expect(exportDocx).toHaveBeenCalledWith("prop-1", ["listing-1"]);
+      expect(exportDocx).toHaveBeenCalledWith(
+        "prop-1",
+        ["listing-1"],
+        undefined,
+        false
+      );
       expect(save).toHaveBeenCalledWith({
         defaultPath: "property-listing.docx",
         filters: [{ name: "Word Document", extensions: ["docx"] }],
@@ -172,11 +231,12 @@ describe("useExport", () => {
         ]);
       });
 
-      expect(exportDocx).toHaveBeenCalledWith("prop-1", [
-        "listing-1",
-        "listing-2",
-        "listing-3",
-      ]);
+      expect(exportDocx).toHaveBeenCalledWith(
+        "prop-1",
+        ["listing-1", "listing-2", "listing-3"],
+        undefined,
+        false
+      );
     });
   });
 });
@@ -106,7 +106,13 @@ describe("usePhotos", () => {
     ];
 
     vi.mocked(tauri.listPhotos).mockResolvedValue(existingPhotos);
-    vi.mocked(tauri.importPhotos).mockResolvedValue(newPhotos);
+    vi.mocked(tauri.importPhotos).mockResolvedValue({
+      imported: newPhotos,
+      files: [
+        { path: "/pics/new.jpg", status: "imported", photoId: "2", error: null },
+        { path: "/pics/new copy.jpg", status: "duplicate", photoId: null, error: null },
+      ],
+    });
 
     const { result } = renderHook(() => usePhotos("prop-1"));
 
@@ -121,6 +127,7 @@ describe("usePhotos", () => {
     expect(tauri.importPhotos).toHaveBeenCalledWith("prop-1");
     expect(result.current.photos).toHaveLength(2);
     expect(result.current.photos[1].id).toBe("2");
+    expect(result.current.importResults[1].status).toBe("duplicate");
     expect(result.current.isImporting).toBe(false);
   });
 
@@ -1,5 +1,7 @@
+import { useEffect, useState } from "react";
 import { convertFileSrc } from "@tauri-apps/api/core";
 import { Trash2, Star, Loader2 } from "lucide-react";
+import { getPhotoDerivatives } from "@/lib/tauri";
 import type { Photo } from "@/lib/types";
 
 interface PhotoGridProps {
@@ -9,6 +11,25 @@ interface PhotoGridProps {
 }
 
 export function PhotoGrid({ photos, isLoading, onDelete }: PhotoGridProps) {
+  // Grid-sized derivatives by photo id; the import thumbnail shows until
+  // they're ready, or if they can't be made
+  const [tiles, setTiles] = useState<Record<string, string>>({});
+  const photoIds = photos.map((p) => p.id).join(",");
+
+  useEffect(() => {
+    if (!photoIds) return;
+    let cancelled = false;
+    getPhotoDerivatives(photoIds.split(","), { preset: "grid" })
+      .then((derivatives) => {
+        if (cancelled) return;
+        setTiles(Object.fromEntries(derivatives.map((d) => [d.photoId, d.path])));
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, [photoIds]);
+
   if (isLoading) {
     return (
       <div className="flex items-center justify-center py-10">
@@ -29,7 +50,7 @@ export function PhotoGrid({ photos, isLoading, onDelete }: PhotoGridProps) {
           className="relative group rounded-lg overflow-hidden border border-gray-200 bg-gray-100"
         >
           <img
-            src={convertFileSrc(photo.thumbnailPath)}
+            src={convertFileSrc(tiles[photo.id] ?? photo.thumbnailPath)}
             alt={photo.filename}
             className="w-full aspect-[3/2] object-cover"
             loading="lazy"
@@ -1,6 +1,8 @@
-import { useCallback } from "react";
+import { useCallback, useEffect, useState } from "react";
 import { useDropzone } from "react-dropzone";
 import { Upload, Loader2, ImagePlus } from "lucide-react";
+import { getSetting } from "@/lib/tauri";
+import { PHOTO_LIMIT_SETTING } from "@/lib/constants";
 
 interface PhotoUploaderProps {
   photoCount: number;
@@ -8,15 +10,30 @@ interface PhotoUploaderProps {
   onImport: () => Promise<void>;
 }
 
-const MAX_PHOTOS = 20;
-
 export function PhotoUploader({
   photoCount,
   isImporting,
   onImport,
 }: PhotoUploaderProps) {
-  const remaining = MAX_PHOTOS - photoCount;
-  const isFull = remaining <= 0;
+  // The limit is a setting; until it loads (or if it can't be read) the
+  // import command still enforces it
+  const [maxPhotos, setMaxPhotos] = useState<number | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    getSetting(PHOTO_LIMIT_SETTING)
+      .then((value) => {
+        const limit = parseInt(value, 10);
+        if (!cancelled && limit > 0) setMaxPhotos(limit);
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  const remaining = maxPhotos === null ? null : maxPhotos - photoCount;
+  const isFull = remaining !== null && remaining <= 0;
 
   const onDrop = useCallback(() => {
     // The actual file selection happens via Tauri's native dialog,
@@ -39,7 +56,7 @@ export function PhotoUploader({
       <div className="border-2 border-dashed border-gray-200 rounded-lg p-6 text-center bg-gray-50">
         <ImagePlus size={24} className="mx-auto text-gray-400 mb-2" />
         <p className="text-sm text-gray-500">
-          Maximum of {MAX_PHOTOS} photos reached
+          Maximum of {maxPhotos} photos reached
         </p>
       </div>
     );
@@ -69,7 +86,13 @@ export function PhotoUploader({
             Click to add photos
           </p>
           <p className="text-xs text-gray-500">
-            {photoCount} / {MAX_PHOTOS} photos &middot; {remaining} remaining
+            {remaining === null ? (
+              <>{photoCount} photos</>
+            ) : (
+              <>
+                {photoCount} / {maxPhotos} photos &middot; {remaining} remaining
+              </>
+            )}
           </p>
         </div>
       )}
@@ -79,6 +79,7 @@ describe("PropertyForm", () => {
       schoolDistrict: "AISD",
       nearbyAmenities: [],
       agentNotes: "Seller flexible on closing date.",
+      agentId: null,
     });
   });
 });
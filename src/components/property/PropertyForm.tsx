@@ -172,6 +172,7 @@ export function PropertyForm({
         schoolDistrict: values.schoolDistrict.trim() || null,
         nearbyAmenities: values.nearbyAmenities,
         agentNotes: values.agentNotes.trim() || null,
+        agentId: initialValues?.agentId ?? null,
       };
       await onSubmit(input);
     } finally {
@@ -1,9 +1,26 @@
 import { useState, useCallback } from "react";
 import { save } from "@tauri-apps/plugin-dialog";
 import { writeFile } from "@tauri-apps/plugin-fs";
-import { exportPdf, exportDocx } from "../lib/tauri";
+import { checkExportClaims, exportPdf, exportDocx } from "../lib/tauri";
 import toast from "react-hot-toast";
 
+// Show the claims the property record doesn't support, and any copy the fact
+// check couldn't read, and ask whether to export anyway: true to export
+// them, false when there are none, null when the agent cancels
+async function confirmUnsupportedClaims(
+  propertyId: string,
+  listingIds: string[]
+): Promise<boolean | null> {
+  const claims = await checkExportClaims(propertyId, listingIds);
+  if (claims.length === 0) return false;
+  const list = claims.map((claim) => `\u2022 ${claim}`).join("\n");
+  const confirmed = window.confirm(
+    "Some of the copy isn't backed by the property record:\n\n" +
+      `${list}\n\nExport anyway?`
+  );
+  return confirmed ? true : null;
+}
+
 export function useExport() {
   const [isExporting, setIsExporting] = useState(false);
 
@@ -11,7 +28,17 @@ export function useExport() {
     async (propertyId: string, listingIds: string[]) => {
       setIsExporting(true);
       try {
-        const bytes = await exportPdf(propertyId, listingIds);
+        const allowUnsupportedClaims = await confirmUnsupportedClaims(
+          propertyId,
+          listingIds
+        );
+        if (allowUnsupportedClaims === null) return;
+        const bytes = await exportPdf(
+          propertyId,
+          listingIds,
+          undefined,
+          allowUnsupportedClaims
+        );
         const filePath = await save({
           defaultPath: "property-listing.pdf",
           filters: [{ name: "PDF", extensions: ["pdf"] }],
@@ -34,7 +61,17 @@ export function useExport() {
     async (propertyId: string, listingIds: string[]) => {
       setIsExporting(true);
       try {
-        const bytes = await exportDocx(propertyId, listingIds);
+        const allowUnsupportedClaims = await confirmUnsupportedClaims(
+          propertyId,
+          listingIds
+        );
+        if (allowUnsupportedClaims === null) return;
+        const bytes = await exportDocx(
+          propertyId,
+          listingIds,
+          undefined,
+          allowUnsupportedClaims
+        );
         const filePath = await save({
           defaultPath: "property-listing.docx",
           filters: [{ name: "Word Document", extensions: ["docx"] }],
@@ -1,5 +1,5 @@
 import { useState, useCallback, useEffect } from "react";
-import type { Photo } from "@/lib/types";
+import type { Photo, PhotoImportFile } from "@/lib/types";
 import * as tauri from "@/lib/tauri";
 
 interface UsePhotosReturn {
@@ -7,6 +7,8 @@ interface UsePhotosReturn {
   isLoading: boolean;
   isImporting: boolean;
   error: string | null;
+  // Per-file results of the most recent import
+  importResults: PhotoImportFile[];
   loadPhotos: () => Promise<void>;
   importPhotos: () => Promise<void>;
   deletePhoto: (id: string) => Promise<void>;
@@ -18,6 +20,7 @@ export function usePhotos(propertyId: string | undefined): UsePhotosReturn {
   const [isLoading, setIsLoading] = useState(false);
   const [isImporting, setIsImporting] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [importResults, setImportResults] = useState<PhotoImportFile[]>([]);
 
   const loadPhotos = useCallback(async () => {
     if (!propertyId) return;
@@ -40,9 +43,10 @@ export function usePhotos(propertyId: string | undefined): UsePhotosReturn {
     setIsImporting(true);
     setError(null);
     try {
-      const newPhotos = await tauri.importPhotos(propertyId);
-      if (newPhotos.length > 0) {
-        setPhotos((prev) => [...prev, ...newPhotos]);
+      const report = await tauri.importPhotos(propertyId);
+      setImportResults(report.files);
+      if (report.imported.length > 0) {
+        setPhotos((prev) => [...prev, ...report.imported]);
       }
     } catch (err: unknown) {
       const message =
@@ -102,6 +106,7 @@ export function usePhotos(propertyId: string | undefined): UsePhotosReturn {
     isLoading,
     isImporting,
     error,
+    importResults,
     loadPhotos,
     importPhotos,
     deletePhoto,
@@ -39,6 +39,10 @@ export function useStreamingGeneration() {
           case "error":
             setError(event.data.message);
             break;
+          case "factChecked":
+            // The listing is saved now; reload so it shows with its claims
+            void loadGenerations(params.propertyId);
+            break;
           case "started":
             // Could use estimatedTokens for progress, but not needed for MVP
             break;
@@ -68,6 +68,7 @@ export const SETTING_KEYS = {
   DEFAULT_LENGTH: "default_length",
 } as const;
 
-export const MAX_PHOTOS = 20;
+// Per-property photo limit, read with getSetting
+export const PHOTO_LIMIT_SETTING = "max_photos_per_property";
 export const MAX_PHOTO_SIZE_MB = 10;
 export const MAX_PHOTO_SIZE_BYTES = MAX_PHOTO_SIZE_MB * 1024 * 1024;
@@ -3,8 +3,48 @@ import type {
   Property,
   CreatePropertyInput,
   Listing,
+  VariantSpec,
+  VariantGroup,
   BrandVoice,
+  BrandVoiceVersion,
+  UpdateBrandVoiceInput,
+  VoiceUpdateSuggestion,
+  PromptPreset,
+  PromptPresetKind,
+  PromptPresetInput,
+  UpdatePromptPresetInput,
+  PromptPreview,
   Photo,
+  UpdatePhotoInput,
+  CaptionDraft,
+  PhotoAnalysisReport,
+  MlsExportOptions,
+  MlsExportReport,
+  SocialCardFormat,
+  SocialCardOptions,
+  SocialBundle,
+  PhotoImportReport,
+  StorageReport,
+  DerivativePreset,
+  DerivativeSpec,
+  PhotoDerivative,
+  StorageCleanup,
+  Agent,
+  CreateAgentInput,
+  PropertySearchQuery,
+  PropertySearchPage,
+  SetStatusInput,
+  PropertyStatusEvent,
+  PriceChange,
+  PriceImprovementChannel,
+  LongFormKind,
+  LanguageCode,
+  LongFormDocument,
+  TrashItem,
+  TrashKind,
+  BackupManifest,
+  BackupFileInfo,
+  PropertyBundle,
 } from "./types";
 
 // Property commands
@@ -17,19 +57,53 @@ export const getProperty = (id: string) =>
 export const listProperties = () =>
   invoke<Property[]>("list_properties");
 
+export const searchProperties = (query: PropertySearchQuery) =>
+  invoke<PropertySearchPage>("search_properties", { query });
+
 export const updateProperty = (id: string, input: CreatePropertyInput) =>
   invoke<Property>("update_property", { id, input });
 
 export const deleteProperty = (id: string) =>
   invoke<void>("delete_property", { id });
 
+export const setPropertyStatus = (id: string, input: SetStatusInput) =>
+  invoke<Property>("set_property_status", { id, input });
+
+export const listPropertyStatusHistory = (propertyId: string) =>
+  invoke<PropertyStatusEvent[]>("list_property_status_history", { propertyId });
+
+export const listPriceHistory = (propertyId: string) =>
+  invoke<PriceChange[]>("list_price_history", { propertyId });
+
+// Agent profile commands
+export const createAgent = (input: CreateAgentInput) =>
+  invoke<Agent>("create_agent", { input });
+
+export const getAgent = (id: string) =>
+  invoke<Agent>("get_agent", { id });
+
+export const listAgents = () =>
+  invoke<Agent[]>("list_agents");
+
+export const updateAgent = (id: string, input: CreateAgentInput) =>
+  invoke<Agent>("update_agent", { id, input });
+
+export const deleteAgent = (id: string) =>
+  invoke<void>("delete_agent", { id });
+
 // Listing commands
 export const listListings = (propertyId: string) =>
   invoke<Listing[]>("list_listings", { propertyId });
 
+export const listVariantGroup = (groupId: string) =>
+  invoke<Listing[]>("list_variant_group", { groupId });
+
 export const toggleListingFavorite = (id: string) =>
   invoke<void>("toggle_listing_favorite", { id });
 
+export const updateListing = (id: string, content: string) =>
+  invoke<Listing>("update_listing", { id, content });
+
 export const deleteListing = (id: string) =>
   invoke<void>("delete_listing", { id });
 
@@ -37,31 +111,101 @@ export const deleteListing = (id: string) =>
 export const generateListing = (
   args: {
     propertyId: string;
-    style: string;
-    tone: string;
-    length: string;
+    // Omitted: the assigned agent's default, then the built-in default
+    style?: string | null;
+    tone?: string | null;
+    length?: string | null;
     seoKeywords: string[];
     brandVoiceId: string | null;
+    language?: LanguageCode | null;
   },
   onEvent: unknown
 ) =>
   invoke<void>("generate_listing", { args, onEvent });
 
+// 2-4 variants from one property analysis; onEvent is a Channel<VariantEvent>
+export const generateListingVariants = (
+  args: {
+    propertyId: string;
+    seoKeywords: string[];
+    language?: LanguageCode | null;
+    variants: VariantSpec[];
+  },
+  onEvent: unknown
+) =>
+  invoke<VariantGroup>("generate_listing_variants", { args, onEvent });
+
 export const generateSocial = (
-  args: { propertyId: string; platform: string; brandVoiceId: string | null },
+  args: {
+    propertyId: string;
+    platform: string;
+    brandVoiceId: string | null;
+    language?: LanguageCode | null;
+  },
   onEvent: unknown
 ) =>
   invoke<void>("generate_social", { args, onEvent });
 
 export const generateEmail = (
-  args: { propertyId: string; templateType: string; brandVoiceId: string | null },
+  args: {
+    propertyId: string;
+    templateType: string;
+    brandVoiceId: string | null;
+    language?: LanguageCode | null;
+  },
   onEvent: unknown
 ) =>
   invoke<void>("generate_email", { args, onEvent });
 
+export const generatePriceImprovement = (
+  args: {
+    propertyId: string;
+    channel: PriceImprovementChannel;
+    brandVoiceId: string | null;
+    language?: LanguageCode | null;
+  },
+  onEvent: unknown
+) =>
+  invoke<void>("generate_price_improvement", { args, onEvent });
+
+export const generateLongForm = (
+  args: {
+    propertyId: string;
+    kind: LongFormKind;
+    brandVoiceId: string | null;
+    charLimit: number | null;
+    language?: LanguageCode | null;
+  },
+  onEvent: unknown
+) => invoke<void>("generate_long_form", { args, onEvent });
+
+export const getLongFormDocument = (listingId: string) =>
+  invoke<LongFormDocument>("get_long_form_document", { listingId });
+
+// One linked listing per language; onEvent is a Channel<TranslationEvent>
+export const translateListing = (
+  args: { listingId: string; languages: LanguageCode[] },
+  onEvent: unknown
+) => invoke<Listing[]>("translate_listing", { args, onEvent });
+
+export const listListingTranslations = (listingId: string) =>
+  invoke<Listing[]>("list_listing_translations", { listingId });
+
+// Re-checks the copy against the property as it is now
+export const checkListingFacts = (listingId: string) =>
+  invoke<Listing>("check_listing_facts", { listingId });
+
 // Photo commands
 export const importPhotos = (propertyId: string) =>
-  invoke<Photo[]>("import_photos", { propertyId });
+  invoke<PhotoImportReport>("import_photos", { propertyId });
+
+// Files or folders (searched recursively); onEvent is a Channel<PhotoImportEvent>
+export const importPhotoPaths = (
+  propertyId: string,
+  paths: string[],
+  onEvent: unknown
+) =>
+  invoke<PhotoImportReport>("import_photo_paths", { propertyId, paths, onEvent });
 
 export const listPhotos = (propertyId: string) =>
   invoke<Photo[]>("list_photos", { propertyId });
@@ -72,6 +216,69 @@ export const deletePhoto = (id: string) =>
 export const reorderPhotos = (propertyId: string, photoIds: string[]) =>
   invoke<void>("reorder_photos", { propertyId, photoIds });
 
+export const updatePhotoDetails = (id: string, input: UpdatePhotoInput) =>
+  invoke<Photo>("update_photo_details", { id, input });
+
+// Drafts only; save the ones the agent keeps with updatePhotoDetails
+export const generatePhotoCaptions = (propertyId: string, photoIds?: string[]) =>
+  invoke<CaptionDraft[]>("generate_photo_captions", { propertyId, photoIds });
+
+export const analyzePhotos = (propertyId: string, photoIds?: string[]) =>
+  invoke<PhotoAnalysisReport>("analyze_photos", { propertyId, photoIds });
+
+export const acceptSuggestedFeatures = (propertyId: string, features: string[]) =>
+  invoke<Property>("accept_suggested_features", { propertyId, features });
+
+export const autoArrangePhotos = (propertyId: string) =>
+  invoke<Photo[]>("auto_arrange_photos", { propertyId });
+
+// destPath is a folder, or a zip archive when it ends in .zip
+export const exportMlsPhotos = (
+  propertyId: string,
+  destPath: string,
+  photoIds?: string[],
+  options?: MlsExportOptions,
+) =>
+  invoke<MlsExportReport>("export_mls_photos", { propertyId, destPath, photoIds, options });
+
+export const getPhotoDerivatives = (
+  photoIds: string[],
+  size: { preset: DerivativePreset } | { spec: DerivativeSpec }
+) => invoke<PhotoDerivative[]>("get_photo_derivatives", { photoIds, ...size });
+
+export const getPhotoStorageReport = () =>
+  invoke<StorageReport>("get_photo_storage_report");
+
+export const cleanUpPhotoStorage = () =>
+  invoke<StorageCleanup>("clean_up_photo_storage");
+
+// Trash commands
+export const listTrash = () => invoke<TrashItem[]>("list_trash");
+
+export const restoreFromTrash = (kind: TrashKind, id: string) =>
+  invoke<void>("restore_from_trash", { kind, id });
+
+export const deleteFromTrash = (kind: TrashKind, id: string) =>
+  invoke<void>("delete_from_trash", { kind, id });
+
+export const emptyTrash = () => invoke<void>("empty_trash");
+
+// Backup commands
+export const createBackup = (destPath: string) =>
+  invoke<BackupManifest>("create_backup", { destPath });
+
+// Restarts the app once the backup is staged
+export const restoreBackup = (archivePath: string) =>
+  invoke<void>("restore_backup", { archivePath });
+
+export const listBackups = () => invoke<BackupFileInfo[]>("list_backups");
+
+export const exportPropertyBundle = (propertyId: string, destPath: string) =>
+  invoke<PropertyBundle>("export_property_bundle", { propertyId, destPath });
+
+export const importPropertyBundle = (archivePath: string) =>
+  invoke<Property>("import_property_bundle", { archivePath });
+
 // Brand voice commands
 export const createBrandVoice = (
   name: string,
@@ -83,9 +290,68 @@ export const createBrandVoice = (
 export const listBrandVoices = () =>
   invoke<BrandVoice[]>("list_brand_voices");
 
+export const updateBrandVoice = (id: string, input: UpdateBrandVoiceInput) =>
+  invoke<BrandVoice>("update_brand_voice", { id, input });
+
+// Adds the samples and re-extracts the style merged with the current profile
+export const retrainBrandVoice = (id: string, sampleListings: string[]) =>
+  invoke<BrandVoice>("retrain_brand_voice", { id, sampleListings });
+
+export const listBrandVoiceVersions = (id: string) =>
+  invoke<BrandVoiceVersion[]>("list_brand_voice_versions", { id });
+
+export const restoreBrandVoiceVersion = (id: string, version: number) =>
+  invoke<BrandVoice>("restore_brand_voice_version", { id, version });
+
+// Favorited and edited listings, optionally only those written with one voice
+export const listVoiceFeedbackListings = (brandVoiceId?: string) =>
+  invoke<Listing[]>("list_voice_feedback_listings", { brandVoiceId });
+
+// Creates a voice named `name`, or retrains `brandVoiceId` on the listings
+export const learnBrandVoice = (
+  listingIds: string[],
+  target: { brandVoiceId: string } | { name: string }
+) => invoke<BrandVoice>("learn_brand_voice", { listingIds, ...target });
+
+// null until enough new favorited or edited listings have accumulated
+export const suggestBrandVoiceUpdate = (id: string) =>
+  invoke<VoiceUpdateSuggestion | null>("suggest_brand_voice_update", { id });
+
+export const applyBrandVoiceUpdate = (suggestion: VoiceUpdateSuggestion) =>
+  invoke<BrandVoice>("apply_brand_voice_update", {
+    id: suggestion.brandVoiceId,
+    listingIds: suggestion.listingIds,
+    proposedStyle: suggestion.proposedStyle,
+  });
+
+// Defaults to the voice the listing was generated with; useJudge adds an
+// LLM rating to the lexical one
+export const scoreListingVoice = (
+  listingId: string,
+  useJudge: boolean,
+  brandVoiceId?: string
+) => invoke<Listing>("score_listing_voice", { listingId, brandVoiceId, useJudge });
+
 export const deleteBrandVoice = (id: string) =>
   invoke<void>("delete_brand_voice", { id });
 
+// Prompt preset commands
+export const createPromptPreset = (input: PromptPresetInput) =>
+  invoke<PromptPreset>("create_prompt_preset", { input });
+
+export const listPromptPresets = (kind?: PromptPresetKind) =>
+  invoke<PromptPreset[]>("list_prompt_presets", { kind });
+
+export const updatePromptPreset = (id: string, input: UpdatePromptPresetInput) =>
+  invoke<PromptPreset>("update_prompt_preset", { id, input });
+
+export const deletePromptPreset = (id: string) =>
+  invoke<void>("delete_prompt_preset", { id });
+
+// Works for unsaved presets too, so the editor can preview as the agent types
+export const previewPromptPreset = (propertyId: string, input: PromptPresetInput) =>
+  invoke<PromptPreview>("preview_prompt_preset", { propertyId, input });
+
 // Settings commands
 export const getSetting = (key: string) =>
   invoke<string>("get_setting", { key });
@@ -94,14 +360,75 @@ export const setSetting = (key: string, value: string) =>
   invoke<void>("set_setting", { key, value });
 
 // Export commands
-export const exportPdf = (propertyId: string, listingIds: string[]) =>
-  invoke<number[]>("export_pdf", { propertyId, listingIds });
+// With a secondary language, each listing is followed by its translation.
+// Copy with claims the property record doesn't support, or in a language the
+// fact check can't read, is refused unless allowUnsupportedClaims is set;
+// checkExportClaims lists those claims first.
+export const checkExportClaims = (
+  propertyId: string,
+  listingIds: string[],
+  secondaryLanguage?: LanguageCode
+) =>
+  invoke<string[]>("check_export_claims", {
+    propertyId,
+    listingIds,
+    secondaryLanguage,
+  });
+
+export const exportPdf = (
+  propertyId: string,
+  listingIds: string[],
+  secondaryLanguage?: LanguageCode,
+  allowUnsupportedClaims?: boolean
+) =>
+  invoke<number[]>("export_pdf", {
+    propertyId,
+    listingIds,
+    secondaryLanguage,
+    allowUnsupportedClaims,
+  });
+
+export const exportDocx = (
+  propertyId: string,
+  listingIds: string[],
+  secondaryLanguage?: LanguageCode,
+  allowUnsupportedClaims?: boolean
+) =>
+  invoke<number[]>("export_docx", {
+    propertyId,
+    listingIds,
+    secondaryLanguage,
+    allowUnsupportedClaims,
+  });
 
-export const exportDocx = (propertyId: string, listingIds: string[]) =>
-  invoke<number[]>("export_docx", { propertyId, listingIds });
+// PNG bytes
+export const renderSocialCard = (
+  propertyId: string,
+  format: SocialCardFormat,
+  options?: SocialCardOptions
+) => invoke<number[]>("render_social_card", { propertyId, format, options });
+
+// Cards in every format plus caption.txt; destPath is a folder, or a zip
+// archive when it ends in .zip. The caption defaults to the latest social post
+// and is fact checked like a PDF or DOCX export.
+export const exportSocialBundle = (
+  propertyId: string,
+  destPath: string,
+  listingId?: string,
+  options?: SocialCardOptions,
+  allowUnsupportedClaims?: boolean
+) =>
+  invoke<SocialBundle>("export_social_bundle", {
+    propertyId,
+    listingId,
+    destPath,
+    options,
+    allowUnsupportedClaims,
+  });
 
-export const copyToClipboard = (text: string) =>
-  invoke<void>("copy_to_clipboard", { text });
+// Copies a listing's text, refusing unsupported claims like an export
+export const copyToClipboard = (listingId: string, allowUnsupportedClaims?: boolean) =>
+  invoke<void>("copy_to_clipboard", { listingId, allowUnsupportedClaims });
 
 // License commands
 export interface LicenseStatus {
@@ -18,10 +18,43 @@ export interface Property {
   schoolDistrict: string | null;
   nearbyAmenities: string[];
   agentNotes: string | null;
+  agentId: string | null;
+  status: PropertyStatus;
+  listedAt: string | null;
+  pendingAt: string | null;
+  soldAt: string | null;
+  soldPrice: number | null; // in cents
+  offMarketAt: string | null;
+  daysOnMarket: number | null;
   createdAt: string;
   updatedAt: string;
 }
 
+export type PropertyStatus =
+  | "draft"
+  | "coming_soon"
+  | "active"
+  | "pending"
+  | "sold"
+  | "withdrawn"
+  | "expired";
+
+export interface SetStatusInput {
+  status: PropertyStatus;
+  soldPrice?: number | null; // in cents
+  effectiveDate?: string | null; // YYYY-MM-DD
+  note?: string | null;
+}
+
+export interface PropertyStatusEvent {
+  id: string;
+  propertyId: string;
+  status: PropertyStatus;
+  price: number; // in cents
+  note: string | null;
+  changedAt: string;
+}
+
 export type PropertyType =
   | "single_family"
   | "condo"
@@ -38,23 +71,237 @@ export interface Photo {
   thumbnailPath: string;
   sortOrder: number;
   caption: string | null;
+  altText: string | null;
+  room: PhotoRoom | null;
+  detectedFeatures: string[];
+  qualityScore: number | null; // 1-10, from vision analysis
+  analyzedAt: string | null;
+  heroScore: number | null;
   createdAt: string;
 }
 
+export type PhotoRoom =
+  | "exterior_front"
+  | "living_room"
+  | "family_room"
+  | "kitchen"
+  | "dining_room"
+  | "primary_bedroom"
+  | "bedroom"
+  | "primary_bathroom"
+  | "bathroom"
+  | "office"
+  | "laundry"
+  | "garage"
+  | "exterior_back"
+  | "pool"
+  | "view"
+  | "community"
+  | "floor_plan"
+  | "other";
+
+export interface PhotoAnalysis {
+  photoId: string;
+  room: PhotoRoom;
+  features: string[];
+  qualityScore: number;
+  qualityNotes: string;
+}
+
+export interface SkippedPhoto {
+  photoId: string;
+  error: string;
+}
+
+export interface PhotoAnalysisReport {
+  photos: PhotoAnalysis[];
+  suggestedFeatures: string[];
+  // Photos whose files couldn't be read
+  skipped: SkippedPhoto[];
+  tokensUsed: number;
+  costCents: number;
+}
+
+export type PhotoImportStatus = "imported" | "duplicate" | "over_limit" | "failed";
+
+export interface PhotoImportFile {
+  path: string;
+  status: PhotoImportStatus;
+  photoId: string | null;
+  error: string | null;
+}
+
+export interface PhotoImportReport {
+  imported: Photo[];
+  files: PhotoImportFile[];
+}
+
+export type PhotoImportEvent =
+  | { event: "started"; data: { total: number } }
+  | { event: "file"; data: { index: number; total: number; file: PhotoImportFile } }
+  | { event: "finished"; data: { imported: number } };
+
+// grid 3:2, featured (PDF), square 1:1, landscape 1.91:1, portrait 4:5
+export type DerivativePreset = "grid" | "featured" | "square" | "landscape" | "portrait";
+
+export interface DerivativeSpec {
+  width: number;
+  height: number;
+  // cover crops to fill; contain fits inside without upscaling
+  fit: "cover" | "contain";
+  quality: number;
+}
+
+export interface PhotoDerivative {
+  photoId: string;
+  path: string;
+  width: number;
+  height: number;
+}
+
+export interface PropertyStorage {
+  propertyId: string;
+  address: string | null;
+  photoCount: number;
+  originalBytes: number;
+  thumbnailBytes: number;
+}
+
+export interface StorageReport {
+  properties: PropertyStorage[];
+  totalOriginalBytes: number;
+  totalThumbnailBytes: number;
+  orphanedFiles: { path: string; bytes: number }[];
+  orphanedBytes: number;
+  // original=false means only the thumbnail is missing
+  missingFiles: { photoId: string; propertyId: string; path: string; original: boolean }[];
+}
+
+export interface StorageCleanup {
+  filesRemoved: number;
+  bytesFreed: number;
+  thumbnailsRebuilt: number;
+  photosRemoved: number;
+}
+
+// Omitted fields use the backend defaults (2048px, quality 85, no limit)
+export interface MlsExportOptions {
+  maxDimension?: number;
+  maxFileBytes?: number | null;
+  quality?: number;
+  watermarkPath?: string | null;
+  overlayText?: string | null;
+}
+
+export interface ExportedPhoto {
+  photoId: string;
+  filename: string;
+  width: number;
+  height: number;
+  bytes: number;
+  overLimit: boolean;
+}
+
+export interface MlsExportReport {
+  destination: string;
+  photos: ExportedPhoto[];
+  // Photos that couldn't be read or encoded
+  skipped: SkippedPhoto[];
+}
+
+export type SocialCardFormat = "instagram_square" | "instagram_portrait" | "facebook";
+
+export interface SocialCardOptions {
+  photoId?: string | null;
+  banner?: string | null;
+  accentColor?: string | null;
+  includeAgent?: boolean;
+  logoPath?: string | null;
+}
+
+export interface SocialBundle {
+  destination: string;
+  files: string[];
+}
+
+// Omitted fields are left unchanged; empty strings clear them
+export interface UpdatePhotoInput {
+  caption?: string;
+  altText?: string;
+  room?: PhotoRoom | "";
+}
+
+export interface CaptionDraft {
+  photoId: string;
+  caption: string;
+  altText: string;
+}
+
 export interface Listing {
   id: string;
   propertyId: string;
   content: string;
   generationType: GenerationType;
-  style: ListingStyle | null;
-  tone: ListingTone | null;
-  length: ListingLength | null;
+  // Built-in values or custom prompt preset keys
+  style: string | null;
+  tone: string | null;
+  length: string | null;
   seoKeywords: string[];
   brandVoiceId: string | null;
   tokensUsed: number;
   generationCostCents: number;
   isFavorite: boolean;
   createdAt: string;
+  editedAt: string | null;
+  voiceScore: number | null; // 0-100
+  voiceScoreDetails: string | null; // JSON VoiceScore
+  variantGroupId: string | null;
+  promptPresetId: string | null; // custom preset it was written with
+  language: LanguageCode;
+  sourceListingId: string | null; // the original, for translations
+  unsupportedClaims: number | null; // null until fact checked
+  factCheckDetails: string | null; // JSON FactCheck
+}
+
+export type ClaimKind =
+  | "bedrooms"
+  | "bathrooms"
+  | "square_feet"
+  | "lot_size"
+  | "price"
+  | "parking"
+  | "year_built"
+  | "year_renovated"
+  | "property_type"
+  | "feature";
+
+export interface Claim {
+  kind: ClaimKind;
+  text: string;
+  // UTF-16 offsets into the listing content, usable with String.slice
+  start: number;
+  end: number;
+  supported: boolean;
+  expected: string | null; // what the property record says instead
+  fromAnalysis: boolean;
+}
+
+export interface FactCheck {
+  unsupported: number;
+  claims: Claim[];
+}
+
+export type LanguageCode = "en" | "es" | "zh" | "vi";
+
+export interface VoiceScore {
+  brandVoiceId: string;
+  score: number;
+  lexicalScore: number;
+  judgeScore: number | null;
+  judgeNotes: string | null;
+  vocabularyUsed: string[];
+  signaturePhrasesUsed: string[];
+  avoidedWordsFound: string[];
 }
 
 export type GenerationType =
@@ -64,7 +311,63 @@ export type GenerationType =
   | "social_linkedin"
   | "email_buyer"
   | "email_seller"
-  | "email_open_house";
+  | "email_open_house"
+  | "email_price_improvement"
+  | "social_price_improvement"
+  | "social_custom"
+  | "email_custom"
+  | LongFormKind;
+
+export type LongFormKind =
+  | "video_script"
+  | "website_copy"
+  | "feature_sheet"
+  | "neighborhood_guide"
+  | "print_ad";
+
+export interface LongFormScene {
+  location: string;
+  durationSeconds: number | null;
+  shots: string[];
+  voiceover: string;
+}
+
+export interface LongFormSection {
+  heading: string;
+  paragraphs: string[];
+  bullets: string[];
+}
+
+export type LongFormDocument =
+  | {
+      kind: "VideoScript";
+      title: string;
+      scenes: LongFormScene[];
+      totalSeconds: number;
+    }
+  | {
+      kind: "WebsiteCopy";
+      headline: string;
+      tagline: string;
+      sections: LongFormSection[];
+    }
+  | { kind: "FeatureSheet"; title: string; rooms: LongFormSection[] }
+  | { kind: "NeighborhoodGuide"; title: string; sections: LongFormSection[] }
+  | { kind: "PrintAd"; headline: string; body: string; characters: number };
+
+export type PriceImprovementChannel =
+  | "email"
+  | "instagram"
+  | "facebook"
+  | "linkedin";
+
+export interface PriceChange {
+  id: string;
+  propertyId: string;
+  oldPrice: number; // in cents
+  newPrice: number; // in cents
+  changedAt: string;
+}
 
 export type ListingStyle = "luxury" | "family" | "investment" | "first_time";
 export type ListingTone = "professional" | "warm" | "exciting";
@@ -81,6 +384,39 @@ export interface BrandVoice {
   updatedAt: string;
 }
 
+export interface BrandVoiceVersion {
+  id: string;
+  brandVoiceId: string;
+  version: number;
+  extractedStyle: string; // JSON string
+  sampleCount: number;
+  changeSource: "extracted" | "edited" | "retrained" | "restored";
+  createdAt: string;
+}
+
+export interface StyleChange {
+  field: string;
+  before: unknown | null; // null when the field is new
+  after: unknown | null; // null when the field was dropped
+  added: string[];
+  removed: string[];
+}
+
+export interface VoiceUpdateSuggestion {
+  brandVoiceId: string;
+  listingIds: string[];
+  proposedStyle: string; // JSON string
+  changes: StyleChange[];
+}
+
+// Omitted fields are left unchanged; style keys use the stored snake_case
+// names and a null value clears the field
+export interface UpdateBrandVoiceInput {
+  name?: string;
+  description?: string;
+  style?: Record<string, unknown>;
+}
+
 export interface ExtractedStyle {
   tone: string;
   vocabulary: string[];
@@ -91,28 +427,59 @@ export interface ExtractedStyle {
   formatting: string;
 }
 
+// style, tone, length, platform and templateType also accept custom
+// prompt preset keys
 export interface GenerationOptions {
-  style: ListingStyle;
-  tone: ListingTone;
-  length: ListingLength;
+  style: ListingStyle | string;
+  tone: ListingTone | string;
+  length: ListingLength | string;
   seoKeywords: string[];
   brandVoiceId: string | null;
 }
 
 export interface SocialGenerationOptions {
-  platform: SocialPlatform;
+  platform: SocialPlatform | string;
   brandVoiceId: string | null;
 }
 
 export type SocialPlatform = "instagram" | "facebook" | "linkedin";
 
 export interface EmailGenerationOptions {
-  templateType: EmailTemplate;
+  templateType: EmailTemplate | string;
   brandVoiceId: string | null;
 }
 
 export type EmailTemplate = "buyer" | "seller" | "open_house";
 
+export type PromptPresetKind = "style" | "tone" | "length" | "platform" | "email_template";
+
+export interface PromptPreset {
+  id: string;
+  kind: PromptPresetKind;
+  key: string; // used in place of a built-in value; fixed at creation
+  name: string;
+  instructions: string; // may use {{variables}}, e.g. {{city}}, {{agent_name}}
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface PromptPresetInput {
+  kind: PromptPresetKind;
+  name: string;
+  instructions: string;
+}
+
+export interface UpdatePromptPresetInput {
+  name: string;
+  instructions: string;
+}
+
+export interface PromptPreview {
+  instructions: string; // with the property's variables filled in
+  system: string;
+  user: string;
+}
+
 export type StreamEvent =
   | { event: "started"; data: { estimatedTokens: number } }
   | { event: "delta"; data: { text: string } }
@@ -125,7 +492,38 @@ export type StreamEvent =
         costCents: number;
       };
     }
-  | { event: "error"; data: { message: string } };
+  | { event: "error"; data: { message: string } }
+  // After the copy is saved, with its claims checked against the property
+  | { event: "factChecked"; data: { listingId: string; factCheck: FactCheck } };
+
+// One style/tone/length/voice combination; a null voice uses the agent's default
+export interface VariantSpec {
+  // Omitted: the assigned agent's default, then the built-in default
+  style?: ListingStyle | string | null;
+  tone?: ListingTone | string | null;
+  length?: ListingLength | string | null;
+  brandVoiceId: string | null;
+}
+
+// `variant` is the index into the requested variants
+export type VariantEvent =
+  | { event: "analyzing"; data: { variants: number } }
+  | { event: "stream"; data: { variant: number; event: StreamEvent } }
+  | { event: "saved"; data: { variant: number; listingId: string } }
+  | { event: "failed"; data: { variant: number; message: string } }
+  | { event: "finished"; data: { groupId: string } };
+
+// Per-language progress of translateListing
+export type TranslationEvent =
+  | { event: "started"; data: { languages: LanguageCode[] } }
+  | { event: "saved"; data: { language: LanguageCode; listingId: string } }
+  | { event: "failed"; data: { language: LanguageCode; message: string } }
+  | { event: "finished" };
+
+export interface VariantGroup {
+  groupId: string;
+  listings: Listing[];
+}
 
 export interface AgentInfo {
   name: string;
@@ -134,6 +532,35 @@ export interface AgentInfo {
   brokerageName: string;
 }
 
+export interface Agent {
+  id: string;
+  name: string;
+  phone: string;
+  email: string;
+  brokerageName: string;
+  licenseNumber: string | null;
+  signature: string | null;
+  defaultBrandVoiceId: string | null;
+  defaultStyle: ListingStyle | null;
+  defaultTone: ListingTone | null;
+  defaultLength: ListingLength | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface CreateAgentInput {
+  name: string;
+  phone: string;
+  email: string;
+  brokerageName: string;
+  licenseNumber: string | null;
+  signature: string | null;
+  defaultBrandVoiceId: string | null;
+  defaultStyle: ListingStyle | null;
+  defaultTone: ListingTone | null;
+  defaultLength: ListingLength | null;
+}
+
 export interface CreatePropertyInput {
   address: string;
   city: string;
@@ -153,4 +580,134 @@ export interface CreatePropertyInput {
   schoolDistrict: string | null;
   nearbyAmenities: string[];
   agentNotes: string | null;
+  agentId?: string | null;
+  // Starting status on create; active when omitted. Edits use setPropertyStatus.
+  status?: Extract<PropertyStatus, "draft" | "coming_soon" | "active">;
+}
+
+export type PropertySort =
+  | "newest"
+  | "oldest"
+  | "price_high"
+  | "price_low"
+  | "address"
+  | "relevance";
+
+export interface PropertySearchQuery {
+  text?: string | null;
+  minPrice?: number | null;
+  maxPrice?: number | null;
+  minBeds?: number | null;
+  maxBeds?: number | null;
+  minBaths?: number | null;
+  maxBaths?: number | null;
+  propertyTypes?: PropertyType[];
+  statuses?: PropertyStatus[];
+  city?: string | null;
+  hasFavoriteListing?: boolean | null;
+  sort?: PropertySort;
+  cursor?: string | null;
+  limit?: number;
+}
+
+export interface PropertySearchPage {
+  items: Property[];
+  total: number;
+  nextCursor: string | null;
+}
+
+export type TrashKind = "property" | "listing" | "photo";
+
+export interface TrashItem {
+  kind: TrashKind;
+  id: string;
+  propertyId: string;
+  propertyAddress: string;
+  detail: string | null; // generation type or photo filename
+  deletedAt: string;
+  purgeAt: string;
+}
+
+export interface BackupManifest {
+  format: string;
+  formatVersion: number;
+  appVersion: string;
+  createdAt: string;
+  migrationVersion: number;
+  photosDir: string;
+  propertyCount: number;
+  listingCount: number;
+  photoCount: number;
+  fileCount: number;
+}
+
+export interface BackupFileInfo {
+  path: string;
+  fileName: string;
+  sizeBytes: number;
+  modifiedAt: string;
+}
+
+// Single-property bundle for handing a listing to another install. Ids are
+// the exporting install's; importing creates new ones.
+export interface BundleProperty
+  extends Omit<CreatePropertyInput, "agentId" | "status"> {
+  id: string;
+  status: PropertyStatus;
+  listedAt: string | null;
+  pendingAt: string | null;
+  soldAt: string | null;
+  soldPrice: number | null;
+  offMarketAt: string | null;
+}
+
+export interface BundleListing {
+  id: string;
+  content: string;
+  generationType: GenerationType;
+  style: string | null;
+  tone: string | null;
+  length: string | null;
+  seoKeywords: string[];
+  brandVoiceId: string | null;
+  tokensUsed: number;
+  generationCostCents: number;
+  isFavorite: boolean;
+  createdAt: string;
+  language: LanguageCode;
+  sourceListingId: string | null;
+  variantGroupId: string | null;
+  promptPresetId: string | null;
+  voiceScore: number | null;
+  voiceScoreDetails: string | null;
+  unsupportedClaims: number | null;
+  factCheckDetails: string | null;
+}
+
+export interface BundlePhoto {
+  id: string;
+  filename: string;
+  sortOrder: number;
+  caption: string | null;
+  altText: string | null;
+  room: string | null;
+  contentHash: string | null;
+  detectedFeatures: string[];
+  qualityScore: number | null;
+  analyzedAt: string | null;
+  heroScore: number | null;
+  originalEntry: string;
+  thumbnailEntry: string;
+}
+
+export interface PropertyBundle {
+  format: string;
+  formatVersion: number;
+  appVersion: string;
+  exportedAt: string;
+  property: BundleProperty;
+  listings: BundleListing[];
+  photos: BundlePhoto[];
+  brandVoices: BrandVoice[];
+  promptPresets: PromptPreset[];
 }
@@ -56,6 +56,7 @@ function propertyToInput(p: Property): CreatePropertyInput {
     schoolDistrict: p.schoolDistrict,
     nearbyAmenities: p.nearbyAmenities,
     agentNotes: p.agentNotes,
+    agentId: p.agentId,
   };
 }
 